@@ -70,6 +70,8 @@ impl TestCube {
             draw_list: DrawList {
                 list: vec![],
                 list_transform: Mat4x4::identity(),
+                clip: None,
+                dimmed: false,
                 commands: vec![DrawCommand {
                     vertex_buffer: cube_vertices,
                     index_buffer: cube_indices,
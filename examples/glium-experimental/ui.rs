@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 use std::f32::consts::FRAC_PI_2;
 use std::rc::Rc;
+use std::time::Instant;
 
 use pengui::backend::glium::*;
 use pengui::core::*;
@@ -24,7 +25,14 @@ pub struct Ui {
     random_color: (f32, f32, f32, f32),
     extrude: f32,
     button_active: bool,
+    checkbox_active: bool,
     texture_button_active: bool,
+    show_editable_text: bool,
+    confirm_clear: bool,
+    last_frame: Instant,
+    frame_times: RingBuffer<(f32, f32)>,
+    planets: Vec<(&'static str, f32, bool)>,
+    selected_planet: Option<usize>,
     pub radius: f32,
     pub editable_text: String,
 }
@@ -35,7 +43,12 @@ impl Ui {
         let ensps_texture =
             Self::register_image(backend, &include_bytes!("../resources/pengui.png")[..]);
 
-        let ui = Interface::new();
+        #[allow(unused_mut)]
+        let mut ui = Interface::new();
+        #[cfg(feature = "system-clipboard")]
+        if let Some(clipboard) = pengui::frontend::glutin::SystemClipboard::new() {
+            ui.set_clipboard(Box::new(clipboard));
+        }
 
         let editable_text = String::from("Editable text. Type here!");
 
@@ -48,7 +61,23 @@ impl Ui {
             random_color: (0.231, 0.294, 0.451, 1.),
             extrude: 0.2,
             button_active: false,
+            checkbox_active: false,
             texture_button_active: false,
+            show_editable_text: true,
+            confirm_clear: false,
+            last_frame: Instant::now(),
+            frame_times: RingBuffer::new(120),
+            planets: vec![
+                ("Mercury", 0.39, false),
+                ("Venus", 0.72, false),
+                ("Earth", 1., true),
+                ("Mars", 1.52, false),
+                ("Jupiter", 5.2, false),
+                ("Saturn", 9.54, false),
+                ("Uranus", 19.2, false),
+                ("Neptune", 30.06, false),
+            ],
+            selected_planet: None,
             radius: 5.,
             editable_text,
         }
@@ -58,38 +87,28 @@ impl Ui {
         self.ui.register_event(event, ray);
     }
 
-    fn input_mul<'a>(
+    fn input_slider<'a>(
         string: &'a str,
         var: &'a mut f32,
+        (min, max): (f32, f32),
         font: &'a Rc<RefCell<dyn FontAtlas>>,
     ) -> InlineBuilder<'a> {
         InlineBuilder::new(move |ui| {
             LabelBuilder::new(string, font).build(loc!(), &ui);
-            if Button::new("◀".to_string(), font).build(loc!(), &ui) {
-                *var /= 1.1;
-            }
-            LabelBuilder::new(format!("{:.2}", var).as_str(), font).build(loc!(), &ui);
-            if Button::new("▶".to_string(), font).build(loc!(), &ui) {
-                *var *= 1.1;
-            }
+            SliderBuilder::new(var, min, max, font)
+                .logarithmic()
+                .build(loc!(), &ui);
         })
     }
 
-    fn input_add<'a>(
-        string: &'a str,
+    fn input_drag<'a>(
         var: &'a mut f32,
         font: &'a Rc<RefCell<dyn FontAtlas>>,
-    ) -> InlineBuilder<'a> {
-        InlineBuilder::new(move |ui| {
-            LabelBuilder::new(string, font).build(loc!(), &ui);
-            if Button::new("◀".to_string(), font).build(loc!(), &ui) {
-                *var -= 1.0;
-            }
-            LabelBuilder::new(format!("{:.2}", var).as_str(), font).build(loc!(), &ui);
-            if Button::new("▶".to_string(), font).build(loc!(), &ui) {
-                *var += 1.0;
-            }
-        })
+    ) -> DragValueBuilder<'a, f32> {
+        DragValueBuilder::new(var, font)
+            .range(5., 100.)
+            .step(1.)
+            .speed(2.)
     }
 
     pub fn ui(&mut self) {
@@ -100,6 +119,9 @@ impl Ui {
 
         WindowBuilder::new(|ui| {
             let frame_number = FrameCounter::new().build(loc!(), &ui);
+            let frame_time = self.last_frame.elapsed().as_secs_f32() * 1000.;
+            self.last_frame = Instant::now();
+            self.frame_times.push((frame_number as f32, frame_time));
             if CenterLayout::new(Button::new("Clickable button".to_string(), &self.font))
                 .build(loc!(), &ui)
             {
@@ -111,10 +133,19 @@ impl Ui {
                     &self.font,
                 )
                 .build(loc!(), &ui);
+                let fraction = (frame_number % 600) as f32 / 600.;
+                InlineBuilder::new(|ui| {
+                    SpinnerBuilder::new().build(loc!(), &ui);
+                    ProgressBarBuilder::new(fraction)
+                        .text(format!("{:.0} %", fraction * 100.), &self.font)
+                        .build(loc!(), &ui);
+                })
+                .build(loc!(), &ui);
             }
             if Button3D::new("3D BUTTON !!!".to_string(), &self.font)
                 .extrude(self.extrude)
                 .color(self.random_color)
+                .tooltip("Picks a random color", &self.font)
                 .build(loc!(), &ui)
             {
                 let r = rand::random::<f32>();
@@ -122,31 +153,123 @@ impl Ui {
                 let b = rand::random::<f32>();
                 self.random_color = (r, g, b, 1.);
             }
+            ColorPickerBuilder::new(&mut self.random_color, &self.font)
+                .compact()
+                .build(loc!(), &ui);
 
-            if CheckBox::new("A checkbox".to_string(), &self.font).build(loc!(), &ui) {
-                if Button::new("        ".to_string(), &self.font)
+            CheckBoxBuilder::new(
+                "A checkbox".to_string(),
+                &mut self.checkbox_active,
+                &self.font,
+            )
+            .build(loc!(), &ui);
+            let font = &self.font;
+            let ensps_texture = self.ensps_texture;
+            let texture_button_active = &mut self.texture_button_active;
+            CollapsingHeaderBuilder::new("Textured button".to_string(), font, |ui| {
+                if Button::new("        ".to_string(), font)
                     .font_size(5.)
                     .color((1., 1., 1., 1.))
-                    .texture(self.ensps_texture)
+                    .texture(ensps_texture)
                     .build(loc!(), &ui)
                 {
-                    self.texture_button_active = !self.texture_button_active;
+                    *texture_button_active = !*texture_button_active;
                 }
-                if self.texture_button_active {
-                    LabelBuilder::new("Hi!", &self.font).build(loc!(), &ui);
+                if *texture_button_active {
+                    LabelBuilder::new("Hi!", font).build(loc!(), &ui);
                 }
-            }
+                ImageBuilder::new(ensps_texture, (6., 3.))
+                    .fit(ImageFit::Cover)
+                    .build(loc!(), &ui);
+            })
+            .build(loc!(), &ui);
+            let frame_times = &self.frame_times;
+            CollapsingHeaderBuilder::new("Frame times (ms)".to_string(), font, |ui| {
+                PlotBuilder::new(font)
+                    .size((20., 8.))
+                    .line(frame_times.iter().copied(), (0.9, 0.6, 0.2, 1.))
+                    .build(loc!(), &ui);
+            })
+            .build(loc!(), &ui);
+            CollapsingHeaderBuilder::new("3D charts".to_string(), font, |ui| {
+                let waves = (0..12)
+                    .map(|row| {
+                        (0..12)
+                            .map(|column| (row as f32 / 2.).sin() * (column as f32 / 3.).cos())
+                            .collect()
+                    })
+                    .collect::<Vec<_>>();
+                SurfacePlotBuilder::new(&waves).build(loc!(), &ui);
+                BarChart3DBuilder::new(&[3., 5., 2., -1., 4.]).build(loc!(), &ui);
+            })
+            .build(loc!(), &ui);
+            let planets = &mut self.planets;
+            let selected_planet = &mut self.selected_planet;
+            CollapsingHeaderBuilder::new("Table".to_string(), font, |ui| {
+                let columns = vec![
+                    Column::new("Planet"),
+                    Column::new("Distance (AU)").align(Align::Right),
+                    Column::new("Visited")
+                        .width(ColumnWidth::Fixed(4.))
+                        .align(Align::Center)
+                        .sortable(false),
+                ];
+                let rows = planets.len();
+                let sort = TableBuilder::new(columns, rows, font, |row, cells| {
+                    let (name, distance, visited) = &mut planets[row];
+                    LabelBuilder::new(name, font).build(loc!(), &cells[0]);
+                    LabelBuilder::new(&format!("{:.2}", distance), font).build(loc!(), &cells[1]);
+                    CheckBoxBuilder::new(String::new(), visited, font).build(loc!(), &cells[2]);
+                })
+                .size((20., 6.))
+                .selection(&mut *selected_planet)
+                .build(loc!(), &ui);
+                if let Some((column, order)) = sort {
+                    *selected_planet = None;
+                    planets.sort_by(|p1, p2| {
+                        let ordering = match column {
+                            0 => p1.0.cmp(p2.0),
+                            _ => p1.1.partial_cmp(&p2.1).unwrap(),
+                        };
+                        match order {
+                            SortOrder::Ascending => ordering,
+                            SortOrder::Descending => ordering.reverse(),
+                        }
+                    });
+                }
+            })
+            .build(loc!(), &ui);
+            CollapsingHeaderBuilder::new("Virtual list".to_string(), font, |ui| {
+                VirtualListBuilder::new(10_000, 1.2, |index, row| {
+                    LabelBuilder::new(&format!("Item {}", index), font).build(loc!(), row);
+                })
+                .size((20., 6.))
+                .build(loc!(), &ui);
+            })
+            .build(loc!(), &ui);
+            SeparatorBuilder::horizontal().build(loc!(), &ui);
             MaximizeLayout::new(TextBuilder::new(&mut self.editable_text, &self.font).size(0.75))
                 .build(loc!(), &ui);
+            if Button::new("Clear text".to_string(), &self.font).build(loc!(), &ui) {
+                self.confirm_clear = true;
+            }
 
-            Self::input_mul("Extrusion", &mut self.extrude, &self.font).build(loc!(), &ui);
-
-            Self::input_mul("Radius", &mut self.radius, &self.font).build(loc!(), &ui);
-
-            Self::input_add("Window width", &mut self.window_width, &self.font).build(loc!(), &ui);
+            Self::input_slider("Extrusion", &mut self.extrude, (0.05, 5.), &self.font)
+                .build(loc!(), &ui);
 
-            Self::input_add("Window height", &mut self.window_height, &self.font)
+            Self::input_slider("Radius", &mut self.radius, (1., 50.), &self.font)
                 .build(loc!(), &ui);
+
+            let font = &self.font;
+            let (width, height) = (&mut self.window_width, &mut self.window_height);
+            let columns = vec![GridTrack::Auto, GridTrack::Fraction(1.)];
+            GridBuilder::new(columns, |grid| {
+                LabelBuilder::new("Window width", font).build(loc!(), grid);
+                Self::input_drag(width, font).build(loc!(), grid);
+                LabelBuilder::new("Window height", font).build(loc!(), grid);
+                Self::input_drag(height, font).build(loc!(), grid);
+            })
+            .build(loc!(), &ui);
         })
         .size((window_width, window_height))
         .transform(Similarity3::new(
@@ -156,17 +279,54 @@ impl Ui {
         ))
         .build(loc!(), &root);
 
+        let font = &self.font;
+        let editable_text = self.editable_text.as_str();
+        let show_editable_text = &mut self.show_editable_text;
         WindowBuilder::new(|ui| {
+            MenuBarBuilder::new(|ui| {
+                MenuBuilder::new("View".to_string(), font, |ui| {
+                    MenuItemBuilder::new("Editable text".to_string(), font)
+                        .checked(show_editable_text)
+                        .build(loc!(), &ui);
+                })
+                .build(loc!(), &ui);
+            })
+            .build(loc!(), &ui);
             PaddingBuilder::new(
                 (1., 1.),
-                LabelBuilder::new("Many characters supported.\rJapanese: 色は匂へど散\rRussian: Не́которые иностра́нцы ду́мают", &self.font)
+                LabelBuilder::new("Many characters supported.\rJapanese: 色は匂へど散\rRussian: Не́которые иностра́нцы ду́мают", font)
             ).build(loc!(), &ui);
-            LabelBuilder::new(self.editable_text.as_str(), &self.font).build(loc!(), &ui);
+            if *show_editable_text {
+                LabelBuilder::new(editable_text, font).build(loc!(), &ui);
+            }
         })
+        .title("Text".to_string())
+        .title_bar(&self.font)
+        .collapsible()
+        .resizable()
         .size((20., 15.))
         .transform(Similarity3::new(Vector3::new(-20., 0., 0.), Vector3::y() * FRAC_PI_2, 1.))
         .build(loc!(), &self.ui.root);
 
+        if self.confirm_clear {
+            let response = ModalBuilder::confirm("Clear the editable text?", &self.font)
+                .title("Confirmation".to_string())
+                .title_bar(&self.font)
+                .size((16., 5.))
+                .transform(Similarity3::new(
+                    Vector3::new(0., 0., -15.),
+                    Vector3::zeros(),
+                    1.,
+                ))
+                .build(loc!(), &self.ui.modals);
+            if let Some(response) = response {
+                if response == ModalResponse::Ok {
+                    self.editable_text.clear();
+                }
+                self.confirm_clear = false;
+            }
+        }
+
         self.ui.end_frame();
         self.ui.generate_layout();
     }
@@ -112,6 +112,35 @@ impl GliumBackend {
         local_transform: Mat4x4,
         command: &DrawCommand,
     ) -> DrawResult {
+        self.draw_command_in(
+            frame,
+            global_transform,
+            local_transform,
+            command,
+            DrawContext::default(),
+        )
+    }
+
+    /// Draws a single draw command, discarding what is outside of the clipping rectangle
+    /// of the context if any.
+    fn draw_command_in(
+        &self,
+        frame: &mut Frame,
+        global_transform: Mat4x4,
+        local_transform: Mat4x4,
+        command: &DrawCommand,
+        context: DrawContext,
+    ) -> DrawResult {
+        let brightness = if context.dimmed {
+            DIMMED_BRIGHTNESS
+        } else {
+            1.
+        };
+        let (clip_matrix, clip_rect) = match context.clip {
+            Some((matrix, (x, y, width, height))) => (matrix, [x, y, x + width, y + height]),
+            None => (Mat4x4::identity(), [f32::MIN, f32::MIN, f32::MAX, f32::MAX]),
+        };
+
         let vertices = command
             .vertex_buffer
             .iter()
@@ -127,6 +156,7 @@ impl GliumBackend {
             DrawMode::Triangles => glium::index::PrimitiveType::TrianglesList,
             DrawMode::Lines => glium::index::PrimitiveType::LinesList,
             DrawMode::Points => glium::index::PrimitiveType::Points,
+            DrawMode::LineStrip => glium::index::PrimitiveType::LineStrip,
         };
         let index_buffer = glium::IndexBuffer::immutable(
             &self.display,
@@ -142,6 +172,7 @@ impl GliumBackend {
                 DrawMode::Triangles => glium::draw_parameters::PolygonMode::Fill,
                 DrawMode::Lines => glium::draw_parameters::PolygonMode::Line,
                 DrawMode::Points => glium::draw_parameters::PolygonMode::Point,
+                DrawMode::LineStrip => glium::draw_parameters::PolygonMode::Line,
             }
         } else {
             self.debug_poly_mode
@@ -160,6 +191,9 @@ impl GliumBackend {
             let uniforms = glium::uniform! {
                 perspective_view: raw_matrix(&global_transform),
                 model: raw_matrix(&(local_transform * command.uniforms.model_matrix)),
+                clip_matrix: raw_matrix(&clip_matrix),
+                clip_rect: clip_rect,
+                brightness: brightness,
                 t: if self.debug_rendering { &self.blank_texture } else { texture },
             };
 
@@ -174,6 +208,9 @@ impl GliumBackend {
             let uniforms = glium::uniform! {
                 perspective_view: raw_matrix(&global_transform),
                 model: raw_matrix(&(local_transform * command.uniforms.model_matrix)),
+                clip_matrix: raw_matrix(&clip_matrix),
+                clip_rect: clip_rect,
+                brightness: brightness,
                 t: &self.blank_texture,
             };
 
@@ -229,13 +266,44 @@ impl GliumBackend {
         local_transform: Mat4x4,
         list: &DrawList,
     ) -> DrawResult {
+        self.draw_list_in(
+            frame,
+            global_transform,
+            local_transform,
+            list,
+            DrawContext::default(),
+        )
+    }
+
+    /// Draws recursively a list of commands in the given context.
+    /// The clip of a list is intersected with the one of the lists holding it (see `nested_clip`).
+    /// Everything in a dimmed list is dimmed.
+    fn draw_list_in(
+        &self,
+        frame: &mut Frame,
+        global_transform: Mat4x4,
+        local_transform: Mat4x4,
+        list: &DrawList,
+        context: DrawContext,
+    ) -> DrawResult {
+        let context = DrawContext {
+            dimmed: context.dimmed || list.dimmed,
+            ..context
+        };
         list.commands.iter().try_for_each(|command| {
-            self.draw_command(frame, global_transform, local_transform, command)
+            self.draw_command_in(frame, global_transform, local_transform, command, context)
         })?;
         let transform = local_transform * list.list_transform;
-        list.list
-            .iter()
-            .try_for_each(|list| self.draw_list(frame, global_transform, transform, list))
+        let context = DrawContext {
+            clip: match list.clip {
+                Some(rect) => nested_clip(context.clip, transform, rect),
+                None => context.clip,
+            },
+            ..context
+        };
+        list.list.iter().try_for_each(|sublist| {
+            self.draw_list_in(frame, global_transform, transform, sublist, context)
+        })
     }
 
     /// Registers a new texture and returns the unique ID associated with it.
@@ -280,6 +348,63 @@ struct GliumVertex {
 // creation of the vertex structure for `glium` from the penGui one
 glium::implement_vertex!(GliumVertex, position, color, tex_uv);
 
+/// What a list passes down to its sublists and commands while drawing
+#[derive(Copy, Clone, Default)]
+struct DrawContext {
+    /// The clipping rectangle, with the inverse of the transformation of its space
+    clip: Option<(Mat4x4, (f32, f32, f32, f32))>,
+    /// Whether a list holding the commands is dimmed
+    dimmed: bool,
+}
+
+/// Returns the clip of a list whose sublists are clipped to `rect`, in the space of `transform`,
+/// while the list itself is clipped by `outer`.
+///
+/// The two rectangles are intersected in the space of `transform` when it is only translated
+/// or scaled in the plane of `outer`. Otherwise, the intersection is not a rectangle, and
+/// only `rect` applies
+fn nested_clip(
+    outer: Option<(Mat4x4, (f32, f32, f32, f32))>,
+    transform: Mat4x4,
+    rect: (f32, f32, f32, f32),
+) -> Option<(Mat4x4, (f32, f32, f32, f32))> {
+    let inverse = match transform.try_inverse() {
+        Some(inverse) => inverse,
+        None => return outer,
+    };
+    let (outer_inverse, (x, y, width, height)) = match outer {
+        Some(outer) => outer,
+        None => return Some((inverse, rect)),
+    };
+
+    // From the space of `transform` to the one of the outer rectangle
+    let relative = outer_inverse * transform;
+    let aligned = [(0, 1), (0, 2), (1, 0), (1, 2), (3, 0), (3, 1), (3, 2)]
+        .iter()
+        .all(|&index| relative[index].abs() < 1e-6)
+        && relative[(0, 0)].abs() > 1e-6
+        && relative[(1, 1)].abs() > 1e-6;
+    if !aligned {
+        return Some((inverse, rect));
+    }
+    // The bounds of the outer rectangle, brought back in the space of `transform`
+    let bounds = |start: f32, length: f32, scale: f32, offset: f32| {
+        let (a, b) = ((start - offset) / scale, (start + length - offset) / scale);
+        (a.min(b), a.max(b))
+    };
+    let (left, right) = bounds(x, width, relative[(0, 0)], relative[(0, 3)]);
+    let (bottom, top) = bounds(y, height, relative[(1, 1)], relative[(1, 3)]);
+    let (left, bottom) = (left.max(rect.0), bottom.max(rect.1));
+    let (right, top) = (right.min(rect.0 + rect.2), top.min(rect.1 + rect.3));
+    Some((
+        inverse,
+        (left, bottom, (right - left).max(0.), (top - bottom).max(0.)),
+    ))
+}
+
+/// Factor applied to the colors of dimmed lists
+const DIMMED_BRIGHTNESS: f32 = 0.4;
+
 /// GLSL vertex shader source
 static VERTEX_SHADER_SRC: &str = r#"
 #version 330
@@ -290,14 +415,18 @@ in vec2 tex_uv;
 
 out vec4 pipe_color;
 out vec2 pipe_tex_uv;
+out vec2 pipe_clip_position;
 
 uniform mat4 perspective_view;
 uniform mat4 model;
+uniform mat4 clip_matrix;
 
 void main() {
-	gl_Position = perspective_view * model * vec4(position, 1.0);
+	vec4 model_position = model * vec4(position, 1.0);
+	gl_Position = perspective_view * model_position;
 	pipe_color = color;
 	pipe_tex_uv = tex_uv;
+	pipe_clip_position = (clip_matrix * model_position).xy;
 }
 "#;
 
@@ -307,13 +436,19 @@ static FRAGMENT_SHADER_SRC: &str = r#"
 
 in vec4 pipe_color;
 in vec2 pipe_tex_uv;
+in vec2 pipe_clip_position;
 
 out vec4 out_color;
 
 uniform sampler2D t;
+uniform vec4 clip_rect;
+uniform float brightness;
 
 void main() {
-	out_color = vec4(pipe_color.xyz, 1.0) * texture(t, pipe_tex_uv);
+	if (any(lessThan(pipe_clip_position, clip_rect.xy)) || any(greaterThan(pipe_clip_position, clip_rect.zw))) {
+		discard;
+	}
+	out_color = vec4(pipe_color.xyz * brightness, 1.0) * texture(t, pipe_tex_uv);
 }
 "#;
 
@@ -334,4 +469,24 @@ mod tests {
         ];
         assert_eq!(raw_matrix(&matrix), array);
     }
+
+    #[test]
+    fn nested_clips() {
+        let outer = Some((Mat4x4::identity(), (0., 0., 10., 10.)));
+
+        // A clip in a space moved by (5, 5) only keeps the part inside both rectangles
+        let transform = Mat4x4::new_translation(&nalgebra::Vector3::new(5., 5., 0.));
+        let (_, rect) = nested_clip(outer, transform, (-2., 0., 10., 10.)).unwrap();
+        assert_eq!(rect, (-2., 0., 7., 5.));
+
+        // Scaled, and not overlapping at all
+        let transform = Mat4x4::new_scaling(2.) * transform;
+        let (_, rect) = nested_clip(outer, transform, (10., 0., 2., 2.)).unwrap();
+        assert_eq!(rect, (10., 0., 0., 0.));
+
+        // Rotated in the plane of the outer rectangle: the inner one applies
+        let transform = Mat4x4::new_rotation(nalgebra::Vector3::z());
+        let (_, rect) = nested_clip(outer, transform, (-2., 0., 10., 10.)).unwrap();
+        assert_eq!(rect, (-2., 0., 10., 10.));
+    }
 }
@@ -0,0 +1,49 @@
+/// A trait describing a clipboard that widgets can copy text to and paste text from.
+///
+/// The interface uses a `MemoryClipboard` by default, which does not communicate
+/// with the rest of the system. A clipboard shared with other applications, like the
+/// `SystemClipboard` of the glutin frontend, can be plugged in with `Interface::set_clipboard`.
+pub trait Clipboard {
+    /// Returns the text currently held by the clipboard, if there is one
+    fn get_contents(&mut self) -> Option<String>;
+
+    /// Replaces the content of the clipboard with the given text
+    fn set_contents(&mut self, contents: String);
+}
+
+/// A clipboard living only in memory, local to an interface
+#[derive(Default)]
+pub struct MemoryClipboard {
+    contents: Option<String>,
+}
+
+impl MemoryClipboard {
+    pub fn new() -> Self {
+        Self { contents: None }
+    }
+}
+
+impl Clipboard for MemoryClipboard {
+    fn get_contents(&mut self) -> Option<String> {
+        self.contents.clone()
+    }
+
+    fn set_contents(&mut self, contents: String) {
+        self.contents = Some(contents);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_clipboard() {
+        let mut clipboard = MemoryClipboard::new();
+        assert_eq!(clipboard.get_contents(), None);
+        clipboard.set_contents("copied".to_string());
+        assert_eq!(clipboard.get_contents(), Some("copied".to_string()));
+        clipboard.set_contents("again".to_string());
+        assert_eq!(clipboard.get_contents(), Some("again".to_string()));
+    }
+}
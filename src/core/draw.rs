@@ -41,6 +41,88 @@ pub fn quad(
     }
 }
 
+/// Draws several rectangles of the same color in a single command.
+/// Each rectangle is given by its bottom-left corner, its width and its height
+pub fn rectangles(
+    rects: &[(f32, f32, f32, f32)],
+    color: (f32, f32, f32, f32),
+    transform: Similarity3<f32>,
+) -> DrawCommand {
+    let mut uniforms = Uniforms::new();
+    uniforms.model_matrix = transform.to_homogeneous();
+
+    let tex_uv = Vector2::new(0., 0.);
+    let mut vertex_buffer = Vec::with_capacity(rects.len() * 4);
+    let mut index_buffer = Vec::with_capacity(rects.len() * 6);
+    for &(x, y, width, height) in rects {
+        let base = vertex_buffer.len() as u32;
+        vertex_buffer.push(Vertex {
+            position: Vector3::new(x, y, 0.),
+            color,
+            tex_uv,
+        });
+        vertex_buffer.push(Vertex {
+            position: Vector3::new(x + width, y, 0.),
+            color,
+            tex_uv,
+        });
+        vertex_buffer.push(Vertex {
+            position: Vector3::new(x, y + height, 0.),
+            color,
+            tex_uv,
+        });
+        vertex_buffer.push(Vertex {
+            position: Vector3::new(x + width, y + height, 0.),
+            color,
+            tex_uv,
+        });
+        index_buffer.extend_from_slice(&[base, base + 1, base + 2, base + 1, base + 2, base + 3]);
+    }
+
+    DrawCommand {
+        vertex_buffer,
+        index_buffer,
+        draw_mode: DrawMode::Triangles,
+        uniforms,
+    }
+}
+
+/// Draws a disc centered on the origin, approximated by a polygon
+/// with the given number of sides
+pub fn disc(
+    radius: f32,
+    sides: u32,
+    color: (f32, f32, f32, f32),
+    transform: Similarity3<f32>,
+) -> DrawCommand {
+    let mut uniforms = Uniforms::new();
+    uniforms.model_matrix = transform.to_homogeneous();
+
+    let tex_uv = Vector2::new(0., 0.);
+    let mut vertex_buffer = vec![Vertex {
+        position: Vector3::new(0., 0., 0.),
+        color,
+        tex_uv,
+    }];
+    let mut index_buffer = Vec::with_capacity(sides as usize * 3);
+    for side in 0..sides {
+        let angle = side as f32 * 2. * std::f32::consts::PI / sides as f32;
+        vertex_buffer.push(Vertex {
+            position: Vector3::new(radius * angle.cos(), radius * angle.sin(), 0.),
+            color,
+            tex_uv,
+        });
+        index_buffer.extend_from_slice(&[0, side + 1, (side + 1) % sides + 1]);
+    }
+
+    DrawCommand {
+        vertex_buffer,
+        index_buffer,
+        draw_mode: DrawMode::Triangles,
+        uniforms,
+    }
+}
+
 pub fn debug_quad(
     width: f32,
     height: f32,
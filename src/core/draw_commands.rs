@@ -51,11 +51,13 @@ impl Default for Uniforms {
 /// - `Triangles` allows to build filled triangles with disjoint groups of three vertices
 /// - `Lines` (not implemented yet) allows to build lines with disjoint pair of two vertices
 /// - `Points` (not implemented yet) allows to draw points, one for each vertex
+/// - `LineStrip` allows to build a polyline, each vertex being joined to the previous one
 pub enum DrawMode {
     Triangles,
     Lines,
     Points,
-    // ... TODO: to complete with TriangleFan, TriangleStrip, LineStrip, LineLoop
+    LineStrip,
+    // ... TODO: to complete with TriangleFan, TriangleStrip, LineLoop
 }
 
 /// Type describing how to draw
@@ -77,10 +79,21 @@ pub struct DrawCommand {
 /// The list of commands then contains the informations necessary to draw the widget,
 /// and the list of `DrawLists` contains the informations necessary to draw the children of the widget.
 /// The matrix `list_tranform` is only applied to the variable `list`.
+///
+/// If `clip` is set, only the part of `list` inside this rectangle is drawn.
+/// The rectangle is given by its bottom-left corner, its width and its height,
+/// in the space of `list` (after `list_transform`). Nested clips are combined: only what
+/// is inside all of them is drawn, as long as their spaces are only translated or scaled
+/// relative to one another, like the widgets of a window. Otherwise, the innermost one applies.
+///
+/// If `dimmed` is set, `commands` and `list` are drawn darker, like the part of an
+/// interface behind a modal dialog.
 pub struct DrawList {
     pub commands: Vec<DrawCommand>,
     pub list: Vec<DrawList>,
     pub list_transform: Mat4x4,
+    pub clip: Option<(f32, f32, f32, f32)>,
+    pub dimmed: bool,
 }
 
 impl DrawList {
@@ -90,6 +103,8 @@ impl DrawList {
             commands: Vec::new(),
             list: Vec::new(),
             list_transform: Mat4x4::identity(),
+            clip: None,
+            dimmed: false,
         }
     }
 }
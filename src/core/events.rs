@@ -97,6 +97,10 @@ pub enum Key {
     ArrowRight,
     ArrowUp,
     ArrowDown,
+    Home,
+    End,
+    PageUp,
+    PageDown,
     // TODO: add other keys
 }
 
@@ -105,6 +109,7 @@ pub enum Event {
     // Mouse events
     MouseButtonPressed(MouseButton),
     MouseButtonReleased(MouseButton),
+    /// Vertical movement of the wheel, in lines, positive when scrolling up
     MouseScrolled(f32),
     MouseEntered,
     MouseLeft,
@@ -135,6 +140,15 @@ pub enum EventResponse {
     Pass,
 }
 
+/// The state of the modifier keys, regardless of the side
+/// of the keyboard they are on
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
 /// Stores the current state of some input methods
 pub struct InputState {
     pub mouse_position: (f32, f32),
@@ -176,6 +190,17 @@ impl InputState {
             _ => (),
         }
     }
+
+    /// Returns the modifier keys currently held down
+    pub fn modifiers(&self) -> Modifiers {
+        let pressed =
+            |left, right| self.keys_pressed.contains(&left) || self.keys_pressed.contains(&right);
+        Modifiers {
+            shift: pressed(Key::LShift, Key::RShift),
+            ctrl: pressed(Key::LCtrl, Key::RCtrl),
+            alt: pressed(Key::LAlt, Key::RAlt),
+        }
+    }
 }
 
 impl Default for InputState {
@@ -1,8 +1,9 @@
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 use crate::core::*;
-use crate::widget::WindowHandler;
+use crate::widget::{PopupLayer, WindowHandler};
 
 /// Global properties of an interface
 pub struct GlobalProperties {
@@ -10,22 +11,118 @@ pub struct GlobalProperties {
     global_transformation: Mat4x4,
     input_state: InputState,
     focus: NodeWeakReference,
+    capture: NodeWeakReference,
+    clipboard: Box<dyn Clipboard>,
+    popup_layer: Option<NodeReference>,
+    open_popups: Vec<NodeWeakReference>,
+    time: Duration,
+    delta_time: Duration,
+    hovered: NodeWeakReference,
+    hover_start: Duration,
 }
 
 impl GlobalProperties {
     pub fn is_focused(&self, other: &NodeWeakReference) -> bool {
-        self.focus == *other
+        self.focus.ptr_eq(other)
     }
 
     pub fn request_focus(&mut self, node: &NodeWeakReference) {
         self.focus = node.clone();
     }
+
+    /// Removes the focus from the node, if it has it
+    pub fn release_focus(&mut self, node: &NodeWeakReference) {
+        if self.focus.ptr_eq(node) {
+            self.focus = Default::default();
+        }
+    }
+
+    pub fn has_pointer_capture(&self, node: &NodeWeakReference) -> bool {
+        self.capture.ptr_eq(node)
+    }
+
+    pub fn capture_pointer(&mut self, node: &NodeWeakReference) {
+        self.capture = node.clone();
+    }
+
+    /// Releases the pointer, if the node had captured it
+    pub fn release_pointer(&mut self, node: &NodeWeakReference) {
+        if self.capture.ptr_eq(node) {
+            self.capture = Default::default();
+        }
+    }
+
+    pub fn input_state(&self) -> &InputState {
+        &self.input_state
+    }
+
+    pub fn clipboard(&mut self) -> &mut dyn Clipboard {
+        self.clipboard.as_mut()
+    }
+
+    pub fn popup_layer(&self) -> Option<NodeReference> {
+        self.popup_layer.clone()
+    }
+
+    pub fn is_popup_open(&self, owner: &NodeWeakReference) -> bool {
+        self.open_popups.iter().any(|other| other.ptr_eq(owner))
+    }
+
+    /// Opens the popup for the given node. `ancestry` holds the owners of the popups
+    /// the node is in, from the outermost one: they stay open, and all others are closed
+    pub fn open_popup(&mut self, owner: &NodeWeakReference, ancestry: Vec<NodeWeakReference>) {
+        self.open_popups = ancestry;
+        self.open_popups.push(owner.clone());
+    }
+
+    /// Closes the popup, if it belongs to the node, along with the popups opened from it
+    pub fn close_popup(&mut self, owner: &NodeWeakReference) {
+        if let Some(index) = self
+            .open_popups
+            .iter()
+            .position(|other| other.ptr_eq(owner))
+        {
+            self.open_popups.truncate(index);
+        }
+    }
+
+    pub fn close_all_popups(&mut self) {
+        self.open_popups.clear();
+    }
+
+    /// Returns the time of the current frame, since the creation of the interface
+    pub fn time(&self) -> Duration {
+        self.time
+    }
+
+    /// Returns the time elapsed between the previous frame and the current one,
+    /// to drive animations
+    pub fn delta_time(&self) -> Duration {
+        self.delta_time
+    }
+
+    /// Returns the widget nearest to the pointer, as found by the last event with a ray
+    pub fn hovered(&self) -> &NodeWeakReference {
+        &self.hovered
+    }
+
+    /// Returns for how long the hovered widget has been hovered, at the current frame
+    pub fn hover_duration(&self) -> Duration {
+        self.time.checked_sub(self.hover_start).unwrap_or_default()
+    }
 }
 
 /// A structure holding an interface during its buildind process
 pub struct Interface {
     properties: Rc<RefCell<GlobalProperties>>,
     pub root: NodeReference,
+    /// The layer holding popups, above all windows.
+    /// Widgets access it through `NodeMetadata::popup_layer`
+    pub popups: NodeReference,
+    /// The layer holding modal dialogs, built like windows in `root`.
+    /// While it holds one, the rest of the interface is dimmed and gets no input
+    pub modals: NodeReference,
+    start: Instant,
 }
 
 // pub struct LockedInterface {
@@ -91,7 +188,21 @@ impl Interface {
             global_transformation: Mat4x4::identity(),
             input_state: Default::default(),
             focus: Default::default(),
+            capture: Default::default(),
+            clipboard: Box::new(MemoryClipboard::new()),
+            popup_layer: None,
+            open_popups: Vec::new(),
+            time: Duration::default(),
+            delta_time: Duration::default(),
+            hovered: Default::default(),
+            hover_start: Duration::default(),
         }));
+        let popups = Node::new_reference_from(
+            ComponentId::new_custom::<PopupLayer>(0),
+            &Rc::downgrade(&properties),
+            Box::new(PopupLayer::new()),
+        );
+        properties.borrow_mut().popup_layer = Some(popups.clone());
         Interface {
             properties: properties.clone(),
             root: Node::new_reference_from(
@@ -99,6 +210,13 @@ impl Interface {
                 &Rc::downgrade(&properties),
                 Box::new(WindowHandler::new()),
             ),
+            modals: Node::new_reference_from(
+                ComponentId::new_custom::<WindowHandler>(1),
+                &Rc::downgrade(&properties),
+                Box::new(WindowHandler::new()),
+            ),
+            popups,
+            start: Instant::now(),
         }
     }
 
@@ -109,25 +227,69 @@ impl Interface {
         self.properties.borrow_mut().global_transformation = transform;
     }
 
+    /// Replaces the clipboard used by the widgets of this interface.
+    /// By default, a `MemoryClipboard` is used.
+    pub fn set_clipboard(&mut self, clipboard: Box<dyn Clipboard>) {
+        self.properties.borrow_mut().clipboard = clipboard;
+    }
+
     /// Ends the frame. After this, no changes to the interface can be applied
     /// TODO: ensure this by using the type system and returning a locked interface
-    pub fn end_frame(&self) {}
+    pub fn end_frame(&self) {
+        // Popups that were not rebuilt during this frame are closed
+        self.popups
+            .apply_to_widget::<PopupLayer, _>(|_, layer| layer.remove_invalid());
+        // So are the windows and the modals
+        self.root
+            .apply_to_widget::<WindowHandler, _>(|_, handler| handler.remove_invalid());
+        self.modals
+            .apply_to_widget::<WindowHandler, _>(|_, handler| handler.remove_invalid());
+    }
 
     /// Starts a new frame. After this point, the interface can be reconstructed in
     /// an *immediate* style: recalling the function to build the same interface will update it,
     /// by creating new widgets if they were not there previously, and deleting ones that are not reconstructed.
     /// Widgets reconstructed are updated properly.
     pub fn new_frame(&mut self) {
+        let time = self.start.elapsed();
+        self.new_frame_at(time);
+    }
+
+    /// Starts a new frame like `new_frame`, at the given time since the creation of the interface,
+    /// for applications that keep their own clock.
+    /// The time elapsed since the previous frame is given to the widgets to animate them
+    pub fn new_frame_at(&mut self, time: Duration) {
+        {
+            let mut properties = self.properties.borrow_mut();
+            properties.delta_time = time.checked_sub(properties.time).unwrap_or_default();
+            properties.time = time;
+        }
         // Invalidate all windows
         self.root.invalidate();
+        self.root
+            .apply_to_widget::<WindowHandler, _>(|_, handler| handler.invalidate_content());
+        self.modals.invalidate();
+        self.modals
+            .apply_to_widget::<WindowHandler, _>(|_, handler| handler.invalidate_content());
+        self.popups
+            .apply_to_widget::<PopupLayer, _>(|_, layer| layer.invalidate_content());
     }
 
     /// Computes the layout, trying to satisfy all constraints provided by each widget.
     pub fn generate_layout(&self) -> LayoutResponse {
-        self.root.layout(&LayoutQuery {
+        let query = LayoutQuery {
             available_space: (Some(0.), Some(0.)),
             objectives: (Objective::None, Objective::None),
-        })
+        };
+        // The modals are stacked over the windows
+        let mut layers = 0;
+        self.root
+            .apply_to_widget::<WindowHandler, _>(|_, handler| layers = handler.len());
+        self.modals
+            .apply_to_widget::<WindowHandler, _>(|_, handler| handler.stack_over(layers));
+        self.popups.layout(&query);
+        self.modals.layout(&query);
+        self.root.layout(&query)
     }
 
     /// Returns a `DrawList`, a tree structure with `DrawCommand`s on its node, holding all the information
@@ -135,17 +297,129 @@ impl Interface {
     /// has to be passed to a backend, in charge of drawing.
     /// TODO: change the name?
     pub fn draw(&self) -> DrawList {
-        self.root.draw()
+        let mut list = DrawList::new();
+        let mut root = self.root.draw();
+        root.dimmed = self.is_modal_open();
+        list.list.push(root);
+        list.list.push(self.modals.draw());
+        list.list.push(self.popups.draw());
+        list
+    }
+
+    /// Tracks the widget under the pointer. Moving to another widget, or clicking,
+    /// restarts the hover duration
+    fn update_hover(&self, nearest: Option<&NodeReference>, event: &Event) {
+        let mut properties = self.properties.borrow_mut();
+        let hovered = nearest.map(NodeReference::downgrade).unwrap_or_default();
+        let pressed = matches!(event, Event::MouseButtonPressed(_));
+        if pressed || !hovered.ptr_eq(&properties.hovered) {
+            properties.hover_start = properties.time;
+        }
+        properties.hovered = hovered;
+    }
+
+    /// Whether a modal dialog is built in `modals`
+    pub fn is_modal_open(&self) -> bool {
+        let mut open = false;
+        self.modals
+            .apply_to_widget::<WindowHandler, _>(|_, handler| open = !handler.is_empty());
+        open
+    }
+
+    /// Whether the widget is in a modal dialog, or in a popup opened from one.
+    /// A popup is followed back to the widget that opened it, so it has to be marked
+    /// with `NodeReference::set_popup_owner`, or built with `WindowBuilder::popup_of`
+    fn is_in_modal(&self, widget: &NodeReference) -> bool {
+        let mut node = Some(widget.clone());
+        while let Some(current) = node {
+            if current.ptr_eq(&self.modals) {
+                return true;
+            }
+            node = match current.parent() {
+                Some(parent) if parent.ptr_eq(&self.popups) => current.popup_owner(),
+                parent => parent,
+            };
+        }
+        false
+    }
+
+    fn is_in_modal_weak(&self, widget: &NodeWeakReference) -> bool {
+        matches!(widget.upgrade(), Some(widget) if self.is_in_modal(&widget))
     }
 
-    /// Registers an event in the interface, propagating it to the right widget
+    /// Puts the window holding the given widget in front of the other windows
+    fn bring_to_front(&self, widget: &NodeReference) {
+        let mut window = widget.clone();
+        while let Some(parent) = window.parent() {
+            if parent.ptr_eq(&self.root) || parent.ptr_eq(&self.modals) {
+                parent.apply_to_widget::<WindowHandler, _>(|_, handler| {
+                    handler.bring_to_front(&window)
+                });
+                return;
+            }
+            window = parent;
+        }
+    }
+
+    /// Registers an event in the interface, propagating it to the right widget.
+    ///
+    /// If a widget captured the pointer, events coming with a ray are sent to it first,
+    /// wherever the ray points to. They are only propagated further if it does not register them.
+    ///
+    /// Popups come next, whatever their distance. A click outside of the open popups closes them,
+    /// and is not propagated further. Otherwise, a click brings the window it hits to the front.
+    ///
+    /// While a modal dialog is open, only the widgets of the modals, and the popups opened
+    /// from them, get events, whether from the pointer or from the keyboard. The only exception
+    /// is a widget behind the modal which captured the pointer: it still gets the button releases.
     pub fn register_event(&mut self, event: Event, ray: Option<&Ray>) -> EventResponse {
         self.properties.borrow_mut().input_state.event(&event);
 
         if let Some(ray) = ray {
-            let mut distances = self.root.interaction_distance(ray, self.root.clone());
+            let modal = self.is_modal_open();
+            let mut popup_distances = self.popups.interaction_distance(ray, self.popups.clone());
+            popup_distances.sort_unstable_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap());
+            let mut distances = if modal {
+                popup_distances.retain(|(_, widget)| self.is_in_modal(widget));
+                self.modals.interaction_distance(ray, self.modals.clone())
+            } else {
+                self.root.interaction_distance(ray, self.root.clone())
+            };
             distances.sort_unstable_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap());
 
+            let nearest = popup_distances.first().or_else(|| distances.first());
+            self.update_hover(nearest.map(|(_, widget)| widget), &event);
+
+            // A widget behind a modal still gets the release ending its drag
+            let capture = self.properties.borrow().capture.clone();
+            let release = matches!(event, Event::MouseButtonReleased(_));
+            if !modal || release || self.is_in_modal_weak(&capture) {
+                if let Some(EventResponse::Registered) = capture.send_event(&event) {
+                    return EventResponse::Registered;
+                }
+            }
+
+            if let Event::MouseButtonPressed(_) = event {
+                let mut properties = self.properties.borrow_mut();
+                let any_open = properties
+                    .open_popups
+                    .iter()
+                    .any(|owner| owner.upgrade().is_some());
+                if popup_distances.is_empty() && any_open {
+                    properties.open_popups.clear();
+                    return EventResponse::Registered;
+                }
+            }
+            for (_distance, widget) in &popup_distances {
+                if let EventResponse::Registered = widget.send_event(&event) {
+                    return EventResponse::Registered;
+                }
+            }
+
+            if let (Event::MouseButtonPressed(_), Some((_, widget))) = (&event, distances.first()) {
+                self.bring_to_front(widget);
+            }
+
             let mut passively_registered = false;
             for (_distance, widget) in &distances {
                 let response = widget.send_event(&event);
@@ -165,11 +439,13 @@ impl Interface {
                 EventResponse::Pass
             }
         } else {
-            self.properties
-                .borrow()
-                .focus
-                .send_event(&event)
-                .unwrap_or(EventResponse::Pass)
+            // The properties must not stay borrowed while the widget reacts,
+            // since it can modify them (focus, clipboard...)
+            let focus = self.properties.borrow().focus.clone();
+            if self.is_modal_open() && !self.is_in_modal_weak(&focus) {
+                return EventResponse::Pass;
+            }
+            focus.send_event(&event).unwrap_or(EventResponse::Pass)
         }
     }
 }
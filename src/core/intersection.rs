@@ -2,6 +2,7 @@ use nalgebra::*;
 use std::f32::EPSILON;
 
 /// A direction and an origin for ray-traced events
+#[derive(Copy, Clone)]
 pub struct Ray(Vector3<f32>, Point3<f32>);
 
 impl Ray {
@@ -28,6 +28,27 @@ pub struct LayoutQuery {
     pub objectives: (Objective, Objective),
 }
 
+impl LayoutQuery {
+    /// Answers the query for a widget of the given size. On each axis, the widget
+    /// shrinks to the available space if it is too big, reporting inconsistencies,
+    /// and grows to it if the objective is `Maximize`.
+    pub fn fit(&self, size: (f32, f32)) -> LayoutResponse {
+        let axis = |size: f32, available: Option<f32>, objective: Objective| match available {
+            Some(available) if size > available => (available, LayoutStatus::Inconsistencies),
+            Some(available) if matches!(objective, Objective::Maximize) => {
+                (available, LayoutStatus::Ok)
+            }
+            _ => (size, LayoutStatus::Ok),
+        };
+        let (width, x_status) = axis(size.0, self.available_space.0, self.objectives.0);
+        let (height, y_status) = axis(size.1, self.available_space.1, self.objectives.1);
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+}
+
 /// The status of the layout along a dimension.
 /// It allows to indicate to the parent if
 /// the constraint or the space given on a dimension
@@ -114,4 +135,26 @@ mod tests {
             LayoutStatus::and(LayoutStatus::WontDisplay, LayoutStatus::Ok)
         );
     }
+
+    #[test]
+    fn fit_size() {
+        let query = LayoutQuery {
+            available_space: (Some(2.), None),
+            objectives: (Objective::Maximize, Objective::Maximize),
+        };
+        let response = query.fit((1., 3.));
+        assert_eq!(response.size, (2., 3.));
+        assert_eq!(response.status, (LayoutStatus::Ok, LayoutStatus::Ok));
+
+        let query = LayoutQuery {
+            available_space: (Some(2.), Some(2.)),
+            objectives: (Objective::Minimize, Objective::Minimize),
+        };
+        let response = query.fit((1., 3.));
+        assert_eq!(response.size, (1., 2.));
+        assert_eq!(
+            response.status,
+            (LayoutStatus::Ok, LayoutStatus::Inconsistencies)
+        );
+    }
 }
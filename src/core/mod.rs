@@ -3,6 +3,7 @@
 //! Contains all core components, structures and traits
 //! necessary to build a user-interface or create a new widget
 
+pub use self::clipboard::*;
 pub use self::component_id::*;
 pub use self::draw::*;
 pub use self::draw_commands::*;
@@ -13,8 +14,10 @@ pub use self::intersection::*;
 pub use self::layout::*;
 pub use self::node::*;
 pub use self::text::*;
+pub use self::text_edit::*;
 pub use self::widget::*;
 
+mod clipboard;
 mod component_id;
 mod draw;
 mod draw_commands;
@@ -25,4 +28,5 @@ mod intersection;
 mod layout;
 mod node;
 mod text;
+mod text_edit;
 mod widget;
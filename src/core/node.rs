@@ -2,6 +2,7 @@ use std::cell::RefCell;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::rc::{Rc, Weak};
+use std::time::Duration;
 
 use crate::core::*;
 
@@ -28,6 +29,11 @@ impl NodeReference {
         self.inner.borrow().metadata.id == id
     }
 
+    /// Whether the node holds a widget of the given type
+    pub fn holds<T: Widget + 'static>(&self) -> bool {
+        self.inner.borrow().content.as_any().is::<T>()
+    }
+
     pub fn is_valid(&self) -> bool {
         !self.inner.borrow().metadata.invalid
     }
@@ -52,6 +58,31 @@ impl NodeReference {
         self.inner.borrow().metadata.transform
     }
 
+    /// Returns the node this one was created in, if any
+    pub fn parent(&self) -> Option<NodeReference> {
+        self.inner.borrow().metadata.parent.upgrade()
+    }
+
+    pub fn downgrade(&self) -> NodeWeakReference {
+        NodeWeakReference::from(&self.inner)
+    }
+
+    /// Marks the node, a popup, as opened by the `owner` widget.
+    /// See `NodeMetadata::open_popup`
+    pub fn set_popup_owner(&self, owner: &NodeReference) {
+        self.inner.borrow_mut().metadata.popup_owner = NodeWeakReference::from(&owner.inner);
+    }
+
+    /// For a popup, returns the widget that opened it
+    pub fn popup_owner(&self) -> Option<NodeReference> {
+        self.inner.borrow().metadata.popup_owner.upgrade()
+    }
+
+    /// Returns whether both references point to the same node
+    pub fn ptr_eq(&self, other: &NodeReference) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
     /// Wraps the `query` function of the contained node.
     pub fn query<T: Widget>(&self, id: ComponentId) -> NodeQueryResult<T> {
         self.inner.borrow_mut().query(id)
@@ -73,7 +104,7 @@ impl NodeReference {
         ray: &Ray,
         self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
-        self.inner.borrow().interaction_distance(ray, self_node)
+        self.inner.borrow_mut().interaction_distance(ray, self_node)
     }
 
     /// Wraps the `send_event` function of the contained node.
@@ -136,6 +167,16 @@ impl NodeWeakReference {
             .upgrade()
             .map(|node| node.borrow_mut().send_event(event))
     }
+
+    pub fn upgrade(&self) -> Option<NodeReference> {
+        self.inner.upgrade().map(NodeReference::new)
+    }
+
+    /// Returns whether both references point to the same node.
+    /// Unlike `==`, the nodes are not borrowed
+    pub fn ptr_eq(&self, other: &NodeWeakReference) -> bool {
+        self.inner.upgrade().is_some() && Weak::ptr_eq(&self.inner, &other.inner)
+    }
 }
 
 impl Default for NodeWeakReference {
@@ -158,6 +199,8 @@ impl Eq for NodeWeakReference {}
 /// - events intercepted last frame (TODO: unimplemented)
 /// - layout constraints and its solution (NOTE: implementation in progress)
 /// - local style (TODO: unimplemented)
+/// - the last pointer ray received, expressed in the space of the parent
+/// - the parent node, if any
 pub struct NodeMetadata {
     pub id: ComponentId,
     pub invalid: bool,
@@ -165,6 +208,10 @@ pub struct NodeMetadata {
     pub transform: Similarity3<f32>,
     pub ui_properties: Weak<RefCell<GlobalProperties>>,
     pub myself: NodeWeakReference,
+    pub parent: NodeWeakReference,
+    pub pointer_ray: Option<Ray>,
+    /// For a popup, the widget that opened it
+    pub popup_owner: NodeWeakReference,
 }
 
 impl NodeMetadata {
@@ -180,6 +227,9 @@ impl NodeMetadata {
             transform: Similarity3::identity(),
             ui_properties: properties.clone(),
             myself: myself.clone(),
+            parent: Default::default(),
+            pointer_ray: None,
+            popup_owner: Default::default(),
         }
     }
 
@@ -195,6 +245,192 @@ impl NodeMetadata {
             prop.borrow_mut().request_focus(&self.myself);
         }
     }
+
+    pub fn release_focus(&self) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().release_focus(&self.myself);
+        }
+    }
+
+    pub fn has_pointer_capture(&self) -> bool {
+        self.ui_properties
+            .upgrade()
+            .map(|prop| prop.borrow().has_pointer_capture(&self.myself))
+            .unwrap_or(false)
+    }
+
+    /// Captures the pointer: the widget will receive all pointer events,
+    /// even if the pointer is not above it, until it releases it
+    pub fn capture_pointer(&self) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().capture_pointer(&self.myself);
+        }
+    }
+
+    pub fn release_pointer(&self) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().release_pointer(&self.myself);
+        }
+    }
+
+    /// Returns the modifier keys currently held down
+    pub fn modifiers(&self) -> Modifiers {
+        self.ui_properties
+            .upgrade()
+            .map(|prop| prop.borrow().input_state().modifiers())
+            .unwrap_or_default()
+    }
+
+    /// Copies the text to the clipboard of the interface
+    pub fn copy_to_clipboard(&self, text: String) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().clipboard().set_contents(text);
+        }
+    }
+
+    /// Returns the text held by the clipboard of the interface, if there is one
+    pub fn paste_from_clipboard(&self) -> Option<String> {
+        self.ui_properties
+            .upgrade()
+            .and_then(|prop| prop.borrow_mut().clipboard().get_contents())
+    }
+
+    /// Returns the transformation from the local space of the widget
+    /// to the space of the interface, as computed by the last layout
+    pub fn global_transform(&self) -> Similarity3<f32> {
+        let mut transform = self.transform;
+        let mut parent = self.parent.upgrade();
+        while let Some(node) = parent {
+            let node = node.inner.borrow();
+            transform = node.metadata.transform * transform;
+            parent = node.metadata.parent.upgrade();
+        }
+        transform
+    }
+
+    /// Returns the layer of the interface holding popups,
+    /// drawn and hit-tested above all windows
+    pub fn popup_layer(&self) -> Option<NodeReference> {
+        self.ui_properties
+            .upgrade()
+            .and_then(|prop| prop.borrow().popup_layer())
+    }
+
+    /// Whether the popup of the interface is open, and belongs to this widget
+    pub fn is_popup_open(&self) -> bool {
+        self.ui_properties
+            .upgrade()
+            .map(|prop| prop.borrow().is_popup_open(&self.myself))
+            .unwrap_or(false)
+    }
+
+    /// Opens a popup of the interface for this widget.
+    ///
+    /// If the widget is itself in a popup, marked with `NodeReference::set_popup_owner`,
+    /// this popup and the ones it comes from stay open, like the menus of a submenu.
+    /// All other popups are closed
+    pub fn open_popup(&self) {
+        let ancestry = self.popup_ancestry();
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().open_popup(&self.myself, ancestry);
+        }
+    }
+
+    /// Closes the popup of this widget, and the ones opened from it
+    pub fn close_popup(&self) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().close_popup(&self.myself);
+        }
+    }
+
+    /// Closes all the popups of the interface, like a menu item does once clicked
+    pub fn close_all_popups(&self) {
+        if let Some(prop) = self.ui_properties.upgrade() {
+            prop.borrow_mut().close_all_popups();
+        }
+    }
+
+    /// Returns the time of the current frame, since the creation of the interface
+    pub fn time(&self) -> Duration {
+        self.ui_properties
+            .upgrade()
+            .map(|prop| prop.borrow().time())
+            .unwrap_or_default()
+    }
+
+    /// Returns the time elapsed between the previous frame and the current one
+    pub fn delta_time(&self) -> Duration {
+        self.ui_properties
+            .upgrade()
+            .map(|prop| prop.borrow().delta_time())
+            .unwrap_or_default()
+    }
+
+    /// If the pointer is over this widget or one of its children, returns for how long.
+    /// The duration is counted from the last time the pointer moved to another widget,
+    /// or clicked, until the current frame
+    pub fn hover_duration(&self) -> Option<Duration> {
+        let prop = self.ui_properties.upgrade()?;
+        let (hovered, duration) = {
+            let prop = prop.borrow();
+            (prop.hovered().upgrade()?, prop.hover_duration())
+        };
+        let mut node = Some(hovered);
+        while let Some(current) = node {
+            if current.downgrade().ptr_eq(&self.myself) {
+                return Some(duration);
+            }
+            node = current.parent();
+        }
+        None
+    }
+
+    /// Whether the widget is in a popup, as computed by the last build
+    pub fn is_in_popup(&self) -> bool {
+        !self.popup_ancestry().is_empty()
+    }
+
+    /// Returns the owners of the popups holding the widget, from the outermost one.
+    /// The widget itself is not borrowed, so that this can be called from its methods
+    fn popup_ancestry(&self) -> Vec<NodeWeakReference> {
+        let mut ancestry = Vec::new();
+        let layer = match self.popup_layer() {
+            Some(layer) => layer,
+            None => return ancestry,
+        };
+        let mut current = self.parent.upgrade();
+        while let Some(node) = current {
+            let inner = node.inner.borrow();
+            current = match inner.metadata.parent.upgrade() {
+                // The node is a popup: going on from the widget that opened it
+                Some(parent) if parent.ptr_eq(&layer) => {
+                    let owner = inner.metadata.popup_owner.clone();
+                    let next = owner.upgrade().and_then(|owner| owner.parent());
+                    ancestry.push(owner);
+                    next
+                }
+                parent => parent,
+            };
+        }
+        ancestry.reverse();
+        ancestry
+    }
+
+    /// Returns the point where the last pointer ray crosses the plane `z = 0`
+    /// of the widget, in its local space.
+    ///
+    /// Returns `None` if no ray was received yet, or if the ray is parallel to the plane
+    pub fn pointer_position(&self) -> Option<(f32, f32)> {
+        let ray = self.pointer_ray?;
+        let transformation = self.transform.inverse();
+        let origin = transformation * ray.origin();
+        let direction = transformation * ray.direction();
+        if direction.z.abs() < f32::EPSILON {
+            return None;
+        }
+        let t = -origin.z / direction.z;
+        Some((origin.x + t * direction.x, origin.y + t * direction.y))
+    }
 }
 
 /// An node is a wrapper around an object with a `Widget` trait,
@@ -243,6 +479,7 @@ impl Node {
     pub fn query<T: Widget>(&mut self, id: ComponentId) -> NodeQueryResult<T> {
         match self.content.query(&self.metadata, id) {
             WidgetQueryResult::Uninitialized(node) => {
+                node.inner.borrow_mut().metadata.parent = self.metadata.myself.clone();
                 NodeQueryResult::<T>::UninitializedNode(node, PhantomData)
             }
             WidgetQueryResult::Initialized(node) => {
@@ -264,11 +501,14 @@ impl Node {
         self.content.draw(&self.metadata)
     }
 
+    /// Wraps the `interaction_distance` function of the contained widget,
+    /// and keeps the ray so the widget can locate the pointer afterwards.
     pub fn interaction_distance(
-        &self,
+        &mut self,
         ray: &Ray,
         self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
+        self.metadata.pointer_ray = Some(*ray);
         self.content
             .interaction_distance(&self.metadata, ray, self_node)
     }
@@ -308,6 +548,41 @@ pub enum WidgetQueryResult {
     Initialized(NodeReference),
 }
 
+/// Answers the query of a child by a widget holding its children in `content`,
+/// the first `valid_index` of them having already been queried during this frame.
+///
+/// The child with the given id, or a new one, is moved right after the valid children.
+/// Since the children are usually queried in the same order from one frame to the next,
+/// the child is looked for from there, and is most often found right away.
+pub fn query_content(
+    content: &mut Vec<NodeReference>,
+    valid_index: &mut usize,
+    metadata: &NodeMetadata,
+    id: ComponentId,
+) -> WidgetQueryResult {
+    let child = content[*valid_index..]
+        .iter()
+        .position(|other| other.has_id(id))
+        .map(|offset| *valid_index + offset);
+    let (index, result) = match child {
+        Some(index) => (
+            index,
+            WidgetQueryResult::Initialized(content[index].clone()),
+        ),
+        None => {
+            let node_ref = Node::new_reference(id, &metadata.ui_properties);
+            content.push(node_ref.clone());
+            (
+                content.len() - 1,
+                WidgetQueryResult::Uninitialized(node_ref),
+            )
+        }
+    };
+    content.swap(*valid_index, index);
+    *valid_index += 1;
+    result
+}
+
 /// The result of a query on a node (implicitly on the content of the widget contained), holding a reference to
 /// to the child node. This structure is annotated during the compilation by the type requested.
 ///
@@ -350,3 +625,29 @@ impl<T: Widget + 'static> NodeQueryResult<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use nalgebra::{Point3, Vector3};
+
+    use super::*;
+
+    #[test]
+    fn pointer_position_in_rotated_widget() {
+        let id = ComponentId::new::<DummyWidget>(crate::loc!());
+        let mut metadata = NodeMetadata::new(id, &Default::default(), &Weak::new());
+        // The plane of the widget faces the positive x axis, 2 units away from the origin
+        metadata.transform =
+            Similarity3::new(Vector3::new(2., 0., 0.), Vector3::y() * FRAC_PI_2, 1.);
+        metadata.pointer_ray = Some(Ray::new(-Vector3::x(), Point3::new(10., 1., -3.)));
+        let (x, y) = metadata.pointer_position().unwrap();
+        assert!((x - 3.).abs() < 1e-5, "{}", x);
+        assert!((y - 1.).abs() < 1e-5, "{}", y);
+
+        // A ray parallel to the plane of the widget crosses it nowhere
+        metadata.pointer_ray = Some(Ray::new(-Vector3::z(), Point3::new(10., 1., -3.)));
+        assert_eq!(metadata.pointer_position(), None);
+    }
+}
@@ -179,3 +179,151 @@ pub fn draw_multiline_text(
         uniforms,
     }
 }
+
+/// The position of a character laid out by `draw_multiline_text`.
+///
+/// `x` and `y` are the coordinates of the bottom-left corner of the box
+/// the character sits in, whose height is the font size, and `width` is
+/// the horizontal space taken by the character
+#[derive(Copy, Clone)]
+pub struct CharacterPosition {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+}
+
+/// A helper function returning the position of each character of the text,
+/// as laid out by `draw_text` on a single line.
+///
+/// An additional position is appended at the end, indicating where
+/// a character inserted after the text would be placed
+pub fn character_positions(
+    text: &str,
+    font: &Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+) -> Vec<CharacterPosition> {
+    let mut font = font.borrow_mut();
+
+    let mut positions = Vec::with_capacity(text.chars().count() + 1);
+    let mut cursor = 0.;
+    let mut last_char = None;
+    text.chars().for_each(|c| {
+        let CharacterInfo {
+            advance_width,
+            kerning,
+            ..
+        } = font.char_info(c, last_char, font_size);
+
+        positions.push(CharacterPosition {
+            x: cursor,
+            y: 0.,
+            width: advance_width + kerning,
+        });
+
+        cursor += advance_width + kerning;
+        last_char = Some(c);
+    });
+    positions.push(CharacterPosition {
+        x: cursor,
+        y: 0.,
+        width: 0.,
+    });
+
+    positions
+}
+
+/// A helper function returning the position of each character of the text,
+/// as laid out by `draw_multiline_text` with the same parameters.
+///
+/// An additional position is appended at the end, indicating where
+/// a character inserted after the text would be placed
+pub fn multiline_character_positions(
+    text: &str,
+    font: &Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    max_width: f32,
+    height: f32,
+) -> Vec<CharacterPosition> {
+    let mut font = font.borrow_mut();
+
+    let VerticalMetrics {
+        ascent, line_gap, ..
+    } = font.get_vertical_metrics();
+
+    let mut positions = Vec::with_capacity(text.chars().count() + 1);
+    let mut cursor_x = 0.;
+    let mut cursor_y = height - ascent;
+    let mut last_char = None;
+    text.chars().for_each(|c| {
+        if c == '\r' {
+            positions.push(CharacterPosition {
+                x: cursor_x,
+                y: cursor_y,
+                width: 0.,
+            });
+            cursor_x = 0.;
+            cursor_y -= (line_gap + 1.) * font_size;
+            last_char = None;
+        } else {
+            let CharacterInfo {
+                advance_width,
+                mut kerning,
+                ..
+            } = font.char_info(c, last_char, font_size);
+
+            if cursor_x + advance_width + kerning > max_width {
+                cursor_x = 0.;
+                cursor_y -= (line_gap + 1.) * font_size;
+                kerning = 0.;
+            }
+
+            positions.push(CharacterPosition {
+                x: cursor_x,
+                y: cursor_y,
+                width: advance_width + kerning,
+            });
+
+            cursor_x += advance_width + kerning;
+            last_char = Some(c);
+        }
+    });
+    positions.push(CharacterPosition {
+        x: cursor_x,
+        y: cursor_y,
+        width: 0.,
+    });
+
+    positions
+}
+
+/// Returns the index of the caret position closest to the given point,
+/// from the positions returned by `multiline_character_positions`
+pub fn caret_index_at(positions: &[CharacterPosition], font_size: f32, point: (f32, f32)) -> usize {
+    let (x, y) = point;
+    // The line whose middle is the closest to the point
+    let line = positions
+        .iter()
+        .map(|position| position.y)
+        .min_by(|y1, y2| {
+            let d1 = (y1 + font_size / 2. - y).abs();
+            let d2 = (y2 + font_size / 2. - y).abs();
+            d1.partial_cmp(&d2).unwrap()
+        })
+        .unwrap_or(0.);
+
+    let mut last_on_line = 0;
+    for (index, position) in positions.iter().enumerate() {
+        if (position.y - line).abs() > f32::EPSILON {
+            continue;
+        }
+        if x < position.x + position.width / 2. {
+            return index;
+        }
+        last_on_line = index;
+    }
+    // The line was wrapped after its last character, so the caret goes after it
+    match positions.get(last_on_line) {
+        Some(position) if position.width > 0. => last_on_line + 1,
+        _ => last_on_line,
+    }
+}
@@ -0,0 +1,428 @@
+/// Maximum number of states kept in the undo history
+const HISTORY_LIMIT: usize = 100;
+
+/// A state of the text saved in the undo/redo history
+#[derive(Clone)]
+struct Snapshot {
+    text: String,
+    caret: usize,
+    anchor: usize,
+}
+
+/// The kind of the last modification, used to merge consecutive
+/// insertions into a single step of the history
+#[derive(Copy, Clone, PartialEq)]
+enum EditKind {
+    Insertion,
+    Deletion,
+    Replacement,
+}
+
+/// The editing state of a text: the text itself, the position of the caret,
+/// the anchor of the selection and the undo/redo history.
+///
+/// The selection spans from the anchor to the caret. When both are equal,
+/// nothing is selected.
+///
+/// All positions are expressed in characters, not in bytes.
+/// Lines are separated by `'\r'`, as in `draw_multiline_text`.
+#[derive(Clone)]
+pub struct TextEditState {
+    text: String,
+    caret: usize,
+    anchor: usize,
+    undo_stack: Vec<Snapshot>,
+    redo_stack: Vec<Snapshot>,
+    last_edit: Option<EditKind>,
+}
+
+fn is_line_break(c: char) -> bool {
+    c == '\r' || c == '\n'
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+impl TextEditState {
+    /// Creates a new editing state, with the caret at the end of the text
+    pub fn new(text: String) -> Self {
+        let caret = text.chars().count();
+        Self {
+            text,
+            caret,
+            anchor: caret,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: None,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Replaces the whole text, clamping the caret and the anchor,
+    /// and clears the history
+    pub fn set_text(&mut self, text: String) {
+        self.text = text;
+        let count = self.char_count();
+        self.caret = self.caret.min(count);
+        self.anchor = self.anchor.min(count);
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = None;
+    }
+
+    pub fn caret(&self) -> usize {
+        self.caret
+    }
+
+    pub fn anchor(&self) -> usize {
+        self.anchor
+    }
+
+    /// Returns the bounds of the selection, ordered, or `None` if nothing is selected
+    pub fn selection(&self) -> Option<(usize, usize)> {
+        if self.caret == self.anchor {
+            None
+        } else {
+            Some((self.caret.min(self.anchor), self.caret.max(self.anchor)))
+        }
+    }
+
+    /// Returns the selected part of the text, empty if nothing is selected
+    pub fn selected_text(&self) -> &str {
+        match self.selection() {
+            Some((start, end)) => &self.text[self.byte_index(start)..self.byte_index(end)],
+            None => "",
+        }
+    }
+
+    /// Moves the caret to the given position. If `select` is true,
+    /// the anchor stays in place and the selection is extended
+    pub fn set_caret(&mut self, position: usize, select: bool) {
+        self.caret = position.min(self.char_count());
+        if !select {
+            self.anchor = self.caret;
+        }
+        self.last_edit = None;
+    }
+
+    pub fn select_all(&mut self) {
+        self.anchor = 0;
+        self.caret = self.char_count();
+        self.last_edit = None;
+    }
+
+    /// Moves the caret one character, or one word, to the left
+    pub fn move_left(&mut self, select: bool, word: bool) {
+        let position = match self.selection() {
+            Some((start, _)) if !select && !word => start,
+            _ if word => self.previous_word_boundary(self.caret),
+            _ => self.caret.saturating_sub(1),
+        };
+        self.set_caret(position, select);
+    }
+
+    /// Moves the caret one character, or one word, to the right
+    pub fn move_right(&mut self, select: bool, word: bool) {
+        let position = match self.selection() {
+            Some((_, end)) if !select && !word => end,
+            _ if word => self.next_word_boundary(self.caret),
+            _ => self.caret + 1,
+        };
+        self.set_caret(position, select);
+    }
+
+    /// Moves the caret to the beginning of the line, or of the whole text
+    pub fn move_home(&mut self, select: bool, whole_text: bool) {
+        let position = if whole_text {
+            0
+        } else {
+            self.line_start(self.caret)
+        };
+        self.set_caret(position, select);
+    }
+
+    /// Moves the caret to the end of the line, or of the whole text
+    pub fn move_end(&mut self, select: bool, whole_text: bool) {
+        let position = if whole_text {
+            self.char_count()
+        } else {
+            self.line_end(self.caret)
+        };
+        self.set_caret(position, select);
+    }
+
+    /// Inserts a string at the caret, replacing the selection if there is one
+    pub fn insert(&mut self, string: &str) {
+        let kind = if self.selection().is_some() || string.chars().any(is_line_break) {
+            EditKind::Replacement
+        } else {
+            EditKind::Insertion
+        };
+        self.save(kind);
+        self.delete_selection();
+        let index = self.byte_index(self.caret);
+        self.text.insert_str(index, string);
+        self.caret += string.chars().count();
+        self.anchor = self.caret;
+    }
+
+    /// Returns the text as it would be after inserting the string with `insert`,
+    /// without modifying anything
+    pub fn preview_insert(&self, string: &str) -> String {
+        let (start, end) = self.selection().unwrap_or((self.caret, self.caret));
+        let mut text = self.text.clone();
+        text.replace_range(self.byte_index(start)..self.byte_index(end), string);
+        text
+    }
+
+    /// Deletes the selection, or the character (or word) before the caret
+    pub fn backspace(&mut self, word: bool) {
+        if self.selection().is_none() {
+            let start = if word {
+                self.previous_word_boundary(self.caret)
+            } else {
+                self.caret.saturating_sub(1)
+            };
+            if start == self.caret {
+                return;
+            }
+            self.anchor = start;
+        }
+        self.save(EditKind::Deletion);
+        self.delete_selection();
+    }
+
+    /// Deletes the selection, or the character (or word) after the caret
+    pub fn delete(&mut self, word: bool) {
+        if self.selection().is_none() {
+            let end = if word {
+                self.next_word_boundary(self.caret)
+            } else {
+                (self.caret + 1).min(self.char_count())
+            };
+            if end == self.caret {
+                return;
+            }
+            self.anchor = end;
+        }
+        self.save(EditKind::Deletion);
+        self.delete_selection();
+    }
+
+    /// Restores the state preceding the last modification.
+    /// Returns whether there was something to undo
+    pub fn undo(&mut self) -> bool {
+        match self.undo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.snapshot();
+                self.redo_stack.push(current);
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restores the state undone by the last call to `undo`.
+    /// Returns whether there was something to redo
+    pub fn redo(&mut self) -> bool {
+        match self.redo_stack.pop() {
+            Some(snapshot) => {
+                let current = self.snapshot();
+                self.undo_stack.push(current);
+                self.restore(snapshot);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Converts a position in characters to an index in bytes
+    fn byte_index(&self, position: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(position)
+            .map(|(index, _)| index)
+            .unwrap_or_else(|| self.text.len())
+    }
+
+    fn delete_selection(&mut self) {
+        if let Some((start, end)) = self.selection() {
+            let range = self.byte_index(start)..self.byte_index(end);
+            self.text.replace_range(range, "");
+            self.caret = start;
+            self.anchor = start;
+        }
+    }
+
+    fn previous_word_boundary(&self, position: usize) -> usize {
+        let chars: Vec<char> = self.text.chars().take(position).collect();
+        let mut position = chars.len();
+        while position > 0 && !is_word_char(chars[position - 1]) {
+            position -= 1;
+        }
+        while position > 0 && is_word_char(chars[position - 1]) {
+            position -= 1;
+        }
+        position
+    }
+
+    fn next_word_boundary(&self, position: usize) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut position = position.min(chars.len());
+        while position < chars.len() && !is_word_char(chars[position]) {
+            position += 1;
+        }
+        while position < chars.len() && is_word_char(chars[position]) {
+            position += 1;
+        }
+        position
+    }
+
+    fn line_start(&self, position: usize) -> usize {
+        self.text
+            .chars()
+            .take(position)
+            .collect::<Vec<char>>()
+            .iter()
+            .rposition(|&c| is_line_break(c))
+            .map(|index| index + 1)
+            .unwrap_or(0)
+    }
+
+    fn line_end(&self, position: usize) -> usize {
+        self.text
+            .chars()
+            .skip(position)
+            .position(is_line_break)
+            .map(|offset| position + offset)
+            .unwrap_or_else(|| self.char_count())
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            text: self.text.clone(),
+            caret: self.caret,
+            anchor: self.anchor,
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.text = snapshot.text;
+        self.caret = snapshot.caret;
+        self.anchor = snapshot.anchor;
+        self.last_edit = None;
+    }
+
+    /// Saves the current state in the history before a modification.
+    /// Consecutive insertions or deletions are merged into a single step
+    fn save(&mut self, kind: EditKind) {
+        let merge = kind != EditKind::Replacement && self.last_edit == Some(kind);
+        if !merge {
+            let snapshot = self.snapshot();
+            self.undo_stack.push(snapshot);
+            if self.undo_stack.len() > HISTORY_LIMIT {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.redo_stack.clear();
+        self.last_edit = Some(kind);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insertion_and_deletion() {
+        let mut state = TextEditState::new("héllo".to_string());
+        assert_eq!(state.caret(), 5);
+        state.insert(" world");
+        assert_eq!(state.text(), "héllo world");
+        state.backspace(false);
+        assert_eq!(state.text(), "héllo worl");
+        state.set_caret(1, false);
+        state.delete(false);
+        assert_eq!(state.text(), "hllo worl");
+        state.backspace(false);
+        state.backspace(false);
+        assert_eq!(state.text(), "llo worl");
+        assert_eq!(state.caret(), 0);
+    }
+
+    #[test]
+    fn selection() {
+        let mut state = TextEditState::new("hello world".to_string());
+        state.move_left(true, true);
+        assert_eq!(state.selected_text(), "world");
+        state.move_left(false, false);
+        assert_eq!(state.caret(), 6);
+        assert_eq!(state.selection(), None);
+        state.move_right(true, false);
+        state.move_right(true, false);
+        assert_eq!(state.selection(), Some((6, 8)));
+        assert_eq!(state.preview_insert("W"), "hello Wrld");
+        state.insert("W");
+        assert_eq!(state.text(), "hello Wrld");
+        state.select_all();
+        state.backspace(false);
+        assert_eq!(state.text(), "");
+    }
+
+    #[test]
+    fn word_movements() {
+        let mut state = TextEditState::new("one, two  three".to_string());
+        state.move_home(false, false);
+        state.move_right(false, true);
+        assert_eq!(state.caret(), 3);
+        state.move_right(false, true);
+        assert_eq!(state.caret(), 8);
+        state.delete(true);
+        assert_eq!(state.text(), "one, two");
+        state.backspace(true);
+        assert_eq!(state.text(), "one, ");
+    }
+
+    #[test]
+    fn lines() {
+        let mut state = TextEditState::new("first\rsecond\rthird".to_string());
+        state.set_caret(9, false);
+        state.move_home(false, false);
+        assert_eq!(state.caret(), 6);
+        state.move_end(true, false);
+        assert_eq!(state.selected_text(), "second");
+        state.move_home(false, true);
+        assert_eq!(state.caret(), 0);
+        state.move_end(false, true);
+        assert_eq!(state.caret(), 18);
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut state = TextEditState::new(String::new());
+        "abc".chars().for_each(|c| state.insert(&c.to_string()));
+        state.set_caret(0, false);
+        state.insert("x");
+        assert_eq!(state.text(), "xabc");
+        assert!(state.undo());
+        assert_eq!(state.text(), "abc");
+        assert!(state.undo());
+        assert_eq!(state.text(), "");
+        assert!(!state.undo());
+        assert!(state.redo());
+        assert_eq!(state.text(), "abc");
+        state.insert("d");
+        assert!(!state.redo());
+        assert!(state.undo());
+        assert_eq!(state.text(), "abc");
+    }
+}
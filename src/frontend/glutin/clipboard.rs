@@ -0,0 +1,26 @@
+use copypasta::{ClipboardContext, ClipboardProvider};
+
+use crate::core::Clipboard;
+
+/// The clipboard of the system, shared with the other applications
+pub struct SystemClipboard {
+    context: ClipboardContext,
+}
+
+impl SystemClipboard {
+    /// Connects to the clipboard of the system.
+    /// Returns `None` if there is none available
+    pub fn new() -> Option<Self> {
+        ClipboardContext::new().ok().map(|context| Self { context })
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn get_contents(&mut self) -> Option<String> {
+        self.context.get_contents().ok()
+    }
+
+    fn set_contents(&mut self, contents: String) {
+        let _ = self.context.set_contents(contents);
+    }
+}
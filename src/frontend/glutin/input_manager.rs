@@ -7,6 +7,9 @@ use glium::glutin::event::{
 
 pub struct Input;
 
+/// Number of pixels considered as a line, for devices scrolling precisely
+const PIXELS_PER_LINE: f32 = 20.;
+
 impl Input {
     pub fn key_from(key_code: VirtualKeyCode) -> Option<Key> {
         match key_code {
@@ -75,11 +78,11 @@ impl Input {
             VirtualKeyCode::Scroll => None,
             VirtualKeyCode::Pause => None,
             VirtualKeyCode::Insert => None,
-            VirtualKeyCode::Home => None,
+            VirtualKeyCode::Home => Some(Key::Home),
             VirtualKeyCode::Delete => Some(Key::Delete),
-            VirtualKeyCode::End => None,
-            VirtualKeyCode::PageDown => None,
-            VirtualKeyCode::PageUp => None,
+            VirtualKeyCode::End => Some(Key::End),
+            VirtualKeyCode::PageDown => Some(Key::PageDown),
+            VirtualKeyCode::PageUp => Some(Key::PageUp),
             VirtualKeyCode::Left => Some(Key::ArrowLeft),
             VirtualKeyCode::Up => Some(Key::ArrowUp),
             VirtualKeyCode::Right => Some(Key::ArrowRight),
@@ -125,10 +128,10 @@ impl Input {
             VirtualKeyCode::Grave => None,
             VirtualKeyCode::Kana => None,
             VirtualKeyCode::Kanji => None,
-            VirtualKeyCode::LAlt => None,
+            VirtualKeyCode::LAlt => Some(Key::LAlt),
             VirtualKeyCode::LBracket => None,
-            VirtualKeyCode::LControl => None,
-            VirtualKeyCode::LShift => None,
+            VirtualKeyCode::LControl => Some(Key::LCtrl),
+            VirtualKeyCode::LShift => Some(Key::LShift),
             VirtualKeyCode::LWin => None,
             VirtualKeyCode::Mail => None,
             VirtualKeyCode::MediaSelect => None,
@@ -146,10 +149,10 @@ impl Input {
             VirtualKeyCode::Plus => None,
             VirtualKeyCode::Power => None,
             VirtualKeyCode::PrevTrack => None,
-            VirtualKeyCode::RAlt => None,
+            VirtualKeyCode::RAlt => Some(Key::RAlt),
             VirtualKeyCode::RBracket => None,
-            VirtualKeyCode::RControl => None,
-            VirtualKeyCode::RShift => None,
+            VirtualKeyCode::RControl => Some(Key::RCtrl),
+            VirtualKeyCode::RShift => Some(Key::RShift),
             VirtualKeyCode::RWin => None,
             VirtualKeyCode::Semicolon => None,
             VirtualKeyCode::Slash => None,
@@ -196,8 +199,8 @@ impl Input {
             }
             WindowEvent::MouseWheel { delta, .. } => {
                 let delta = match delta {
-                    MouseScrollDelta::LineDelta(delta, _) => *delta,
-                    MouseScrollDelta::PixelDelta(pos) => pos.x as f32,
+                    MouseScrollDelta::LineDelta(_, delta) => *delta,
+                    MouseScrollDelta::PixelDelta(pos) => pos.y as f32 / PIXELS_PER_LINE,
                 };
                 Some(Event::MouseScrolled(delta))
             }
@@ -1,4 +1,17 @@
 //! The `glutin` event frontend that converts `glutin` events to penGui events
+//!
+//! `glutin` does not give access to the clipboard of the system. With the
+//! `system-clipboard` feature, `SystemClipboard` shares it with the widgets:
+//!
+//! ```ignore
+//! if let Some(clipboard) = SystemClipboard::new() {
+//!     ui.set_clipboard(Box::new(clipboard));
+//! }
+//! ```
+#[cfg(feature = "system-clipboard")]
+pub use self::clipboard::*;
 pub use self::input_manager::*;
 
+#[cfg(feature = "system-clipboard")]
+mod clipboard;
 mod input_manager;
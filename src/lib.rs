@@ -9,3 +9,6 @@ pub mod backend;
 pub mod core;
 pub mod frontend;
 pub mod widget;
+
+#[cfg(test)]
+mod test_support;
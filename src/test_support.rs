@@ -0,0 +1,74 @@
+//! Fixtures shared by the tests of the widgets
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Vector3};
+
+use crate::core::*;
+use crate::loc;
+use crate::widget::WindowBuilder;
+
+/// A font where every character is half a unit wide, to test widgets without a backend
+pub(crate) struct MockFont;
+
+impl FontAtlas for MockFont {
+    fn get_vertical_metrics(&self) -> VerticalMetrics {
+        VerticalMetrics {
+            ascent: 0.8,
+            descent: -0.2,
+            line_gap: 0.2,
+        }
+    }
+
+    fn get_texture(&self) -> TextureId {
+        TextureId::Font(0)
+    }
+
+    fn char_info(&mut self, _: char, _: Option<char>, size: f32) -> CharacterInfo {
+        CharacterInfo {
+            texture_uv: (0., 0.),
+            texture_size: (0., 0.),
+            top_left: (0., size),
+            bottom_right: (0.5 * size, 0.),
+            advance_width: 0.5 * size,
+            kerning: 0.,
+        }
+    }
+
+    fn size_of(&self, string: &str, font_size: f32) -> (f32, f32) {
+        (0.5 * font_size * string.chars().count() as f32, font_size)
+    }
+
+    fn multiline_size_of(&self, string: &str, font_size: f32, _: f32) -> (f32, f32) {
+        self.size_of(string, font_size)
+    }
+}
+
+pub(crate) fn mock_font() -> Rc<RefCell<dyn FontAtlas>> {
+    Rc::new(RefCell::new(MockFont))
+}
+
+/// A ray going straight through the plane `z = 0` at the given point
+pub(crate) fn ray_at(x: f32, y: f32) -> Ray {
+    Ray::new(-Vector3::z(), Point3::new(x, y, 10.))
+}
+
+/// Builds a frame with a single window of 10 by 10, centered on the origin,
+/// whose content is built by `content`, and lays it out
+pub(crate) fn build_in_window<F: FnMut(&NodeReference)>(ui: &mut Interface, content: F) {
+    ui.new_frame();
+    WindowBuilder::new(content)
+        .size((10., 10.))
+        .build(loc!(), &ui.root);
+    ui.end_frame();
+    ui.generate_layout();
+}
+
+/// Presses the left button with the pointer at the given point of the plane `z = 0`
+pub(crate) fn press_at(ui: &mut Interface, x: f32, y: f32) -> EventResponse {
+    ui.register_event(
+        Event::MouseButtonPressed(MouseButton::Left),
+        Some(&ray_at(x, y)),
+    )
+}
@@ -0,0 +1,298 @@
+use nalgebra::{Point3, Vector2};
+
+use crate::core::*;
+
+/// A bar chart whose bars are boxes extruded towards the viewer.
+///
+/// The bars grow upwards from the zero of the chart, or downwards for negative values,
+/// and share its width. When the pointer is over a bar, it is highlighted,
+/// and `build` returns its index and its value.
+pub struct BarChart3DBuilder {
+    values: Vec<f32>,
+    size: (f32, f32),
+    depth: f32,
+    gap: f32,
+    range: Option<(f32, f32)>,
+    color: (f32, f32, f32, f32),
+    hover_color: (f32, f32, f32, f32),
+}
+
+const BAR: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const HOVERED: (f32, f32, f32, f32) = (0.9, 0.6, 0.2, 1.);
+
+impl BarChart3DBuilder {
+    pub fn new(values: &[f32]) -> Self {
+        Self {
+            values: values.to_vec(),
+            size: (8., 6.),
+            depth: 1.,
+            gap: 0.2,
+            range: None,
+            color: BAR,
+            hover_color: HOVERED,
+        }
+    }
+
+    /// Sets the size of the chart in the plane of the widget, when it is not maximized by its parent
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets how far the bars are extruded towards the viewer
+    pub fn depth(self, depth: f32) -> Self {
+        Self { depth, ..self }
+    }
+
+    /// Sets the space between two bars
+    pub fn gap(self, gap: f32) -> Self {
+        Self { gap, ..self }
+    }
+
+    /// Fixes the values at the bottom and at the top of the chart, instead of fitting the values.
+    /// The range is extended to include zero
+    pub fn range(self, min: f32, max: f32) -> Self {
+        Self {
+            range: Some((min, max)),
+            ..self
+        }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn hover_color(self, hover_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            hover_color,
+            ..self
+        }
+    }
+}
+
+impl WidgetBuilder for BarChart3DBuilder {
+    type AchievedType = BarChart3D;
+    type UpdateFeedback = Option<(usize, f32)>;
+    type BuildFeedback = Option<(usize, f32)>;
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self.create();
+        let ray = metadata
+            .pointer_ray
+            .filter(|_| metadata.hover_duration().is_some());
+        widget.hovered = ray.and_then(|ray| {
+            let transformation = metadata.transform.inverse();
+            let local_ray = Ray::new(
+                transformation * ray.direction(),
+                transformation * ray.origin(),
+            );
+            widget.hit(metadata.size, &local_ray).map(|(_, bar)| bar)
+        });
+        widget.hovered.map(|bar| (bar, widget.values[bar]))
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let (min, max) = self.range.unwrap_or_else(|| {
+            self.values
+                .iter()
+                .filter(|value| value.is_finite())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                    (min.min(*value), max.max(*value))
+                })
+        });
+        let (min, max) = (min.min(0.), max.max(0.));
+        BarChart3D {
+            values: self.values,
+            size: self.size,
+            depth: self.depth,
+            gap: self.gap,
+            range: if max > min { (min, max) } else { (0., 1.) },
+            color: self.color,
+            hover_color: self.hover_color,
+            hovered: None,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, hovered) = parent.query::<Self::AchievedType>(id).update(self);
+        hovered
+    }
+}
+
+/// The widget behind a `BarChart3DBuilder`
+pub struct BarChart3D {
+    values: Vec<f32>,
+    size: (f32, f32),
+    depth: f32,
+    gap: f32,
+    range: (f32, f32),
+    color: (f32, f32, f32, f32),
+    hover_color: (f32, f32, f32, f32),
+    /// The index of the bar under the pointer
+    hovered: Option<usize>,
+}
+
+/// The faces of a box, as indices of its corners, with how much light they get.
+/// A corner `i` has the coordinates `(i & 1, (i >> 1) & 1, (i >> 2) & 1)`
+const FACES: [([usize; 4], f32); 6] = [
+    // Front and back
+    ([4, 5, 6, 7], 1.),
+    ([0, 1, 2, 3], 0.5),
+    // Top and bottom
+    ([2, 3, 6, 7], 0.8),
+    ([0, 1, 4, 5], 0.8),
+    // Right and left
+    ([1, 3, 5, 7], 0.6),
+    ([0, 2, 4, 6], 0.6),
+];
+
+impl BarChart3D {
+    /// The position of a value on the vertical axis
+    fn level(&self, size: (f32, f32), value: f32) -> f32 {
+        let (min, max) = self.range;
+        (value.max(min).min(max) - min) / (max - min) * size.1
+    }
+
+    /// The corners of a bar, in the local space of the widget
+    fn corners(&self, size: (f32, f32), bar: usize) -> [Point3<f32>; 8] {
+        let slot = size.0 / self.values.len() as f32;
+        let gap = self.gap.min(slot) / 2.;
+        let (x_min, x_max) = (bar as f32 * slot + gap, (bar + 1) as f32 * slot - gap);
+        let (zero, level) = (self.level(size, 0.), self.level(size, self.values[bar]));
+        let (y_min, y_max) = (zero.min(level), zero.max(level));
+        let corner = |index: usize| {
+            Point3::new(
+                if index & 1 == 0 { x_min } else { x_max },
+                if index & 2 == 0 { y_min } else { y_max },
+                if index & 4 == 0 { 0. } else { self.depth },
+            )
+        };
+        [0, 1, 2, 3, 4, 5, 6, 7].map(corner)
+    }
+
+    /// Finds the bar the ray, in the local space of the widget, hits first,
+    /// with the distance to it
+    fn hit(&self, size: (f32, f32), ray: &Ray) -> Option<(f32, usize)> {
+        (0..self.values.len())
+            .filter_map(|bar| {
+                let corners = self.corners(size, bar);
+                FACES
+                    .iter()
+                    .flat_map(|(face, _)| {
+                        vec![
+                            [corners[face[0]], corners[face[1]], corners[face[2]]],
+                            [corners[face[1]], corners[face[2]], corners[face[3]]],
+                        ]
+                    })
+                    .filter_map(|triangle| intersection(ray, &triangle))
+                    .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+                    .map(|distance| (distance, bar))
+            })
+            .min_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap())
+    }
+}
+
+impl WidgetLogic for BarChart3D {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        query.fit(self.size)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut vertex_buffer = Vec::with_capacity(self.values.len() * 24);
+        let mut index_buffer = Vec::with_capacity(self.values.len() * 36);
+        for bar in 0..self.values.len() {
+            let corners = self.corners(size, bar);
+            let (r, g, b, a) = if self.hovered == Some(bar) {
+                self.hover_color
+            } else {
+                self.color
+            };
+            // Each face has its own vertices, to be shaded with the light it gets
+            for (face, light) in &FACES {
+                let first = vertex_buffer.len() as u32;
+                vertex_buffer.extend(face.iter().map(|&corner| Vertex {
+                    position: corners[corner].coords,
+                    color: (r * light, g * light, b * light, a),
+                    tex_uv: Vector2::new(0., 0.),
+                }));
+                index_buffer.extend_from_slice(&[
+                    first,
+                    first + 1,
+                    first + 2,
+                    first + 1,
+                    first + 2,
+                    first + 3,
+                ]);
+            }
+        }
+
+        let mut uniforms = Uniforms::new();
+        uniforms.model_matrix = metadata.transform.to_homogeneous();
+        let mut list = DrawList::new();
+        list.commands.push(DrawCommand {
+            vertex_buffer,
+            index_buffer,
+            draw_mode: DrawMode::Triangles,
+            uniforms,
+        });
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.hit(metadata.size, &new_ray)
+            .map(|(d, _)| vec![(d, self_node)])
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, ray_at};
+
+    #[test]
+    fn hover_bar() {
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            let mut hovered = None;
+            build_in_window(ui, |window| {
+                hovered = BarChart3DBuilder::new(&[1., -1., 3.])
+                    .size((9., 10.))
+                    .gap(1.)
+                    .build(loc!(), window);
+            });
+            hovered
+        };
+
+        // The values go from -1 to 3: zero is at y = -2.5, the first bar goes up to 0,
+        // the second one down to -5, and the last one up to 5
+        assert_eq!(build(&mut ui), None);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-3.5, -1.)));
+        assert_eq!(build(&mut ui), Some((0, 1.)));
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-3.5, 1.)));
+        assert_eq!(build(&mut ui), None);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-0.5, -4.)));
+        assert_eq!(build(&mut ui), Some((1, -1.)));
+        // Between two bars
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(1., 0.)));
+        assert_eq!(build(&mut ui), None);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(2.5, 4.)));
+        assert_eq!(build(&mut ui), Some((2, 3.)));
+    }
+}
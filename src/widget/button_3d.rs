@@ -216,7 +216,10 @@ impl WidgetLogic for Button3D {
         self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         let size = metadata.size;
         let points = [
             // Top points
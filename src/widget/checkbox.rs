@@ -5,7 +5,10 @@ use nalgebra::{Point3, Translation3, Vector2, Vector3};
 
 use crate::core::*;
 
-/// A basic checkbox widget, with a label
+/// A basic checkbox widget, with a label.
+///
+/// It keeps its checked state itself. Use `CheckBoxBuilder` to bind it
+/// to a `bool` owned by the application instead.
 pub struct CheckBox {
     label: String,
     checked_color: (f32, f32, f32, f32),
@@ -13,6 +16,9 @@ pub struct CheckBox {
     text_color: (f32, f32, f32, f32),
     font: Rc<RefCell<dyn FontAtlas>>,
     checked: bool,
+    indeterminate: bool,
+    /// Whether the checkbox was clicked since the last update
+    clicked: bool,
     texture: Option<TextureId>,
 }
 
@@ -29,6 +35,8 @@ impl CheckBox {
             text_color: TEXT,
             font: font.clone(),
             checked: false,
+            indeterminate: false,
+            clicked: false,
             texture: None,
         }
     }
@@ -73,6 +81,7 @@ impl WidgetBuilder for CheckBox {
         widget.checked_color = self.checked_color;
         widget.unchecked_color = self.unchecked_color;
         widget.text_color = self.text_color;
+        widget.clicked = false;
         widget.checked
     }
 
@@ -87,6 +96,119 @@ impl WidgetBuilder for CheckBox {
     }
 }
 
+/// A checkbox bound to a `bool` owned by the application, returning
+/// from `build` whether the user changed it.
+///
+/// It can also be displayed as indeterminate, typically for a checkbox
+/// selecting all the elements of a list when only some of them are selected.
+/// Clicking an indeterminate checkbox checks it.
+pub struct CheckBoxBuilder<'a> {
+    label: String,
+    checked: &'a mut bool,
+    indeterminate: bool,
+    checked_color: (f32, f32, f32, f32),
+    unchecked_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    font: Rc<RefCell<dyn FontAtlas>>,
+    texture: Option<TextureId>,
+}
+
+impl<'a> CheckBoxBuilder<'a> {
+    pub fn new(label: String, checked: &'a mut bool, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            label,
+            checked,
+            indeterminate: false,
+            checked_color: CHECKED,
+            unchecked_color: UNCHECKED,
+            text_color: TEXT,
+            font: font.clone(),
+            texture: None,
+        }
+    }
+
+    /// Displays the checkbox as neither checked nor unchecked
+    pub fn indeterminate(self, indeterminate: bool) -> Self {
+        Self {
+            indeterminate,
+            ..self
+        }
+    }
+
+    pub fn checked_color(self, checked_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            checked_color,
+            ..self
+        }
+    }
+
+    pub fn unchecked_color(self, unchecked_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            unchecked_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+
+    pub fn texture(self, texture_id: TextureId) -> Self {
+        Self {
+            texture: Some(texture_id),
+            ..self
+        }
+    }
+}
+
+impl<'a> WidgetBuilder for CheckBoxBuilder<'a> {
+    type AchievedType = CheckBox;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let mut changed = false;
+        if widget.clicked {
+            widget.clicked = false;
+            *self.checked = widget.checked;
+            changed = true;
+        }
+
+        widget.label = self.label;
+        widget.checked = *self.checked;
+        widget.indeterminate = self.indeterminate && !changed;
+        widget.checked_color = self.checked_color;
+        widget.unchecked_color = self.unchecked_color;
+        widget.text_color = self.text_color;
+        widget.texture = self.texture;
+        changed
+    }
+
+    fn create(self) -> Self::AchievedType {
+        CheckBox {
+            label: self.label,
+            checked_color: self.checked_color,
+            unchecked_color: self.unchecked_color,
+            text_color: self.text_color,
+            font: self.font,
+            checked: *self.checked,
+            indeterminate: self.indeterminate,
+            clicked: false,
+            texture: self.texture,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
 const PADDING: f32 = 0.2;
 
 impl WidgetLogic for CheckBox {
@@ -142,7 +264,7 @@ impl WidgetLogic for CheckBox {
 
     fn draw(&self, metadata: &NodeMetadata) -> DrawList {
         let color = {
-            if self.checked {
+            if self.checked && !self.indeterminate {
                 self.checked_color
             } else {
                 self.unchecked_color
@@ -196,6 +318,17 @@ impl WidgetLogic for CheckBox {
 
         let mut list = DrawList::new();
         list.commands.push(background_command);
+        if self.indeterminate {
+            let thickness = (border - PADDING) / 4.;
+            list.commands.push(quad(
+                border - 3. * PADDING,
+                thickness,
+                None,
+                self.checked_color,
+                metadata.transform
+                    * Translation3::new(2. * PADDING, (size.1 - thickness) / 2., 0.02),
+            ));
+        }
         list.commands.push(text_command);
         list
     }
@@ -207,7 +340,10 @@ impl WidgetLogic for CheckBox {
         self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         let border = metadata.size.1 - PADDING;
         let points = [
             Point3::new(PADDING, PADDING, 0.),
@@ -230,10 +366,60 @@ impl WidgetLogic for CheckBox {
     fn send_event(&mut self, _metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
         match event {
             Event::MouseButtonPressed(MouseButton::Left) => {
-                self.checked = !self.checked;
+                self.checked = self.indeterminate || !self.checked;
+                self.indeterminate = false;
+                self.clicked = true;
                 EventResponse::Registered
             }
             _ => EventResponse::Pass,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+
+    #[test]
+    fn bound_and_indeterminate() {
+        let font = mock_font();
+        let mut items = [true, false];
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, items: &mut [bool; 2]| {
+            build_in_window(ui, |window| {
+                let mut all = items.iter().all(|&item| item);
+                let some = !all && items.iter().any(|&item| item);
+                if CheckBoxBuilder::new("All".to_string(), &mut all, &font)
+                    .indeterminate(some)
+                    .build(loc!(), window)
+                {
+                    items.iter_mut().for_each(|item| *item = all);
+                }
+            });
+        };
+
+        build(&mut ui, &mut items);
+        // The box of the checkbox is at the top left of the window
+        let click = |ui: &mut Interface| {
+            press_at(ui, -4.3, 4.3);
+        };
+
+        // Indeterminate, so clicking checks everything
+        click(&mut ui);
+        build(&mut ui, &mut items);
+        assert_eq!(items, [true, true]);
+
+        click(&mut ui);
+        build(&mut ui, &mut items);
+        assert_eq!(items, [false, false]);
+
+        // The state is owned by the application, which can change it
+        items = [true, true];
+        build(&mut ui, &mut items);
+        click(&mut ui);
+        build(&mut ui, &mut items);
+        assert_eq!(items, [false, false]);
+    }
+}
@@ -0,0 +1,431 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3, UnitQuaternion, Vector2, Vector3};
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// Builds the widgets shown under an open header
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// A header with an arrow, which shows or hides the widgets below it when clicked.
+///
+/// The widgets are displayed from top to bottom, indented under the header.
+/// They are only built while the header is open, and the open state is kept
+/// by the widget itself, so the generator does not need to track it.
+///
+/// `build` returns whether the header is open.
+pub struct CollapsingHeaderBuilder<'a> {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    default_open: bool,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a> CollapsingHeaderBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(
+        label: String,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+        generator: F,
+    ) -> Self {
+        Self {
+            label,
+            font: font.clone(),
+            font_size: 1.,
+            default_open: false,
+            color: BACKGROUND,
+            text_color: TEXT,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets whether the header is open when it is first built
+    pub fn default_open(self, default_open: bool) -> Self {
+        Self {
+            default_open,
+            ..self
+        }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for CollapsingHeaderBuilder<'a> {
+    type AchievedType = CollapsingHeader;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.set_label(self.label, self.font_size);
+        widget.set_colors(Some(self.color), self.color, self.text_color);
+        widget.start_update()
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let mut widget = CollapsingHeader::new(self.label, self.font_size, &self.font);
+        widget.set_colors(Some(self.color), self.color, self.text_color);
+        widget.set_expandable(true);
+        widget.set_open(self.default_open);
+        widget
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let generator = self.generator.take();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        CollapsingHeader::build_content(&node_ref, generator)
+    }
+}
+
+/// The widget behind a `CollapsingHeaderBuilder` or a `TreeNodeBuilder`
+pub struct CollapsingHeader {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    /// The background of the header, if any
+    color: Option<(f32, f32, f32, f32)>,
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    /// Whether there is an arrow to open the header
+    expandable: bool,
+    /// Whether the header is selected, if it can be
+    selection: Option<bool>,
+    open: bool,
+    /// Set when the label of a selectable header was clicked since the last update
+    clicked: bool,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+const PADDING: f32 = 0.2;
+/// Horizontal shift of the content relative to the header
+pub(crate) const INDENT: f32 = 1.;
+
+impl CollapsingHeader {
+    /// Creates a header that cannot be opened nor selected, without background
+    pub(crate) fn new(label: String, font_size: f32, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            label,
+            font: font.clone(),
+            font_size,
+            color: None,
+            selected_color: SELECTED,
+            text_color: TEXT,
+            expandable: false,
+            selection: None,
+            open: false,
+            clicked: false,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    pub(crate) fn set_label(&mut self, label: String, font_size: f32) {
+        self.label = label;
+        self.font_size = font_size;
+    }
+
+    pub(crate) fn set_colors(
+        &mut self,
+        color: Option<(f32, f32, f32, f32)>,
+        selected_color: (f32, f32, f32, f32),
+        text_color: (f32, f32, f32, f32),
+    ) {
+        self.color = color;
+        self.selected_color = selected_color;
+        self.text_color = text_color;
+    }
+
+    pub(crate) fn set_expandable(&mut self, expandable: bool) {
+        self.expandable = expandable;
+    }
+
+    pub(crate) fn set_selection(&mut self, selection: Option<bool>) {
+        self.selection = selection;
+    }
+
+    pub(crate) fn set_open(&mut self, open: bool) {
+        self.open = open;
+    }
+
+    /// Invalidates the content before it is built again,
+    /// and returns whether the label was clicked since the last update
+    pub(crate) fn start_update(&mut self) -> bool {
+        self.content.iter().for_each(|child| child.invalidate());
+        self.valid_index = 0;
+        std::mem::take(&mut self.clicked)
+    }
+
+    /// Builds the content of an open header, and returns whether it is open.
+    /// The content of a closed one is kept as it is until it is opened again
+    pub(crate) fn build_content<F: FnMut(&NodeReference)>(
+        node_ref: &NodeReference,
+        generator: Option<F>,
+    ) -> bool {
+        let mut open = false;
+        node_ref.apply_to_widget::<Self, _>(|_, widget| open = widget.is_open());
+        if let (true, Some(mut generator)) = (open, generator) {
+            (generator)(node_ref);
+            node_ref.apply_to_widget::<Self, _>(|_, widget| {
+                widget.content.retain(|child| child.is_valid())
+            });
+        }
+        open
+    }
+
+    fn header_height(&self) -> f32 {
+        self.font_size + 2. * PADDING
+    }
+
+    /// Width of the area holding the arrow, on the left of the header
+    fn arrow_width(&self) -> f32 {
+        self.font_size + PADDING
+    }
+
+    fn is_open(&self) -> bool {
+        self.open && self.expandable
+    }
+}
+
+impl WidgetLogic for CollapsingHeader {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (label_width, _) = self
+            .font
+            .borrow()
+            .size_of(self.label.as_str(), self.font_size);
+        let header_height = self.header_height();
+        let mut width = self.arrow_width() + label_width + PADDING;
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+
+        // The content is laid out from top to bottom like in a window, under the header
+        let mut sizes = Vec::new();
+        let mut height = header_height;
+        if self.is_open() {
+            for node in &self.content {
+                let response = node.layout(&LayoutQuery {
+                    available_space: (
+                        query
+                            .available_space
+                            .0
+                            .map(|width| (width - INDENT).max(0.)),
+                        query
+                            .available_space
+                            .1
+                            .map(|available| (available - height - WIDGET_SEPARATOR).max(0.)),
+                    ),
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                });
+                status.0 = LayoutStatus::and(status.0, response.status.0);
+                status.1 = LayoutStatus::and(status.1, response.status.1);
+                width = width.max(response.size.0 + INDENT);
+                height += WIDGET_SEPARATOR + response.size.1;
+                sizes.push(response.size);
+            }
+        }
+
+        let mut cursor = height - header_height;
+        for (node, size) in self.content.iter().zip(sizes) {
+            cursor -= WIDGET_SEPARATOR + size.1;
+            node.set_size(size);
+            node.set_transform(Similarity3::identity() * Translation3::new(INDENT, cursor, 0.));
+        }
+
+        // The header takes all the width available
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+        if width > available_width {
+            status.0 = LayoutStatus::and(status.0, LayoutStatus::Inconsistencies);
+        }
+        if height > available_height {
+            status.1 = LayoutStatus::and(status.1, LayoutStatus::Inconsistencies);
+        }
+
+        LayoutResponse {
+            size: (available_width, height),
+            status,
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let header_height = self.header_height();
+        let header_transform =
+            metadata.transform * Translation3::new(0., size.1 - header_height, 0.);
+
+        let mut list = DrawList::new();
+        let color = match self.selection {
+            Some(true) => Some(self.selected_color),
+            _ => self.color,
+        };
+        if let Some(color) = color {
+            list.commands
+                .push(quad(size.0, header_height, None, color, header_transform));
+        }
+
+        if self.expandable {
+            // A triangle pointing down when open, and to the right when closed
+            let angle = if self.open {
+                0.
+            } else {
+                std::f32::consts::FRAC_PI_2
+            };
+            let half = self.font_size / 3.;
+            let mut uniforms = Uniforms::new();
+            uniforms.model_matrix = (header_transform
+                * Translation3::new(PADDING + self.font_size / 2., header_height / 2., 0.01)
+                * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle))
+            .to_homogeneous();
+            let vertex = |x: f32, y: f32| Vertex {
+                position: Vector3::new(x, y, 0.),
+                color: self.text_color,
+                tex_uv: Vector2::new(0., 0.),
+            };
+            list.commands.push(DrawCommand {
+                vertex_buffer: vec![
+                    vertex(-half, half / 2.),
+                    vertex(half, half / 2.),
+                    vertex(0., -half / 2.),
+                ],
+                index_buffer: vec![0, 1, 2],
+                draw_mode: DrawMode::Triangles,
+                uniforms,
+            });
+        }
+
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (header_transform * Translation3::new(self.arrow_width(), PADDING, 0.01))
+                .to_homogeneous(),
+        ));
+
+        if self.is_open() {
+            self.content.iter().for_each(|node| {
+                list.list.push(node.draw());
+            });
+            list.list_transform = metadata.transform.to_homogeneous();
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let bottom = size.1 - self.header_height();
+        let points = [
+            Point3::new(0., bottom, 0.),
+            Point3::new(size.0, bottom, 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let mut result: Vec<(f32, NodeReference)> = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default();
+
+        if self.is_open() {
+            result.extend(
+                self.content
+                    .iter()
+                    .flat_map(|content| content.interaction_distance(&new_ray, content.clone())),
+            );
+        }
+        result
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                // Selectable headers are only toggled by their arrow
+                let on_arrow = !matches!(
+                    metadata.pointer_position(),
+                    Some((x, _)) if x > self.arrow_width()
+                );
+                if self.selection.is_some() && !(self.expandable && on_arrow) {
+                    self.clicked = true;
+                } else if self.expandable {
+                    self.open = !self.open;
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+    use crate::widget::*;
+
+    #[test]
+    fn open_and_close() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            let mut built = false;
+            let mut open = false;
+            build_in_window(ui, |window| {
+                open = CollapsingHeaderBuilder::new("Header".to_string(), &font, |header| {
+                    built = true;
+                    LabelBuilder::new("Content", &font).build(loc!(), header);
+                })
+                .build(loc!(), window);
+            });
+            (open, built)
+        };
+
+        assert_eq!(build(&mut ui), (false, false));
+        press_at(&mut ui, 0., 4.3);
+        assert_eq!(build(&mut ui), (true, true));
+        press_at(&mut ui, 0., 4.3);
+        assert_eq!(build(&mut ui), (false, false));
+    }
+}
@@ -0,0 +1,827 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3, Vector2, Vector3};
+
+use crate::core::*;
+use crate::loc;
+use crate::widget::window::WIDGET_SEPARATOR;
+use crate::widget::{DragValueBuilder, InlineBuilder, InputBuilder, LabelBuilder, WindowBuilder};
+
+/// A color picker bound to an RGBA color, with components between 0 and 1.
+///
+/// The full picker shows a square choosing the saturation and the value of the color,
+/// next to a strip choosing its hue, with fields to type its RGB, HSV and hexadecimal values.
+/// The alpha component is kept as it is.
+///
+/// In `compact` mode, only a swatch of the color is displayed, opening the full picker
+/// in a popup when clicked. `build` returns whether the color was changed by the user.
+pub struct ColorPickerBuilder<'a> {
+    color: Option<&'a mut (f32, f32, f32, f32)>,
+    /// The color before the update, while `color` is taken by `build`
+    current: (f32, f32, f32, f32),
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    size: f32,
+    compact: bool,
+}
+
+const BORDER: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const MARKER_LIGHT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const MARKER_DARK: (f32, f32, f32, f32) = (0., 0., 0., 1.);
+
+impl<'a> ColorPickerBuilder<'a> {
+    pub fn new(color: &'a mut (f32, f32, f32, f32), font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            current: *color,
+            color: Some(color),
+            font: font.clone(),
+            font_size: 1.,
+            size: 8.,
+            compact: false,
+        }
+    }
+
+    /// Sets the side of the saturation/value square
+    pub fn size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Only displays a swatch of the color, opening the full picker in a popup
+    pub fn compact(self) -> Self {
+        Self {
+            compact: true,
+            ..self
+        }
+    }
+
+    /// Builds the swatch of the compact mode, and the popup with the full picker when open
+    fn build_compact(self, loc: CodeLocation, parent: &NodeReference) -> bool {
+        let id = ComponentId::new::<ColorSwatch>(loc);
+        let color = self.color.unwrap();
+        let (font, font_size, size) = (self.font, self.font_size, self.size);
+        let swatch = ColorSwatch {
+            color: *color,
+            font_size,
+        };
+        let (node_ref, _) = parent.query::<ColorSwatch>(id).update(swatch);
+
+        let mut popup = None;
+        node_ref.apply_to_widget::<ColorSwatch, _>(|metadata, _| {
+            if metadata.is_popup_open() {
+                popup = metadata
+                    .popup_layer()
+                    .map(|layer| (layer, metadata.global_transform()));
+            }
+        });
+
+        let mut changed = false;
+        if let Some((layer, transform)) = popup {
+            let (width, height) = picker_size(&font, font_size, size);
+            WindowBuilder::new(|popup| {
+                changed = ColorPickerBuilder::new(color, &font)
+                    .font_size(font_size)
+                    .size(size)
+                    .build(loc, popup);
+            })
+            .size((width, height))
+            .transform(transform * Translation3::new(0., -height, POPUP_DEPTH))
+            .popup_of(&node_ref)
+            .build(loc, &layer);
+        }
+        changed
+    }
+}
+
+impl<'a> WidgetBuilder for ColorPickerBuilder<'a> {
+    type AchievedType = ColorPicker;
+    type UpdateFeedback = Option<(f32, f32, f32)>;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.size = self.size;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+
+        if widget.picked {
+            widget.picked = false;
+            return Some(hsv_to_rgb(widget.hsv));
+        }
+        widget.follow(self.current);
+        None
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let (r, g, b, _) = self.current;
+        ColorPicker {
+            hsv: rgb_to_hsv((r, g, b)),
+            size: self.size,
+            drag: None,
+            picked: false,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        if self.compact {
+            return self.build_compact(loc, parent);
+        }
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let color = self.color.take().unwrap();
+        let (font, font_size, size) = (self.font.clone(), self.font_size, self.size);
+        let (node_ref, picked) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut changed = false;
+        if let Some((r, g, b)) = picked {
+            *color = (r, g, b, color.3);
+            changed = true;
+        }
+        let mut hsv = (0., 0., 0.);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| hsv = widget.hsv);
+
+        // The fields of each row share the width of the picker, after a label
+        let (width, _) = picker_size(&font, font_size, size);
+        let label_width = label_width(&font, font_size);
+        let field_width = (width - label_width - 3. * WIDGET_SEPARATOR) / 3.;
+        let to_byte = |component: f32| (component * 255.).round() as i32;
+        let mut rgb = [to_byte(color.0), to_byte(color.1), to_byte(color.2)];
+        let mut hsv_fields = [
+            (hsv.0 * 360.).round() as i32,
+            (hsv.1 * 100.).round() as i32,
+            (hsv.2 * 100.).round() as i32,
+        ];
+        let mut hex = to_hex((color.0, color.1, color.2));
+        let (mut rgb_changed, mut hsv_changed, mut hex_changed) = (false, false, false);
+
+        InlineBuilder::new(|row| {
+            LabelBuilder::new("RGB", &font)
+                .size(font_size)
+                .build(loc!(), row);
+            for (index, component) in rgb.iter_mut().enumerate() {
+                rgb_changed |= DragValueBuilder::new(component, &font)
+                    .range(0, 255)
+                    .font_size(font_size)
+                    .width(field_width)
+                    .build(CodeLocation(file!(), line!(), index as u32), row);
+            }
+        })
+        .build(loc!(), &node_ref);
+        InlineBuilder::new(|row| {
+            LabelBuilder::new("HSV", &font)
+                .size(font_size)
+                .build(loc!(), row);
+            for (index, (component, max)) in hsv_fields.iter_mut().zip(&[359, 100, 100]).enumerate()
+            {
+                hsv_changed |= DragValueBuilder::new(component, &font)
+                    .range(0, *max)
+                    .font_size(font_size)
+                    .width(field_width)
+                    .build(CodeLocation(file!(), line!(), index as u32), row);
+            }
+        })
+        .build(loc!(), &node_ref);
+        InlineBuilder::new(|row| {
+            LabelBuilder::new("Hex", &font)
+                .size(font_size)
+                .build(loc!(), row);
+            hex_changed = InputBuilder::new(&mut hex, &font)
+                .size(font_size)
+                .width(width - label_width - WIDGET_SEPARATOR)
+                .build(loc!(), row)
+                .changed;
+        })
+        .build(loc!(), &node_ref);
+
+        let from_byte = |component: i32| component as f32 / 255.;
+        let mut picked = None;
+        if rgb_changed {
+            picked = Some((from_byte(rgb[0]), from_byte(rgb[1]), from_byte(rgb[2])));
+        } else if hsv_changed {
+            let [h, s, v] = hsv_fields;
+            let hsv = (h as f32 / 360., s as f32 / 100., v as f32 / 100.);
+            // Set directly, so that the hue is kept even for grays
+            node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| widget.hsv = hsv);
+            picked = Some(hsv_to_rgb(hsv));
+        } else if hex_changed {
+            picked = from_hex(&hex);
+        }
+        if let Some((r, g, b)) = picked {
+            *color = (r, g, b, color.3);
+            changed = true;
+        }
+
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid());
+        });
+        changed
+    }
+}
+
+/// Which part of a color picker is being dragged
+#[derive(Copy, Clone, PartialEq)]
+enum Drag {
+    Square,
+    HueStrip,
+}
+
+/// The widget behind a `ColorPickerBuilder`, holding the fields of the color below
+/// the square and the strip it draws.
+///
+/// It keeps the color in HSV, so that the hue and the saturation are not lost
+/// when the color becomes gray or black.
+pub struct ColorPicker {
+    hsv: (f32, f32, f32),
+    size: f32,
+    drag: Option<Drag>,
+    /// Whether the color was changed with the pointer since the last update
+    picked: bool,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+/// Width of the hue strip, relative to the size of the square
+const STRIP_WIDTH: f32 = 0.125;
+/// Number of cells along each side of the square, interpolating the colors
+const SQUARE_CELLS: usize = 8;
+const MARKER_SIZE: f32 = 0.3;
+const PADDING: f32 = 0.2;
+/// Distance between the popup and the swatch, towards the viewer
+const POPUP_DEPTH: f32 = 0.1;
+
+impl ColorPicker {
+    /// Follows a color changed by the application or by the fields
+    fn follow(&mut self, (r, g, b, _): (f32, f32, f32, f32)) {
+        let (current_r, current_g, current_b) = hsv_to_rgb(self.hsv);
+        let same = |c1: f32, c2: f32| (c1 - c2).abs() < 1e-4;
+        if same(r, current_r) && same(g, current_g) && same(b, current_b) {
+            return;
+        }
+        let (h, s, v) = rgb_to_hsv((r, g, b));
+        self.hsv = if v == 0. {
+            (self.hsv.0, self.hsv.1, v)
+        } else if s == 0. {
+            (self.hsv.0, s, v)
+        } else {
+            (h, s, v)
+        };
+    }
+
+    /// The height of the fields below the square
+    fn fields_height(&self, size: (f32, f32)) -> f32 {
+        size.1 - self.size
+    }
+
+    /// Changes the color according to the pointer, at `position` in the local space
+    fn pick(&mut self, metadata: &NodeMetadata, drag: Drag, position: (f32, f32)) {
+        let bottom = self.fields_height(metadata.size);
+        let unit = |value: f32| value.clamp(0., 1.);
+        let y = unit((position.1 - bottom) / self.size);
+        match drag {
+            Drag::Square => {
+                self.hsv.1 = unit(position.0 / self.size);
+                self.hsv.2 = y;
+            }
+            // The hue wraps around, so the top of the strip is the same as the bottom
+            Drag::HueStrip => self.hsv.0 = y.min(0.9999),
+        }
+        self.picked = true;
+    }
+}
+
+impl WidgetLogic for ColorPicker {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
+        let width = self.size * (1. + STRIP_WIDTH) + WIDGET_SEPARATOR;
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let responses = self
+            .content
+            .iter()
+            .map(|node| {
+                node.layout(&LayoutQuery {
+                    available_space: (Some(width), None),
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                })
+            })
+            .collect::<Vec<_>>();
+        let fields_height = responses
+            .iter()
+            .map(|response| response.size.1 + WIDGET_SEPARATOR)
+            .sum::<f32>();
+
+        // The fields are stacked from the top, below the square
+        let mut y = fields_height;
+        for (node, response) in self.content.iter().zip(responses) {
+            y -= WIDGET_SEPARATOR + response.size.1;
+            node.set_size(response.size);
+            node.set_transform(Similarity3::identity() * Translation3::new(0., y, 0.));
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+        }
+        LayoutResponse {
+            size: (width, fields_height + self.size),
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let bottom = self.fields_height(metadata.size);
+        let size = self.size;
+        let vertex = |x: f32, y: f32, (r, g, b): (f32, f32, f32)| Vertex {
+            position: Vector3::new(x, bottom + y, 0.),
+            color: (r, g, b, 1.),
+            tex_uv: Vector2::new(0., 0.),
+        };
+        let uniforms = || {
+            let mut uniforms = Uniforms::new();
+            uniforms.model_matrix = metadata.transform.to_homogeneous();
+            uniforms
+        };
+
+        // The square interpolates the colors of a grid, from gray on its left
+        // to saturated on its right, and from black at the bottom to bright at the top
+        let cells = SQUARE_CELLS;
+        let mut square = DrawCommand {
+            vertex_buffer: Vec::new(),
+            index_buffer: Vec::new(),
+            draw_mode: DrawMode::Triangles,
+            uniforms: uniforms(),
+        };
+        for j in 0..=cells {
+            for i in 0..=cells {
+                let (s, v) = (i as f32 / cells as f32, j as f32 / cells as f32);
+                square.vertex_buffer.push(vertex(
+                    s * size,
+                    v * size,
+                    hsv_to_rgb((self.hsv.0, s, v)),
+                ));
+            }
+        }
+        for j in 0..cells {
+            for i in 0..cells {
+                let corner = (j * (cells + 1) + i) as u32;
+                let above = corner + cells as u32 + 1;
+                square.index_buffer.extend_from_slice(&[
+                    corner,
+                    corner + 1,
+                    above,
+                    corner + 1,
+                    above,
+                    above + 1,
+                ]);
+            }
+        }
+
+        // The strip goes through the six primary and secondary colors, back to red
+        let strip_x = size + WIDGET_SEPARATOR;
+        let strip_width = size * STRIP_WIDTH;
+        let mut strip = DrawCommand {
+            vertex_buffer: Vec::new(),
+            index_buffer: Vec::new(),
+            draw_mode: DrawMode::Triangles,
+            uniforms: uniforms(),
+        };
+        for k in 0..=6 {
+            let hue = k as f32 / 6.;
+            let color = hsv_to_rgb((hue % 1., 1., 1.));
+            strip.vertex_buffer.push(vertex(strip_x, hue * size, color));
+            strip
+                .vertex_buffer
+                .push(vertex(strip_x + strip_width, hue * size, color));
+        }
+        for k in 0..6 {
+            let first = 2 * k as u32;
+            strip.index_buffer.extend_from_slice(&[
+                first,
+                first + 1,
+                first + 2,
+                first + 1,
+                first + 2,
+                first + 3,
+            ]);
+        }
+
+        // Markers on the current color, contrasting with it
+        let (_, s, v) = self.hsv;
+        let marker = if v > 0.5 { MARKER_DARK } else { MARKER_LIGHT };
+        let half = MARKER_SIZE / 2.;
+        let square_marker = quad(
+            MARKER_SIZE,
+            MARKER_SIZE,
+            None,
+            marker,
+            metadata.transform * Translation3::new(s * size - half, bottom + v * size - half, 0.01),
+        );
+        let strip_marker = quad(
+            strip_width + MARKER_SIZE,
+            MARKER_SIZE / 2.,
+            None,
+            MARKER_LIGHT,
+            metadata.transform
+                * Translation3::new(
+                    strip_x - half,
+                    bottom + self.hsv.0 * size - MARKER_SIZE / 4.,
+                    0.01,
+                ),
+        );
+
+        let mut list = DrawList::new();
+        list.commands.push(square);
+        list.commands.push(strip);
+        list.commands.push(square_marker);
+        list.commands.push(strip_marker);
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let mut distances = self
+            .content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect::<Vec<_>>();
+
+        // The square and the strip
+        let bottom = self.fields_height(metadata.size);
+        let (width, top) = (metadata.size.0, metadata.size.1);
+        let points = [
+            Point3::new(0., bottom, 0.),
+            Point3::new(width, bottom, 0.),
+            Point3::new(0., top, 0.),
+            Point3::new(width, top, 0.),
+        ];
+        if let Some(d) = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        {
+            distances.push((d, self_node));
+        }
+        distances
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                let position = match metadata.pointer_position() {
+                    Some(position) => position,
+                    None => return EventResponse::Pass,
+                };
+                let drag = if position.0 <= self.size {
+                    Drag::Square
+                } else if position.0 >= self.size + WIDGET_SEPARATOR {
+                    Drag::HueStrip
+                } else {
+                    return EventResponse::Pass;
+                };
+                self.drag = Some(drag);
+                self.pick(metadata, drag, position);
+                metadata.capture_pointer();
+                EventResponse::Registered
+            }
+            Event::MouseMoved(_, _) if metadata.has_pointer_capture() => {
+                if let (Some(drag), Some(position)) = (self.drag, metadata.pointer_position()) {
+                    self.pick(metadata, drag, position);
+                }
+                EventResponse::Registered
+            }
+            Event::MouseButtonReleased(MouseButton::Left)
+            | Event::MouseButtonReleased(MouseButton::Touch)
+                if metadata.has_pointer_capture() =>
+            {
+                self.drag = None;
+                metadata.release_pointer();
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The swatch of a compact `ColorPickerBuilder`, opening the full picker when clicked
+pub struct ColorSwatch {
+    color: (f32, f32, f32, f32),
+    font_size: f32,
+}
+
+impl WidgetBuilder for ColorSwatch {
+    type AchievedType = ColorSwatch;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        self
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+impl WidgetLogic for ColorSwatch {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let height = self.font_size + 2. * PADDING;
+        let size = (2. * height, height);
+        let status = |size: f32, available: Option<f32>| match available {
+            Some(available) if size > available => LayoutStatus::Inconsistencies,
+            _ => LayoutStatus::Ok,
+        };
+        LayoutResponse {
+            size,
+            status: (
+                status(size.0, query.available_space.0),
+                status(size.1, query.available_space.1),
+            ),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let (r, g, b, _) = self.color;
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, BORDER, metadata.transform));
+        list.commands.push(quad(
+            size.0 - 2. * PADDING,
+            size.1 - 2. * PADDING,
+            None,
+            (r, g, b, 1.),
+            metadata.transform * Translation3::new(PADDING, PADDING, 0.01),
+        ));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if metadata.is_popup_open() {
+                    metadata.close_popup();
+                } else {
+                    metadata.open_popup();
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The width of the labels of the rows of fields
+fn label_width(font: &Rc<RefCell<dyn FontAtlas>>, font_size: f32) -> f32 {
+    let font = font.borrow();
+    ["RGB", "HSV", "Hex"]
+        .iter()
+        .map(|label| font.size_of(label, font_size).0)
+        .fold(0., f32::max)
+}
+
+/// The size of the full picker, as computed by its layout
+fn picker_size(font: &Rc<RefCell<dyn FontAtlas>>, font_size: f32, size: f32) -> (f32, f32) {
+    let row = (font_size + 2. * PADDING).max(font.borrow().size_of("RGB", font_size).1);
+    (
+        size * (1. + STRIP_WIDTH) + WIDGET_SEPARATOR,
+        size + 3. * (row + WIDGET_SEPARATOR),
+    )
+}
+
+/// Converts a color from HSV to RGB, all components being between 0 and 1
+pub fn hsv_to_rgb((h, s, v): (f32, f32, f32)) -> (f32, f32, f32) {
+    let sector = (h * 6.).floor();
+    let f = h * 6. - sector;
+    let (p, q, t) = (v * (1. - s), v * (1. - s * f), v * (1. - s * (1. - f)));
+    match sector as i32 % 6 {
+        0 => (v, t, p),
+        1 => (q, v, p),
+        2 => (p, v, t),
+        3 => (p, q, v),
+        4 => (t, p, v),
+        _ => (v, p, q),
+    }
+}
+
+/// Converts a color from RGB to HSV, all components being between 0 and 1.
+/// The hue of grays is 0
+pub fn rgb_to_hsv((r, g, b): (f32, f32, f32)) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let s = if max > 0. { delta / max } else { 0. };
+    let h = if delta == 0. {
+        0.
+    } else if max == r {
+        ((g - b) / delta).rem_euclid(6.) / 6.
+    } else if max == g {
+        ((b - r) / delta + 2.) / 6.
+    } else {
+        ((r - g) / delta + 4.) / 6.
+    };
+    (h, s, max)
+}
+
+/// Formats a color like `#ff8000`
+pub fn to_hex((r, g, b): (f32, f32, f32)) -> String {
+    let byte = |component: f32| (component.clamp(0., 1.) * 255.).round() as u8;
+    format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b))
+}
+
+/// Parses a color formatted like `#ff8000`, the `#` being optional
+pub fn from_hex(text: &str) -> Option<(f32, f32, f32)> {
+    let digits = text.trim().trim_start_matches('#');
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let component = |index: usize| {
+        u8::from_str_radix(&digits[index..index + 2], 16)
+            .ok()
+            .map(|byte| byte as f32 / 255.)
+    };
+    Some((component(0)?, component(2)?, component(4)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+    use crate::widget::{ModalBuilder, ModalResponse};
+
+    #[test]
+    fn conversions() {
+        let close = |(r1, g1, b1): (f32, f32, f32), (r2, g2, b2): (f32, f32, f32)| {
+            (r1 - r2).abs() < 1e-4 && (g1 - g2).abs() < 1e-4 && (b1 - b2).abs() < 1e-4
+        };
+        for &rgb in &[
+            (1., 0., 0.),
+            (0.2, 0.6, 0.4),
+            (0.5, 0.5, 0.5),
+            (0.1, 0.3, 0.9),
+        ] {
+            assert!(close(hsv_to_rgb(rgb_to_hsv(rgb)), rgb));
+        }
+        assert!(close(rgb_to_hsv((0., 1., 0.)), (1. / 3., 1., 1.)));
+        assert_eq!(to_hex((1., 0.5, 0.)), "#ff8000");
+        assert_eq!(from_hex("#FF8000"), Some((1., 128. / 255., 0.)));
+        assert_eq!(from_hex("ff80"), None);
+    }
+
+    #[test]
+    fn pick_with_pointer() {
+        let font = mock_font();
+        let mut color = (1., 0., 0., 0.5);
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, color: &mut (f32, f32, f32, f32)| {
+            ui.new_frame();
+            let mut changed = false;
+            WindowBuilder::new(|window| {
+                changed = ColorPickerBuilder::new(color, &font).build(loc!(), window);
+            })
+            .size((12., 16.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            changed
+        };
+        let press = |ui: &mut Interface, x: f32, y: f32| {
+            press_at(ui, x, y);
+            ui.register_event(Event::MouseButtonReleased(MouseButton::Left), None);
+        };
+
+        // The square goes from x = -6 to 2 and from y = 0 to 8, the strip from x = 2.5 to 3.5
+        assert!(!build(&mut ui, &mut color));
+        press(&mut ui, -2., 4.);
+        assert!(build(&mut ui, &mut color));
+        assert_eq!(color, (0.5, 0.25, 0.25, 0.5));
+
+        press(&mut ui, 3., 8. / 3.);
+        assert!(build(&mut ui, &mut color));
+        let (r, g, b, _) = color;
+        assert!((r - 0.25).abs() < 1e-3 && (g - 0.5).abs() < 1e-3 && (b - 0.25).abs() < 1e-3);
+
+        // The hue is kept when the color becomes black
+        color = (0., 0., 0., 0.5);
+        assert!(!build(&mut ui, &mut color));
+        press(&mut ui, 2., 8.);
+        assert!(build(&mut ui, &mut color));
+        let (r, g, b, _) = color;
+        assert!(r.abs() < 1e-3 && (g - 1.).abs() < 1e-3 && b.abs() < 1e-3);
+    }
+
+    #[test]
+    fn compact_popup() {
+        let font = mock_font();
+        let mut color = (1., 0., 0., 1.);
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, color: &mut (f32, f32, f32, f32)| {
+            build_in_window(ui, |window| {
+                ColorPickerBuilder::new(color, &font)
+                    .compact()
+                    .build(loc!(), window);
+            });
+            ui.draw().list.last().unwrap().list.len()
+        };
+
+        // The swatch goes from x = -5 to -2.2 and from y = 3.6 to 5
+        assert_eq!(build(&mut ui, &mut color), 0);
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui, &mut color), 1);
+        assert_eq!(build(&mut ui, &mut color), 1);
+        assert_eq!(color, (1., 0., 0., 1.));
+    }
+
+    #[test]
+    fn compact_popup_in_modal() {
+        let font = mock_font();
+        let mut color = (1., 0., 0., 1.);
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, color: &mut (f32, f32, f32, f32)| {
+            ui.new_frame();
+            let mut changed = false;
+            ModalBuilder::new(|modal| {
+                changed = ColorPickerBuilder::new(color, &font)
+                    .compact()
+                    .build(loc!(), modal);
+                None::<ModalResponse<()>>
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.modals);
+            ui.end_frame();
+            ui.generate_layout();
+            changed
+        };
+
+        // The popup opened from a modal is not blocked by it. Its top left corner is
+        // at the bottom left one of the swatch, and its square goes from x = -5 to 3
+        // and from y = -4.4 to 3.6
+        build(&mut ui, &mut color);
+        press_at(&mut ui, -4., 4.3);
+        build(&mut ui, &mut color);
+        press_at(&mut ui, -1., -0.4);
+        ui.register_event(Event::MouseButtonReleased(MouseButton::Left), None);
+        assert!(build(&mut ui, &mut color));
+        assert_eq!(color, (0.5, 0.25, 0.25, 1.));
+    }
+}
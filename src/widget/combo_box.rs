@@ -0,0 +1,360 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Translation3, Vector2, Vector3};
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+use crate::widget::{Selectable, SelectableBuilder, WindowBuilder};
+
+/// A drop-down list, to choose one of several options.
+///
+/// Clicking the box opens the list of options in a popup, in front of its window.
+/// Clicking an option selects it, and clicking anywhere else closes the list.
+/// `build` returns whether the value was changed by the user.
+pub struct ComboBoxBuilder<'a, T: PartialEq> {
+    value: Option<&'a mut T>,
+    options: Vec<(T, String)>,
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    width: f32,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a, T: PartialEq> ComboBoxBuilder<'a, T> {
+    pub fn new(value: &'a mut T, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            value: Some(value),
+            options: Vec::new(),
+            label: String::new(),
+            font: font.clone(),
+            font_size: 1.,
+            width: 8.,
+            color: BACKGROUND,
+            text_color: TEXT,
+        }
+    }
+
+    /// Adds an option to the list, displayed with the given label
+    pub fn option(mut self, value: T, label: String) -> Self {
+        self.options.push((value, label));
+        self
+    }
+
+    /// Sets the width of the box and of its list
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: PartialEq> WidgetBuilder for ComboBoxBuilder<'a, T> {
+    type AchievedType = ComboBox;
+    type UpdateFeedback = ();
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.label = self.label;
+        widget.font_size = self.font_size;
+        widget.width = self.width;
+        widget.color = self.color;
+        widget.text_color = self.text_color;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        ComboBox {
+            label: self.label,
+            font: self.font,
+            font_size: self.font_size,
+            width: self.width,
+            color: self.color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let value = self.value.take().unwrap();
+        let mut options = std::mem::take(&mut self.options);
+        self.label = options
+            .iter()
+            .find(|(option, _)| option == value)
+            .map(|(_, label)| label.clone())
+            .unwrap_or_default();
+        let (font, font_size, width) = (self.font.clone(), self.font_size, self.width);
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut popup = None;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, _| {
+            if metadata.is_popup_open() {
+                popup = metadata
+                    .popup_layer()
+                    .map(|layer| (layer, metadata.global_transform()));
+            }
+        });
+
+        let mut changed = false;
+        if let Some((layer, transform)) = popup {
+            let count = options.len() as f32;
+            let row = font_size + 2. * PADDING;
+            let height = count * row + (count - 1.).max(0.) * WIDGET_SEPARATOR;
+            WindowBuilder::new(|popup| {
+                for (index, (option, label)) in options.drain(..).enumerate() {
+                    let id = ComponentId::new_biased::<Selectable>(loc, index as UniqueId);
+                    let selectable =
+                        SelectableBuilder::single(label, value, option, &font).font_size(font_size);
+                    let (_, clicked) = popup.query::<Selectable>(id).update(selectable);
+                    changed |= clicked;
+                }
+            })
+            .size((width, height))
+            .transform(transform * Translation3::new(0., -height, POPUP_DEPTH))
+            .popup_of(&node_ref)
+            .build(loc, &layer);
+
+            if changed {
+                node_ref
+                    .apply_to_widget::<Self::AchievedType, _>(|metadata, _| metadata.close_popup());
+            }
+        }
+        changed
+    }
+}
+
+/// The widget behind a `ComboBoxBuilder`, displaying the option selected
+pub struct ComboBox {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    width: f32,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+/// Distance between the popup and the box, towards the viewer
+const POPUP_DEPTH: f32 = 0.1;
+
+impl WidgetLogic for ComboBox {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let mut width = self.width;
+        let mut height = self.font_size + 2. * PADDING;
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        if width > available_width {
+            width = available_width;
+        }
+        if height > available_height {
+            height = available_height;
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+
+        // A small triangle pointing down, on the right of the box
+        let arrow = {
+            let half = self.font_size / 4.;
+            let (x, y) = (size.0 - PADDING - half, size.1 / 2.);
+            let mut uniforms = Uniforms::new();
+            uniforms.model_matrix = metadata.transform.to_homogeneous();
+            let vertex = |x: f32, y: f32| Vertex {
+                position: Vector3::new(x, y, 0.01),
+                color: self.text_color,
+                tex_uv: Vector2::new(0., 0.),
+            };
+            DrawCommand {
+                vertex_buffer: vec![
+                    vertex(x - half, y + half / 2.),
+                    vertex(x + half, y + half / 2.),
+                    vertex(x, y - half / 2.),
+                ],
+                index_buffer: vec![0, 1, 2],
+                draw_mode: DrawMode::Triangles,
+                uniforms,
+            }
+        };
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+        list.commands.push(arrow);
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (metadata.transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+        ));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if metadata.is_popup_open() {
+                    metadata.close_popup();
+                } else {
+                    metadata.open_popup();
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+    use crate::widget::{ModalBuilder, ModalResponse};
+
+    #[test]
+    fn select_in_popup() {
+        let font = mock_font();
+        let mut value = 0;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, value: &mut i32| {
+            let mut changed = false;
+            build_in_window(ui, |window| {
+                changed = ComboBoxBuilder::new(value, &font)
+                    .option(0, "Zero".to_string())
+                    .option(1, "One".to_string())
+                    .option(2, "Two".to_string())
+                    .build(loc!(), window);
+            });
+            changed
+        };
+
+        build(&mut ui, &mut value);
+        // The box goes from y = 3.6 to 5, and the list is below it, with rows 1.4 high
+        // separated by 0.5. The second option is between y = 0.3 and 1.7
+        press_at(&mut ui, -4., 4.3);
+        assert!(!build(&mut ui, &mut value));
+        build(&mut ui, &mut value);
+        press_at(&mut ui, -4., 1.);
+        assert!(build(&mut ui, &mut value));
+        assert_eq!(value, 1);
+
+        // Closed once an option was chosen, so the same position is not an option anymore
+        build(&mut ui, &mut value);
+        press_at(&mut ui, -4., 1.);
+        assert!(!build(&mut ui, &mut value));
+        assert_eq!(value, 1);
+
+        // Clicking outside of an open list closes it
+        press_at(&mut ui, -4., 4.3);
+        build(&mut ui, &mut value);
+        assert!(matches!(
+            press_at(&mut ui, 20., 20.),
+            EventResponse::Registered
+        ));
+        build(&mut ui, &mut value);
+        press_at(&mut ui, -4., 1.);
+        assert!(!build(&mut ui, &mut value));
+        assert_eq!(value, 1);
+    }
+
+    #[test]
+    fn select_in_modal() {
+        let font = mock_font();
+        let mut value = 0;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, value: &mut i32| {
+            ui.new_frame();
+            let mut changed = false;
+            ModalBuilder::new(|modal| {
+                changed = ComboBoxBuilder::new(value, &font)
+                    .option(0, "Zero".to_string())
+                    .option(1, "One".to_string())
+                    .build(loc!(), modal);
+                None::<ModalResponse<()>>
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.modals);
+            ui.end_frame();
+            ui.generate_layout();
+            changed
+        };
+
+        // The list opened from a modal is not blocked by it, like the modal itself
+        build(&mut ui, &mut value);
+        press_at(&mut ui, -4., 4.3);
+        build(&mut ui, &mut value);
+        press_at(&mut ui, -4., 1.);
+        assert!(build(&mut ui, &mut value));
+        assert_eq!(value, 1);
+    }
+}
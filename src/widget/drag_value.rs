@@ -0,0 +1,558 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use nalgebra::{Point3, Translation3};
+
+use crate::core::*;
+use crate::widget::input::InputValue;
+use crate::widget::numeric::*;
+
+/// A field editing a number by dragging the pointer horizontally over it.
+///
+/// A double-click turns it into a text field, to type the value directly.
+/// Enter or leaving the field validates the new value, Escape cancels it.
+pub struct DragValueBuilder<'a, T: Numeric + InputValue> {
+    value: &'a mut T,
+    range: NumericRange,
+    speed: f64,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    width: f32,
+    precision: usize,
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND_FOCUSED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTION: (f32, f32, f32, f32) = (0.349, 0.475, 0.765, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const CARET_WIDTH: f32 = 0.05;
+
+impl<'a, T: Numeric + InputValue> DragValueBuilder<'a, T> {
+    pub fn new(value: &'a mut T, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        let mut range = NumericRange::unbounded();
+        if T::INTEGER {
+            range.step = Some(1.);
+        }
+        Self {
+            value,
+            range,
+            speed: if T::INTEGER { 1. } else { 0.1 },
+            font: font.clone(),
+            font_size: 1.,
+            width: 4.,
+            precision: if T::INTEGER { 0 } else { 2 },
+            text_color: TEXT,
+        }
+    }
+
+    /// Sets the bounds of the value, which is unbounded by default
+    pub fn range(mut self, min: T, max: T) -> Self {
+        let range = NumericRange::new(min.to_f64(), max.to_f64());
+        self.range.min = range.min;
+        self.range.max = range.max;
+        self
+    }
+
+    /// Sets the interval between two possible values
+    pub fn step(mut self, step: T) -> Self {
+        self.range.step = Some(step.to_f64());
+        self
+    }
+
+    /// Makes dragging multiply the value instead of adding to it.
+    /// This has no effect while the value is not strictly positive
+    pub fn logarithmic(mut self) -> Self {
+        self.range.logarithmic = true;
+        self
+    }
+
+    /// Sets how much the value changes when the pointer moves by one unit.
+    /// When logarithmic, the value is multiplied by `exp(speed)` instead
+    pub fn speed(self, speed: f64) -> Self {
+        Self { speed, ..self }
+    }
+
+    /// Sets the number of decimals displayed
+    pub fn precision(self, precision: usize) -> Self {
+        Self { precision, ..self }
+    }
+
+    /// Sets the width of the field, when it is not maximized by its parent
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: Numeric + InputValue> WidgetBuilder for DragValueBuilder<'a, T> {
+    type AchievedType = DragValue;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(self, metadata: &NodeMetadata, widget: &mut Self::AchievedType) -> bool {
+        let mut value = widget.pending.take();
+
+        if widget.edit.is_some() && (widget.committed || !metadata.is_focused()) {
+            if let Some(typed) = widget
+                .edit
+                .take()
+                .and_then(|edit| T::from_text(edit.text()))
+            {
+                value = Some(typed.to_f64());
+            }
+        }
+        widget.committed = false;
+
+        let mut changed = false;
+        if let Some(value) = value {
+            let value = T::from_f64(self.range.snap(value));
+            if value != *self.value {
+                *self.value = value;
+                changed = true;
+            }
+        }
+
+        widget.value = self.value.to_f64();
+        widget.range = self.range;
+        widget.speed = self.speed;
+        widget.font_size = self.font_size;
+        widget.width = self.width;
+        widget.precision = self.precision;
+        widget.text_color = self.text_color;
+        widget.accepts = T::accepts;
+        changed
+    }
+
+    fn create(self) -> Self::AchievedType {
+        DragValue {
+            value: self.value.to_f64(),
+            pending: None,
+            range: self.range,
+            speed: self.speed,
+            font: self.font,
+            font_size: self.font_size,
+            width: self.width,
+            precision: self.precision,
+            text_color: self.text_color,
+            accepts: T::accepts,
+            drag: None,
+            last_click: None,
+            edit: None,
+            committed: false,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
+/// A drag in progress: where it started, and whether the pointer moved enough
+/// for it not to be a simple click
+struct Drag {
+    start_x: f32,
+    start_value: f64,
+    moved: bool,
+}
+
+/// The widget behind a `DragValueBuilder`
+pub struct DragValue {
+    value: f64,
+    /// The value chosen by dragging, not yet written back
+    pending: Option<f64>,
+    range: NumericRange,
+    speed: f64,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    width: f32,
+    precision: usize,
+    text_color: (f32, f32, f32, f32),
+    accepts: fn(&str) -> bool,
+    drag: Option<Drag>,
+    /// The time of the frame of the last click, in the clock of the interface
+    last_click: Option<Duration>,
+    /// The text being typed, if the field is being edited
+    edit: Option<TextEditState>,
+    /// Whether the typed text was validated since the last update
+    committed: bool,
+}
+
+const PADDING: f32 = 0.2;
+/// Distance the pointer has to move before a click becomes a drag
+const DRAG_THRESHOLD: f32 = 0.05;
+/// Maximum delay between the two clicks of a double-click
+const DOUBLE_CLICK_DELAY: Duration = Duration::from_millis(400);
+
+impl DragValue {
+    fn text(&self) -> String {
+        format!("{:.*}", self.precision, self.value)
+    }
+
+    fn start_editing(&mut self, metadata: &mut NodeMetadata) {
+        let mut edit = TextEditState::new(self.text());
+        edit.select_all();
+        self.edit = Some(edit);
+        metadata.request_focus();
+    }
+
+    fn drag_to(&mut self, x: f32) {
+        if let Some(drag) = self.drag.as_mut() {
+            let dx = x - drag.start_x;
+            drag.moved |= dx.abs() > DRAG_THRESHOLD;
+            if !drag.moved {
+                return;
+            }
+            let value = if self.range.logarithmic && drag.start_value > 0. {
+                drag.start_value * (self.speed * dx as f64).exp()
+            } else {
+                drag.start_value + self.speed * dx as f64
+            };
+            let value = self.range.snap(value);
+            self.value = value;
+            self.pending = Some(value);
+        }
+    }
+
+    /// Handles a click, either to start a drag or to move the caret while editing
+    fn press(&mut self, metadata: &mut NodeMetadata) {
+        let position = metadata.pointer_position();
+        let shift = metadata.modifiers().shift;
+        match (self.edit.as_mut(), position) {
+            (Some(edit), Some((x, _))) => {
+                let positions = character_positions(edit.text(), &self.font, self.font_size);
+                let index = caret_index_at(&positions, self.font_size, (x - PADDING, 0.));
+                edit.set_caret(index, shift);
+            }
+            (Some(_), None) => {}
+            (None, position) => {
+                metadata.capture_pointer();
+                self.drag = Some(Drag {
+                    start_x: position.map(|(x, _)| x).unwrap_or(0.),
+                    start_value: self.value,
+                    moved: false,
+                });
+            }
+        }
+    }
+
+    /// Ends a drag, and starts editing on the second of two quick clicks
+    fn release(&mut self, metadata: &mut NodeMetadata) {
+        metadata.release_pointer();
+        if let Some(drag) = self.drag.take() {
+            if drag.moved {
+                self.last_click = None;
+                return;
+            }
+            let now = metadata.time();
+            match self.last_click.take() {
+                Some(last) if now.checked_sub(last).unwrap_or_default() <= DOUBLE_CLICK_DELAY => {
+                    self.start_editing(metadata)
+                }
+                _ => self.last_click = Some(now),
+            }
+        }
+    }
+
+    fn insert(&mut self, string: &str) {
+        if let Some(edit) = self.edit.as_mut() {
+            if (self.accepts)(edit.preview_insert(string).as_str()) {
+                edit.insert(string);
+            }
+        }
+    }
+}
+
+impl WidgetLogic for DragValue {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let mut width = self.width;
+        let mut height = self.font_size + 2. * PADDING;
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        match query.objectives.0 {
+            Objective::Maximize => {
+                width = available_width;
+            }
+            Objective::Minimize | Objective::None => {
+                if width > available_width {
+                    width = available_width;
+                }
+            }
+        }
+        if height > available_height {
+            height = available_height;
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let editing = self.edit.is_some() && metadata.is_focused();
+
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            size.0,
+            size.1,
+            None,
+            if editing || metadata.has_pointer_capture() {
+                BACKGROUND_FOCUSED
+            } else {
+                BACKGROUND
+            },
+            metadata.transform,
+        ));
+
+        match self.edit.as_ref() {
+            Some(edit) if editing => {
+                let origin = metadata.transform * Translation3::new(PADDING, PADDING, 0.);
+                let positions = character_positions(edit.text(), &self.font, self.font_size);
+                if let Some((start, end)) = edit.selection() {
+                    let selection: Vec<(f32, f32, f32, f32)> = positions[start..end]
+                        .iter()
+                        .map(|position| (position.x, position.y, position.width, self.font_size))
+                        .collect();
+                    list.commands.push(rectangles(
+                        &selection,
+                        SELECTION,
+                        origin * Translation3::new(0., 0., 0.005),
+                    ));
+                }
+                let caret = positions[edit.caret()];
+                list.commands.push(quad(
+                    CARET_WIDTH,
+                    self.font_size,
+                    None,
+                    self.text_color,
+                    origin * Translation3::new(caret.x, caret.y, 0.015),
+                ));
+                list.commands.push(draw_text(
+                    edit.text(),
+                    &self.font,
+                    self.font_size,
+                    self.text_color,
+                    (origin * Translation3::new(0., 0., 0.01)).to_homogeneous(),
+                ));
+            }
+            _ => {
+                let text = self.text();
+                let (text_width, _) = self.font.borrow().size_of(text.as_str(), self.font_size);
+                let position = Translation3::new((size.0 - text_width) / 2., PADDING, 0.01);
+                list.commands.push(draw_text(
+                    text.as_str(),
+                    &self.font,
+                    self.font_size,
+                    self.text_color,
+                    (metadata.transform * position).to_homogeneous(),
+                ));
+            }
+        }
+
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        let Modifiers { shift, ctrl, .. } = metadata.modifiers();
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                self.press(metadata);
+                EventResponse::Registered
+            }
+            Event::MouseMoved(_, _) if metadata.has_pointer_capture() => {
+                if let Some((x, _)) = metadata.pointer_position() {
+                    self.drag_to(x);
+                }
+                EventResponse::Registered
+            }
+            Event::MouseButtonReleased(MouseButton::Left)
+            | Event::MouseButtonReleased(MouseButton::Touch)
+                if metadata.has_pointer_capture() =>
+            {
+                self.release(metadata);
+                EventResponse::Registered
+            }
+            Event::KeyPressed(key) if self.edit.is_some() => {
+                match key {
+                    Key::Return | Key::NumpadEnter => {
+                        self.committed = true;
+                        metadata.release_focus();
+                    }
+                    Key::Escape => {
+                        self.edit = None;
+                        metadata.release_focus();
+                    }
+                    Key::Backspace => self.edit.as_mut().unwrap().backspace(ctrl),
+                    Key::Delete => self.edit.as_mut().unwrap().delete(ctrl),
+                    Key::ArrowLeft => self.edit.as_mut().unwrap().move_left(shift, ctrl),
+                    Key::ArrowRight => self.edit.as_mut().unwrap().move_right(shift, ctrl),
+                    Key::Home => self.edit.as_mut().unwrap().move_home(shift, true),
+                    Key::End => self.edit.as_mut().unwrap().move_end(shift, true),
+                    Key::A if ctrl => self.edit.as_mut().unwrap().select_all(),
+                    _ => return EventResponse::Pass,
+                }
+                EventResponse::Registered
+            }
+            Event::Character(c) if self.edit.is_some() => {
+                if !c.is_control() {
+                    self.insert(c.encode_utf8(&mut [0; 4]));
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+    use crate::widget::WindowBuilder;
+
+    fn build(ui: &mut Interface, font: &Rc<RefCell<dyn FontAtlas>>, value: &mut f32) -> bool {
+        let mut changed = false;
+        build_in_window(ui, |window| {
+            changed = DragValueBuilder::new(value, font)
+                .range(0., 10.)
+                .speed(1.)
+                .build(loc!(), window);
+        });
+        changed
+    }
+
+    fn click(ui: &mut Interface, x: f32, y: f32) {
+        press_at(ui, x, y);
+        ui.register_event(
+            Event::MouseButtonReleased(MouseButton::Left),
+            Some(&ray_at(x, y)),
+        );
+    }
+
+    #[test]
+    fn drag_and_type() {
+        let font = mock_font();
+        let mut value = 5f32;
+        let mut ui = Interface::new();
+        build(&mut ui, &font, &mut value);
+
+        // Dragging by 2 units to the right, then far to the left, out of the range
+        let (x, y) = (-4., 4.5);
+        press_at(&mut ui, x, y);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(x + 2., y)));
+        assert!(build(&mut ui, &font, &mut value));
+        assert!((value - 7.).abs() < 1e-5);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(x - 20., y)));
+        ui.register_event(
+            Event::MouseButtonReleased(MouseButton::Left),
+            Some(&ray_at(x - 20., y)),
+        );
+        assert!(build(&mut ui, &font, &mut value));
+        assert!(value.abs() < f32::EPSILON);
+
+        // A double-click selects the whole text, which is replaced by typing
+        click(&mut ui, x, y);
+        click(&mut ui, x, y);
+        for c in "3.5".chars() {
+            ui.register_event(Event::Character(c), None);
+        }
+        assert!(!build(&mut ui, &font, &mut value));
+        ui.register_event(Event::KeyPressed(Key::Return), None);
+        assert!(build(&mut ui, &font, &mut value));
+        assert!((value - 3.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn double_click_delay() {
+        let font = mock_font();
+        let mut value = 5f32;
+        let mut ui = Interface::new();
+        // Builds a frame at the given time, and returns the value
+        let mut build = |ui: &mut Interface, millis: u64| {
+            ui.new_frame_at(Duration::from_millis(millis));
+            WindowBuilder::new(|window| {
+                DragValueBuilder::new(&mut value, &font).build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            value
+        };
+        let type_two = |ui: &mut Interface| {
+            ui.register_event(Event::Character('2'), None);
+            ui.register_event(Event::KeyPressed(Key::Return), None);
+        };
+
+        // Two clicks too far apart in the time of the interface do not start editing
+        build(&mut ui, 0);
+        click(&mut ui, -4., 4.5);
+        build(&mut ui, 600);
+        click(&mut ui, -4., 4.5);
+        type_two(&mut ui);
+        assert!((build(&mut ui, 700) - 5.).abs() < f32::EPSILON);
+
+        // The third click comes soon enough after the second one
+        click(&mut ui, -4., 4.5);
+        type_two(&mut ui);
+        assert!((build(&mut ui, 800) - 2.).abs() < f32::EPSILON);
+    }
+}
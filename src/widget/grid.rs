@@ -0,0 +1,554 @@
+use nalgebra::*;
+
+use crate::core::*;
+use crate::loc;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// How large a column or a row of a grid is
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GridTrack {
+    Fixed(f32),
+    /// As large as the largest of its widgets
+    Auto,
+    /// A share of the space left by the other tracks, proportional to the given weight.
+    /// Without a limit on the space, it is as large as its widgets like an `Auto` track
+    Fraction(f32),
+}
+
+/// Builds the widgets of a grid in its node
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// The builder for a grid, placing its widgets in columns and rows, so that the widgets
+/// of a column are aligned whatever the content of the other rows.
+///
+/// The widgets are placed in order, from left to right, starting a new row once all the
+/// columns are filled. A widget wrapped in a `GridCellBuilder` is placed in the given cell
+/// instead, and can span several columns and rows; the next widgets are placed after it.
+///
+/// Each widget takes the width of its cell, and is centered vertically in it. Rows are `Auto`
+/// unless given otherwise. Fractional columns share the width given by the parent of the grid,
+/// so that a grid with such a column takes all this width, and the same goes for the rows.
+pub struct GridBuilder<'a> {
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    spacing: (f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a> GridBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(columns: Vec<GridTrack>, generator: F) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+            spacing: (WIDGET_SEPARATOR, WIDGET_SEPARATOR),
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the size of the first rows, the next ones being `Auto`
+    pub fn rows(self, rows: Vec<GridTrack>) -> Self {
+        Self { rows, ..self }
+    }
+
+    /// Sets the space between two columns and between two rows
+    pub fn spacing(self, spacing: (f32, f32)) -> Self {
+        Self { spacing, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for GridBuilder<'a> {
+    type AchievedType = Grid;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.columns = self.columns;
+        widget.rows = self.rows;
+        widget.spacing = self.spacing;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Grid {
+            columns: self.columns,
+            rows: self.rows,
+            spacing: self.spacing,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+    }
+}
+
+/// The cell of a widget in a grid: its first row and column, and how many it spans
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Placement {
+    row: usize,
+    column: usize,
+    rows: usize,
+    columns: usize,
+}
+
+/// The widget behind a `GridBuilder`
+pub struct Grid {
+    columns: Vec<GridTrack>,
+    rows: Vec<GridTrack>,
+    spacing: (f32, f32),
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl Grid {
+    /// Finds the cell of each widget, with the number of columns of the grid
+    fn placements(&self, columns: usize) -> Vec<Placement> {
+        let (mut row, mut column) = (0, 0);
+        self.content
+            .iter()
+            .map(|node| {
+                let mut placement = None;
+                if node.holds::<GridCell>() {
+                    node.apply_to_widget::<GridCell, _>(|_, cell| placement = Some(cell.placement));
+                }
+                let placement = match placement {
+                    Some(placement) => {
+                        let column = placement.column.min(columns - 1);
+                        Placement {
+                            column,
+                            columns: placement.columns.clamp(1, columns - column),
+                            rows: placement.rows.max(1),
+                            ..placement
+                        }
+                    }
+                    None => {
+                        if column >= columns {
+                            row += 1;
+                            column = 0;
+                        }
+                        Placement {
+                            row,
+                            column,
+                            rows: 1,
+                            columns: 1,
+                        }
+                    }
+                };
+                row = placement.row;
+                column = placement.column + placement.columns;
+                placement
+            })
+            .collect()
+    }
+}
+
+/// Computes the sizes of the tracks of one direction of a grid, from the first track
+/// and the number of tracks spanned by each widget, and its minimal size
+fn track_sizes(
+    tracks: &[GridTrack],
+    spans: &[(usize, usize, f32)],
+    available: Option<f32>,
+    spacing: f32,
+) -> Vec<f32> {
+    // Without a limit, fractional tracks fit their widgets
+    let fits = |track: &GridTrack| match track {
+        GridTrack::Fixed(_) => false,
+        GridTrack::Auto => true,
+        GridTrack::Fraction(_) => available.is_none(),
+    };
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .map(|track| match track {
+            GridTrack::Fixed(size) => *size,
+            _ => 0.,
+        })
+        .collect();
+
+    for &(start, _, size) in spans.iter().filter(|(_, span, _)| *span == 1) {
+        if fits(&tracks[start]) {
+            sizes[start] = sizes[start].max(size);
+        }
+    }
+
+    // The widgets spanning several tracks widen the ones fitting their widgets if needed,
+    // unless they span a fractional track taking the space left
+    for &(start, span, size) in spans.iter().filter(|(_, span, _)| *span > 1) {
+        let range = start..start + span;
+        let fractional = tracks[range.clone()]
+            .iter()
+            .any(|track| matches!(track, GridTrack::Fraction(_)));
+        if fractional && available.is_some() {
+            continue;
+        }
+        let current = sizes[range.clone()].iter().sum::<f32>() + spacing * (span - 1) as f32;
+        let fitting: Vec<usize> = range.filter(|index| fits(&tracks[*index])).collect();
+        if size > current && !fitting.is_empty() {
+            let extra = (size - current) / fitting.len() as f32;
+            fitting.iter().for_each(|index| sizes[*index] += extra);
+        }
+    }
+
+    if let Some(available) = available {
+        let weights = tracks
+            .iter()
+            .map(|track| match track {
+                GridTrack::Fraction(weight) => weight.max(0.),
+                _ => 0.,
+            })
+            .collect::<Vec<_>>();
+        let total = weights.iter().sum::<f32>();
+        if total > 0. {
+            let used = sizes.iter().sum::<f32>() + spacing * tracks.len().saturating_sub(1) as f32;
+            let left = (available - used).max(0.);
+            for (size, weight) in sizes.iter_mut().zip(weights) {
+                if weight > 0. {
+                    *size = left * weight / total;
+                }
+            }
+        }
+    }
+    sizes
+}
+
+/// The position of the start of each track, and the total size of the tracks
+fn track_offsets(sizes: &[f32], spacing: f32) -> (Vec<f32>, f32) {
+    let mut cursor = 0.;
+    let offsets = sizes
+        .iter()
+        .map(|size| {
+            let offset = cursor;
+            cursor += size + spacing;
+            offset
+        })
+        .collect();
+    (offsets, (cursor - spacing).max(0.))
+}
+
+impl WidgetLogic for Grid {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let columns = if self.columns.is_empty() {
+            vec![GridTrack::Auto]
+        } else {
+            self.columns.clone()
+        };
+        let placements = self.placements(columns.len());
+        let row_count = placements
+            .iter()
+            .map(|placement| placement.row + placement.rows)
+            .max()
+            .unwrap_or(0)
+            .max(self.rows.len());
+        let rows = (0..row_count)
+            .map(|row| self.rows.get(row).copied().unwrap_or(GridTrack::Auto))
+            .collect::<Vec<_>>();
+
+        // The widgets are first laid out to find the size of the tracks fitting them
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let sizes = self
+            .content
+            .iter()
+            .map(|node| {
+                let response = node.layout(&LayoutQuery {
+                    available_space: query.available_space,
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                });
+                status.0 = LayoutStatus::and(status.0, response.status.0);
+                status.1 = LayoutStatus::and(status.1, response.status.1);
+                response.size
+            })
+            .collect::<Vec<_>>();
+        let spans = |size: fn(&(f32, f32)) -> f32, span: fn(&Placement) -> (usize, usize)| {
+            placements
+                .iter()
+                .zip(&sizes)
+                .map(|(placement, child_size)| {
+                    let (start, count) = span(placement);
+                    (start, count, size(child_size))
+                })
+                .collect::<Vec<_>>()
+        };
+        let widths = track_sizes(
+            &columns,
+            &spans(
+                |size| size.0,
+                |placement| (placement.column, placement.columns),
+            ),
+            query.available_space.0,
+            self.spacing.0,
+        );
+        let heights = track_sizes(
+            &rows,
+            &spans(|size| size.1, |placement| (placement.row, placement.rows)),
+            query.available_space.1,
+            self.spacing.1,
+        );
+        let (x_offsets, width) = track_offsets(&widths, self.spacing.0);
+        let (y_offsets, height) = track_offsets(&heights, self.spacing.1);
+
+        // Then each widget is laid out again in its cell, taking its width,
+        // and centered vertically. The rows go from top to bottom
+        let extent = |offsets: &[f32], sizes: &[f32], start: usize, count: usize| {
+            let end = start + count - 1;
+            (offsets[start], offsets[end] + sizes[end] - offsets[start])
+        };
+        for (node, placement) in self.content.iter().zip(&placements) {
+            let (x, cell_width) = extent(&x_offsets, &widths, placement.column, placement.columns);
+            let (top, cell_height) = extent(&y_offsets, &heights, placement.row, placement.rows);
+            let response = node.layout(&LayoutQuery {
+                available_space: (Some(cell_width), Some(cell_height)),
+                objectives: (Objective::Maximize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            let y = height - top - cell_height + (cell_height - response.size.1) / 2.;
+            node.set_size(response.size);
+            node.set_transform(Similarity3::identity() * Translation3::new(x, y, 0.));
+        }
+
+        let fits = |size: f32, available: Option<f32>| match available {
+            Some(available) if size > available => LayoutStatus::Inconsistencies,
+            _ => LayoutStatus::Ok,
+        };
+        LayoutResponse {
+            size: (width, height),
+            status: (
+                LayoutStatus::and(status.0, fits(width, query.available_space.0)),
+                LayoutStatus::and(status.1, fits(height, query.available_space.1)),
+            ),
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+/// Places a widget in a given cell of a grid, spanning one column and one row by default
+pub struct GridCellBuilder<T: WidgetBuilder> {
+    placement: Placement,
+    content: Option<T>,
+}
+
+impl<T: WidgetBuilder> GridCellBuilder<T> {
+    pub fn new(row: usize, column: usize, content: T) -> Self {
+        Self {
+            placement: Placement {
+                row,
+                column,
+                rows: 1,
+                columns: 1,
+            },
+            content: Some(content),
+        }
+    }
+
+    /// Sets the number of rows and columns spanned by the widget
+    pub fn span(self, rows: usize, columns: usize) -> Self {
+        Self {
+            placement: Placement {
+                rows,
+                columns,
+                ..self.placement
+            },
+            ..self
+        }
+    }
+}
+
+impl<T: WidgetBuilder> WidgetBuilder for GridCellBuilder<T> {
+    type AchievedType = GridCell;
+    type UpdateFeedback = ();
+    type BuildFeedback = T::BuildFeedback;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.placement = self.placement;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        GridCell {
+            placement: self.placement,
+            content: None,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let content = self.content.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        content.build(loc!(), &node_ref)
+    }
+}
+
+/// The widget behind a `GridCellBuilder`, taking the size of its content
+pub struct GridCell {
+    placement: Placement,
+    content: Option<NodeReference>,
+}
+
+impl WidgetLogic for GridCell {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        match &self.content {
+            Some(other) if other.has_id(id) => WidgetQueryResult::Initialized(other.clone()),
+            _ => {
+                let node_ref = Node::new_reference(id, &metadata.ui_properties);
+                self.content = Some(node_ref.clone());
+                WidgetQueryResult::Uninitialized(node_ref)
+            }
+        }
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        match &self.content {
+            Some(node) => {
+                let response = node.layout(query);
+                node.set_size(response.size);
+                node.set_transform(Similarity3::identity());
+                response
+            }
+            None => LayoutResponse {
+                size: (0., 0.),
+                status: (LayoutStatus::Ok, LayoutStatus::Ok),
+            },
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::{Spacer, SpacerBuilder, WindowBuilder};
+
+    #[test]
+    fn spanning_widgets() {
+        let tracks = [GridTrack::Auto, GridTrack::Fixed(1.), GridTrack::Auto];
+        // The third widget spans all the tracks, and needs 4 more than the others
+        let spans = [(0, 1, 2.), (2, 1, 1.), (0, 3, 9.)];
+        assert_eq!(track_sizes(&tracks, &spans, None, 0.5), vec![4., 1., 3.]);
+
+        // The fractional tracks share the space left, and fit their widgets without a limit
+        let tracks = [
+            GridTrack::Fraction(1.),
+            GridTrack::Auto,
+            GridTrack::Fraction(3.),
+        ];
+        let spans = [(0, 1, 1.), (1, 1, 2.), (2, 1, 2.)];
+        assert_eq!(
+            track_sizes(&tracks, &spans, Some(11.), 0.5),
+            vec![2., 2., 6.]
+        );
+        assert_eq!(track_sizes(&tracks, &spans, None, 0.5), vec![1., 2., 2.]);
+    }
+
+    #[test]
+    fn aligned_columns() {
+        let mut ui = Interface::new();
+        let mut spacers = Vec::new();
+        let mut spacer = |parent: &NodeReference, loc: CodeLocation, size: (f32, f32)| {
+            let id = ComponentId::new::<Spacer>(loc);
+            let (node, _) = parent
+                .query::<Spacer>(id)
+                .update(SpacerBuilder::fixed(size));
+            spacers.push(node);
+        };
+        ui.new_frame();
+        WindowBuilder::new(|window| {
+            let columns = vec![GridTrack::Auto, GridTrack::Fraction(1.)];
+            GridBuilder::new(columns, |grid| {
+                spacer(grid, loc!(), (2., 1.));
+                spacer(grid, loc!(), (1., 1.));
+                spacer(grid, loc!(), (3., 0.5));
+                spacer(grid, loc!(), (1., 2.));
+                GridCellBuilder::new(2, 0, SpacerBuilder::fixed((4., 1.)))
+                    .span(1, 2)
+                    .build(loc!(), grid);
+                spacer(grid, loc!(), (1., 1.));
+            })
+            .build(loc!(), window);
+        })
+        .size((10., 10.))
+        .build(loc!(), &ui.root);
+        ui.end_frame();
+        let response = ui.generate_layout();
+        assert_eq!(response.status, (LayoutStatus::Ok, LayoutStatus::Ok));
+
+        // The first column is 3 wide, the second one takes the 6.5 left,
+        // and the rows are 1, 2, 1 and 1 high from the top. The last widget
+        // is placed after the one spanning the third row
+        let positions = spacers
+            .iter()
+            .map(|node| {
+                let translation = node.get_transform().isometry.translation;
+                (translation.x, translation.y)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![(0., 5.5), (3.5, 5.5), (0., 3.75), (3.5, 3.), (0., 0.)]
+        );
+    }
+}
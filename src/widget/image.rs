@@ -0,0 +1,201 @@
+use nalgebra::{Translation3, Vector2, Vector3};
+
+use crate::core::*;
+
+/// How an image fills the space given to it by the layout
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ImageFit {
+    /// The image takes the whole space, without keeping its proportions
+    Stretch,
+    /// The whole image is displayed as large as possible, keeping its proportions
+    Contain,
+    /// The image covers the whole space, keeping its proportions, and is cropped around its center
+    Cover,
+}
+
+/// A rectangle of a texture, given by its minimal and maximal texture coordinates
+type UvRect = ((f32, f32), (f32, f32));
+
+/// Displays a registered texture, with an intrinsic size that it takes when possible.
+///
+/// When its parent maximizes it, or does not leave enough space, the image is placed
+/// in the space given according to its `fit`. Only a part of the texture can be displayed,
+/// with `uv`, and its colors can be multiplied by a `tint`.
+pub struct ImageBuilder {
+    texture: TextureId,
+    size: (f32, f32),
+    fit: ImageFit,
+    uv: UvRect,
+    tint: (f32, f32, f32, f32),
+}
+
+impl ImageBuilder {
+    pub fn new(texture: TextureId, size: (f32, f32)) -> Self {
+        Self {
+            texture,
+            size,
+            fit: ImageFit::Contain,
+            uv: ((0., 0.), (1., 1.)),
+            tint: (1., 1., 1., 1.),
+        }
+    }
+
+    pub fn fit(self, fit: ImageFit) -> Self {
+        Self { fit, ..self }
+    }
+
+    /// Only displays the rectangle of the texture between the two texture coordinates,
+    /// `(0, 0)` being the bottom left corner of the texture and `(1, 1)` its top right one
+    pub fn uv(self, min: (f32, f32), max: (f32, f32)) -> Self {
+        Self {
+            uv: (min, max),
+            ..self
+        }
+    }
+
+    /// Multiplies the colors of the texture by the given color
+    pub fn tint(self, tint: (f32, f32, f32, f32)) -> Self {
+        Self { tint, ..self }
+    }
+}
+
+impl WidgetBuilder for ImageBuilder {
+    type AchievedType = Image;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self.create();
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Image {
+            texture: self.texture,
+            size: self.size,
+            fit: self.fit,
+            uv: self.uv,
+            tint: self.tint,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+/// The widget behind an `ImageBuilder`
+pub struct Image {
+    texture: TextureId,
+    size: (f32, f32),
+    fit: ImageFit,
+    uv: UvRect,
+    tint: (f32, f32, f32, f32),
+}
+
+impl Image {
+    /// Places the image in an area of the given size, according to its fit.
+    /// Returns the position and the size of the rectangle drawn, and the texture coordinates
+    /// of its corners
+    fn placement(&self, area: (f32, f32)) -> ((f32, f32), (f32, f32), UvRect) {
+        let ((u_min, v_min), (u_max, v_max)) = self.uv;
+        let scales = (area.0 / self.size.0, area.1 / self.size.1);
+        match self.fit {
+            ImageFit::Stretch => ((0., 0.), area, self.uv),
+            ImageFit::Contain => {
+                let scale = scales.0.min(scales.1);
+                let size = (self.size.0 * scale, self.size.1 * scale);
+                let position = ((area.0 - size.0) / 2., (area.1 - size.1) / 2.);
+                (position, size, self.uv)
+            }
+            ImageFit::Cover => {
+                // Only the part of the texture around its center fitting in the area is kept
+                let scale = scales.0.max(scales.1);
+                let kept = (scales.0 / scale, scales.1 / scale);
+                let crop = |min: f32, max: f32, kept: f32| {
+                    let margin = (max - min) * (1. - kept) / 2.;
+                    (min + margin, max - margin)
+                };
+                let (u_min, u_max) = crop(u_min, u_max, kept.0);
+                let (v_min, v_max) = crop(v_min, v_max, kept.1);
+                ((0., 0.), area, ((u_min, v_min), (u_max, v_max)))
+            }
+        }
+    }
+}
+
+impl WidgetLogic for Image {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        query.fit(self.size)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let (position, size, ((u_min, v_min), (u_max, v_max))) = self.placement(metadata.size);
+        let vertex = |x: f32, y: f32, u: f32, v: f32| Vertex {
+            position: Vector3::new(x, y, 0.),
+            color: self.tint,
+            tex_uv: Vector2::new(u, v),
+        };
+
+        let mut uniforms = Uniforms::new();
+        uniforms.model_matrix =
+            (metadata.transform * Translation3::new(position.0, position.1, 0.)).to_homogeneous();
+        uniforms.texture = Some(self.texture);
+
+        let mut list = DrawList::new();
+        list.commands.push(DrawCommand {
+            vertex_buffer: vec![
+                vertex(0., 0., u_min, v_min),
+                vertex(size.0, 0., u_max, v_min),
+                vertex(0., size.1, u_min, v_max),
+                vertex(size.0, size.1, u_max, v_max),
+            ],
+            index_buffer: vec![0, 1, 2, 1, 2, 3],
+            draw_mode: DrawMode::Triangles,
+            uniforms,
+        });
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fit_modes() {
+        let image = |fit: ImageFit| {
+            ImageBuilder::new(TextureId::Texture(0), (4., 2.))
+                .uv((0.5, 0.), (1., 1.))
+                .fit(fit)
+                .create()
+        };
+
+        // The area is wider than the image
+        let area = (8., 2.);
+        let stretch = image(ImageFit::Stretch).placement(area);
+        assert_eq!(stretch, ((0., 0.), (8., 2.), ((0.5, 0.), (1., 1.))));
+        let contain = image(ImageFit::Contain).placement(area);
+        assert_eq!(contain, ((2., 0.), (4., 2.), ((0.5, 0.), (1., 1.))));
+        let cover = image(ImageFit::Cover).placement(area);
+        assert_eq!(cover, ((0., 0.), (8., 2.), ((0.5, 0.25), (1., 0.75))));
+
+        // It keeps its intrinsic size if possible, unless it is maximized
+        let query = |available_space, objectives| LayoutQuery {
+            available_space,
+            objectives,
+        };
+        let mut widget = image(ImageFit::Contain);
+        let minimize = (Objective::Minimize, Objective::Minimize);
+        let response = widget.layout(&query((Some(10.), None), minimize));
+        assert_eq!(response.size, (4., 2.));
+        let maximize = (Objective::Maximize, Objective::None);
+        let response = widget.layout(&query((Some(10.), Some(1.)), maximize));
+        assert_eq!(response.size, (10., 1.));
+        assert_eq!(response.status.1, LayoutStatus::Inconsistencies);
+    }
+}
@@ -1,19 +1,28 @@
 use nalgebra::*;
 
 use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+use crate::widget::Spacer;
 
 /// The builder for a window, that can hold an unlimited number of widgets.
 /// Display them in a finite space from top to bottom.
 pub struct InlineBuilder<'a> {
+    spacing: f32,
     generator: Option<Box<dyn 'a + FnMut(&NodeReference)>>,
 }
 
 impl<'a> InlineBuilder<'a> {
     pub fn new<F: 'a + FnMut(&NodeReference)>(generator: F) -> Self {
         Self {
+            spacing: WIDGET_SEPARATOR,
             generator: Some(Box::new(generator)),
         }
     }
+
+    /// Sets the space between two widgets of the row
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
 }
 
 impl<'a> WidgetBuilder for InlineBuilder<'a> {
@@ -26,12 +35,14 @@ impl<'a> WidgetBuilder for InlineBuilder<'a> {
         _metadata: &NodeMetadata,
         widget: &mut Self::AchievedType,
     ) -> Self::UpdateFeedback {
+        widget.spacing = self.spacing;
         widget.content.iter().for_each(|child| child.invalidate());
         widget.valid_index = 0;
     }
 
     fn create(self) -> Self::AchievedType {
         Self::AchievedType {
+            spacing: self.spacing,
             content: Vec::new(),
             valid_index: 0,
         }
@@ -50,22 +61,49 @@ impl<'a> WidgetBuilder for InlineBuilder<'a> {
 
 /// Internal window structure
 pub struct Inline {
+    /// The space between two widgets
+    spacing: f32,
     content: Vec<NodeReference>,
     valid_index: usize,
 }
 
-const WIDGET_SEPARATOR: f32 = 0.5;
+impl Inline {
+    /// Places the components laid out from left to right, with their sizes, sharing
+    /// the `remaining` horizontal space between the flexible spacers.
+    /// Returns the size of the row
+    fn place(&self, sizes: &[(f32, f32)], remaining: f32) -> (f32, f32) {
+        let flexible = self
+            .content
+            .iter()
+            .take(sizes.len())
+            .filter(|node| Spacer::is_flexible(node))
+            .count();
+        let extra = remaining.max(0.) / flexible.max(1) as f32;
+        let (mut cursor, mut height) = (0f32, 0f32);
+        for (node, size) in self.content.iter().zip(sizes) {
+            let size = if Spacer::is_flexible(node) {
+                (size.0 + extra, size.1)
+            } else {
+                *size
+            };
+            node.set_size(size);
+            node.set_transform(Similarity3::identity() * Translation3::new(cursor, 0., 0.));
+            cursor += size.0 + self.spacing;
+            height = height.max(size.1);
+        }
+        ((cursor - self.spacing).max(0.), height)
+    }
+}
 
 impl WidgetLogic for Inline {
     fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
         let (mut horizontal_space, vertical_space) = query.available_space;
         let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
-        let mut cursor = 0.;
-        let mut height = 0.;
+        let mut sizes = Vec::with_capacity(self.content.len());
         // For each component, compute the layout with all available space
-        // but with the `Minimize` objective. The vertical space taken is
+        // but with the `Minimize` objective. The horizontal space taken is
         // substracted from the remaining space
-        for node in &mut self.content {
+        for node in &self.content {
             let response = {
                 node.layout(&LayoutQuery {
                     available_space: (horizontal_space, vertical_space),
@@ -73,54 +111,34 @@ impl WidgetLogic for Inline {
                 })
             };
 
-            node.set_size(response.size);
-            node.set_transform(Similarity3::identity() * Translation3::new(cursor, 0., 0.));
-            cursor += response.size.0 + WIDGET_SEPARATOR;
-            height = height.max(response.size.1);
-
-            horizontal_space = horizontal_space.map(|x| x - response.size.0 + WIDGET_SEPARATOR);
+            sizes.push(response.size);
+            horizontal_space = horizontal_space.map(|x| x - response.size.0);
             status.0 = LayoutStatus::and(status.0, response.status.0);
             status.1 = LayoutStatus::and(status.1, response.status.1);
 
             if horizontal_space.unwrap_or(0.) < 0. {
-                // The window will ignore the components that cannot fit
+                // The row will ignore the components that cannot fit
                 return LayoutResponse {
-                    size: (cursor, height),
+                    size: self.place(&sizes, 0.),
                     status: (
-                        status.0,
-                        LayoutStatus::and(LayoutStatus::Inconsistencies, status.1),
+                        LayoutStatus::and(LayoutStatus::Inconsistencies, status.0),
+                        status.1,
                     ),
                 };
             }
+            horizontal_space = horizontal_space.map(|x| x - self.spacing);
         }
 
+        // The space left after the last component is shared by the flexible spacers
+        let remaining = horizontal_space.map_or(0., |x| x + self.spacing);
         LayoutResponse {
-            size: (cursor, height),
+            size: self.place(&sizes, remaining),
             status,
         }
     }
 
     fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
-        let child = self
-            .content
-            .iter()
-            .enumerate()
-            .find(|(_, other)| other.has_id(id))
-            .map(|(index, other)| (index, other.clone()));
-        let (index, result) = match child {
-            Some((index, node_ref)) => (index, WidgetQueryResult::Initialized(node_ref)),
-            None => {
-                let node_ref = Node::new_reference(id, &metadata.ui_properties);
-                self.content.push(node_ref.clone());
-                (
-                    self.content.len() - 1,
-                    WidgetQueryResult::Uninitialized(node_ref),
-                )
-            }
-        };
-        self.content.swap(self.valid_index, index);
-        self.valid_index += 1;
-        result
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
     }
 
     fn draw(&self, metadata: &NodeMetadata) -> DrawList {
@@ -148,7 +166,10 @@ impl WidgetLogic for Inline {
         _self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         self.content
             .iter()
             .map(|content| content.interaction_distance(&new_ray, content.clone()))
@@ -156,3 +177,38 @@ impl WidgetLogic for Inline {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::widget::{ImageBuilder, WindowBuilder};
+
+    #[test]
+    fn horizontal_overflow() {
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, width: f32| {
+            ui.new_frame();
+            WindowBuilder::new(|window| {
+                InlineBuilder::new(|row| {
+                    for index in 0..3 {
+                        ImageBuilder::new(TextureId::Font(0), (3., 1.))
+                            .build(CodeLocation(file!(), line!(), index), row);
+                    }
+                })
+                .build(loc!(), window);
+            })
+            .size((width, 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout().status
+        };
+
+        // The row is 10 wide with the separators, and only overflows horizontally
+        assert_eq!(build(&mut ui, 10.), (LayoutStatus::Ok, LayoutStatus::Ok));
+        assert_eq!(
+            build(&mut ui, 9.5),
+            (LayoutStatus::Inconsistencies, LayoutStatus::Ok)
+        );
+    }
+}
@@ -0,0 +1,669 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::*;
+
+use nalgebra::{Point3, Translation3};
+
+/// A type that can be edited through an `Input` field
+pub trait InputValue: PartialEq + Sized {
+    /// Formats the value as the text displayed in the field.
+    /// The precision is the number of decimals, for types which have some
+    fn to_text(&self, precision: Option<usize>) -> String;
+
+    /// Parses the text of the field, returning `None` if it does not
+    /// describe a valid value
+    fn from_text(text: &str) -> Option<Self>;
+
+    /// Checks whether the text of the field, while being edited, could still
+    /// become a valid value. Characters making this function return `false`
+    /// are rejected
+    fn accepts(text: &str) -> bool;
+}
+
+impl InputValue for String {
+    fn to_text(&self, _precision: Option<usize>) -> String {
+        self.clone()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        Some(text.to_string())
+    }
+
+    fn accepts(_text: &str) -> bool {
+        true
+    }
+}
+
+/// Checks that the text is an optional sign followed by digits
+fn is_partial_integer(text: &str) -> bool {
+    let digits = text.strip_prefix(&['-', '+'][..]).unwrap_or(text);
+    digits.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Checks that the text is an optional sign followed by digits,
+/// at most one decimal point and an optional exponent
+fn is_partial_float(text: &str) -> bool {
+    let (mantissa, exponent) = match text.find(&['e', 'E'][..]) {
+        Some(index) => (&text[..index], Some(&text[index + 1..])),
+        None => (text, None),
+    };
+    let mantissa = mantissa.strip_prefix(&['-', '+'][..]).unwrap_or(mantissa);
+    mantissa.chars().filter(|&c| c == '.').count() <= 1
+        && mantissa.chars().all(|c| c.is_ascii_digit() || c == '.')
+        && exponent.map(is_partial_integer).unwrap_or(true)
+}
+
+macro_rules! integer_input_value {
+    ($($t:ty),*) => {
+        $(
+            impl InputValue for $t {
+                fn to_text(&self, _precision: Option<usize>) -> String {
+                    self.to_string()
+                }
+
+                fn from_text(text: &str) -> Option<Self> {
+                    text.parse().ok()
+                }
+
+                fn accepts(text: &str) -> bool {
+                    is_partial_integer(text)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_input_value {
+    ($($t:ty),*) => {
+        $(
+            impl InputValue for $t {
+                fn to_text(&self, precision: Option<usize>) -> String {
+                    match precision {
+                        Some(precision) => format!("{:.*}", precision, self),
+                        None => self.to_string(),
+                    }
+                }
+
+                fn from_text(text: &str) -> Option<Self> {
+                    text.parse().ok().filter(|value: &Self| value.is_finite())
+                }
+
+                fn accepts(text: &str) -> bool {
+                    is_partial_float(text)
+                }
+            }
+        )*
+    };
+}
+
+integer_input_value!(i32, i64);
+float_input_value!(f32, f64);
+
+/// What happened to an `Input` field since the previous frame
+/// - `changed` indicates the value was modified by the user
+/// - `committed` indicates the user validated the field, by pressing Enter
+///   or by leaving it
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq)]
+pub struct InputFeedback {
+    pub changed: bool,
+    pub committed: bool,
+}
+
+/// A single-line field editing a value of type `T`.
+///
+/// The value is updated each time the text describes a valid value,
+/// and the text is formatted again once committed.
+pub struct InputBuilder<'a, T: InputValue> {
+    value: &'a mut T,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    size: f32,
+    width: f32,
+    precision: Option<usize>,
+    placeholder: String,
+    mask: Option<char>,
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND_FOCUSED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTION: (f32, f32, f32, f32) = (0.349, 0.475, 0.765, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const PLACEHOLDER: (f32, f32, f32, f32) = (0.5, 0.5, 0.5, 1.);
+const CARET_WIDTH: f32 = 0.05;
+
+impl<'a, T: InputValue> InputBuilder<'a, T> {
+    pub fn new(value: &'a mut T, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            value,
+            font: font.clone(),
+            size: 1.0,
+            width: 8.,
+            precision: None,
+            placeholder: String::new(),
+            mask: None,
+            text_color: TEXT,
+        }
+    }
+
+    pub fn size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets the width of the field, when it is not maximized by its parent
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    /// Sets the number of decimals displayed, for floating-point values
+    pub fn precision(self, precision: usize) -> Self {
+        Self {
+            precision: Some(precision),
+            ..self
+        }
+    }
+
+    /// Sets a hint displayed in the field while it is empty
+    pub fn placeholder(self, placeholder: String) -> Self {
+        Self {
+            placeholder,
+            ..self
+        }
+    }
+
+    /// Hides the text by displaying the given character instead of each
+    /// character typed. Copying the text is then disabled
+    pub fn mask(self, mask: char) -> Self {
+        Self {
+            mask: Some(mask),
+            ..self
+        }
+    }
+
+    /// Hides the text the usual way for passwords
+    pub fn password(self) -> Self {
+        self.mask('•')
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: InputValue> WidgetBuilder for InputBuilder<'a, T> {
+    type AchievedType = Input;
+    type UpdateFeedback = InputFeedback;
+    type BuildFeedback = InputFeedback;
+
+    fn update(self, metadata: &NodeMetadata, widget: &mut Self::AchievedType) -> InputFeedback {
+        widget.size = self.size;
+        widget.width = self.width;
+        widget.placeholder = self.placeholder;
+        widget.mask = self.mask;
+        widget.text_color = self.text_color;
+        widget.accepts = T::accepts;
+
+        let mut feedback = InputFeedback::default();
+        if widget.edited {
+            widget.edited = false;
+            if let Some(value) = T::from_text(widget.edit.text()) {
+                if value != *self.value {
+                    *self.value = value;
+                    feedback.changed = true;
+                }
+            }
+        }
+
+        let focused = metadata.is_focused();
+        if widget.editing && !focused {
+            feedback.committed = true;
+        }
+        widget.editing = focused;
+
+        if !focused {
+            let text = self.value.to_text(self.precision);
+            if text != widget.edit.text() {
+                widget.edit.set_text(text);
+            }
+        }
+
+        feedback
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Input {
+            edit: TextEditState::new(self.value.to_text(self.precision)),
+            font: self.font,
+            size: self.size,
+            width: self.width,
+            placeholder: self.placeholder,
+            mask: self.mask,
+            text_color: self.text_color,
+            accepts: T::accepts,
+            edited: false,
+            editing: false,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
+/// The widget behind an `InputBuilder`, holding the text being edited
+pub struct Input {
+    edit: TextEditState,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    size: f32,
+    width: f32,
+    placeholder: String,
+    mask: Option<char>,
+    text_color: (f32, f32, f32, f32),
+    accepts: fn(&str) -> bool,
+    /// Whether the text was modified since the last update
+    edited: bool,
+    /// Whether the field was focused during the last update
+    editing: bool,
+}
+
+const PADDING: f32 = 0.2;
+
+impl Input {
+    /// The text as displayed, that is, masked if needed
+    fn displayed_text(&self) -> String {
+        match self.mask {
+            Some(mask) => self.edit.text().chars().map(|_| mask).collect(),
+            None => self.edit.text().to_string(),
+        }
+    }
+
+    /// Returns the range of characters that fit in the field, such that
+    /// the caret is always visible
+    fn visible_range(&self, positions: &[CharacterPosition], width: f32) -> (usize, usize) {
+        let caret = positions[self.edit.caret()].x;
+        let first = positions
+            .iter()
+            .position(|position| caret - position.x <= width)
+            .unwrap_or(0);
+        let offset = positions[first].x;
+        let last = positions
+            .iter()
+            .rposition(|position| position.x + position.width - offset <= width)
+            .unwrap_or(first)
+            .max(first);
+        (first, last)
+    }
+
+    fn insert(&mut self, string: &str) {
+        if (self.accepts)(self.edit.preview_insert(string).as_str()) {
+            self.edit.insert(string);
+            self.edited = true;
+        }
+    }
+
+    /// Applies a modification to the text, and reverts it if the resulting
+    /// text is not accepted, so that the field can never hold a text that
+    /// could not become a valid value
+    fn modify<F: FnOnce(&mut TextEditState)>(&mut self, modification: F) {
+        let previous = self.edit.clone();
+        modification(&mut self.edit);
+        if self.edit.text() == previous.text() {
+            return;
+        }
+        if (self.accepts)(self.edit.text()) {
+            self.edited = true;
+        } else {
+            self.edit = previous;
+        }
+    }
+}
+
+impl WidgetLogic for Input {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let mut width = self.width;
+        let mut height = self.size + 2. * PADDING;
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        match query.objectives.0 {
+            Objective::Maximize => {
+                width = available_width;
+            }
+            Objective::Minimize | Objective::None => {
+                if width > available_width {
+                    width = available_width;
+                }
+            }
+        }
+        if height > available_height {
+            height = available_height;
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let focused = metadata.is_focused();
+        let size = metadata.size;
+        let inner_width = size.0 - 2. * PADDING;
+
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            size.0,
+            size.1,
+            None,
+            if focused {
+                BACKGROUND_FOCUSED
+            } else {
+                BACKGROUND
+            },
+            metadata.transform,
+        ));
+
+        if self.edit.text().is_empty() && !focused {
+            list.commands.push(draw_text(
+                self.placeholder.as_str(),
+                &self.font,
+                self.size,
+                PLACEHOLDER,
+                (metadata.transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+            ));
+            return list;
+        }
+
+        let text = self.displayed_text();
+        let positions = character_positions(text.as_str(), &self.font, self.size);
+        let (first, last) = self.visible_range(&positions, inner_width);
+        let offset = positions[first].x;
+        let origin = metadata.transform * Translation3::new(PADDING - offset, PADDING, 0.);
+
+        if focused {
+            if let Some((start, end)) = self.edit.selection() {
+                let (start, end) = (start.max(first), end.min(last).max(first));
+                let selection: Vec<(f32, f32, f32, f32)> = positions[start.min(end)..end]
+                    .iter()
+                    .map(|position| (position.x, position.y, position.width, self.size))
+                    .collect();
+                list.commands.push(rectangles(
+                    &selection,
+                    SELECTION,
+                    origin * Translation3::new(0., 0., 0.005),
+                ));
+            }
+
+            let caret = positions[self.edit.caret()];
+            list.commands.push(quad(
+                CARET_WIDTH,
+                self.size,
+                None,
+                self.text_color,
+                origin * Translation3::new(caret.x, caret.y, 0.015),
+            ));
+        }
+
+        let visible: String = text.chars().skip(first).take(last - first).collect();
+        list.commands.push(draw_text(
+            visible.as_str(),
+            &self.font,
+            self.size,
+            self.text_color,
+            (origin * Translation3::new(offset, 0., 0.01)).to_homogeneous(),
+        ));
+
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        let Modifiers { shift, ctrl, .. } = metadata.modifiers();
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if let Some((x, _)) = metadata.pointer_position() {
+                    let text = self.displayed_text();
+                    let positions = character_positions(text.as_str(), &self.font, self.size);
+                    let (first, _) = self.visible_range(&positions, metadata.size.0 - 2. * PADDING);
+                    let x = x - PADDING + positions[first].x;
+                    let index = caret_index_at(&positions, self.size, (x, self.size / 2.));
+                    self.edit.set_caret(index, shift && metadata.is_focused());
+                }
+                metadata.request_focus();
+                EventResponse::Registered
+            }
+            Event::KeyPressed(key) => {
+                match key {
+                    Key::ArrowLeft => self.edit.move_left(shift, ctrl),
+                    Key::ArrowRight => self.edit.move_right(shift, ctrl),
+                    Key::Home => self.edit.move_home(shift, true),
+                    Key::End => self.edit.move_end(shift, true),
+                    Key::Backspace => self.modify(|edit| edit.backspace(ctrl)),
+                    Key::Delete => self.modify(|edit| edit.delete(ctrl)),
+                    Key::Return | Key::NumpadEnter => metadata.release_focus(),
+                    Key::Z if ctrl && shift => self.modify(|edit| {
+                        edit.redo();
+                    }),
+                    Key::Z if ctrl => self.modify(|edit| {
+                        edit.undo();
+                    }),
+                    Key::Y if ctrl => self.modify(|edit| {
+                        edit.redo();
+                    }),
+                    Key::A if ctrl => self.edit.select_all(),
+                    Key::C if ctrl => {
+                        if self.mask.is_none() && self.edit.selection().is_some() {
+                            metadata.copy_to_clipboard(self.edit.selected_text().to_string());
+                        }
+                    }
+                    Key::X if ctrl => {
+                        if self.mask.is_none() && self.edit.selection().is_some() {
+                            metadata.copy_to_clipboard(self.edit.selected_text().to_string());
+                            self.modify(|edit| edit.backspace(false));
+                        }
+                    }
+                    Key::V if ctrl => {
+                        if let Some(contents) = metadata.paste_from_clipboard() {
+                            // Only the first line is kept in a single-line field
+                            let line = contents.split(&['\r', '\n'][..]).next();
+                            self.insert(line.unwrap_or(""));
+                        }
+                    }
+                    _ => return EventResponse::Pass,
+                }
+                EventResponse::Registered
+            }
+            Event::Character(c) => {
+                if !c.is_control() {
+                    self.insert(c.encode_utf8(&mut [0; 4]));
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+    use crate::widget::*;
+
+    #[test]
+    fn partial_numbers() {
+        assert!(i64::accepts(""));
+        assert!(i64::accepts("-"));
+        assert!(i64::accepts("-42"));
+        assert!(!i64::accepts("4.2"));
+        assert!(!i64::accepts("4-2"));
+
+        assert!(f32::accepts("-"));
+        assert!(f32::accepts("-4."));
+        assert!(f32::accepts(".5e-"));
+        assert!(f32::accepts("1.5E10"));
+        assert!(!f32::accepts("1.5.2"));
+        assert!(!f32::accepts("1e2.5"));
+        assert!(!f32::accepts("abc"));
+    }
+
+    #[test]
+    fn parsing_and_formatting() {
+        assert_eq!(i64::from_text("-42"), Some(-42));
+        assert_eq!(i64::from_text("-"), None);
+        assert_eq!(f32::from_text("1.5e1"), Some(15.));
+        assert_eq!(f32::from_text("inf"), None);
+        assert_eq!(f32::from_text("."), None);
+        assert_eq!(1.5f32.to_text(Some(3)), "1.500");
+        assert_eq!(String::from_text("text"), Some("text".to_string()));
+    }
+
+    #[test]
+    fn edit_and_commit() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let (mut value, mut password) = (1.5f32, String::new());
+        let mut build = |ui: &mut Interface| {
+            let mut result = None;
+            build_in_window(ui, |window| {
+                let id = ComponentId::new::<Input>(loc!());
+                let (_, feedback) = window
+                    .query::<Input>(id)
+                    .update(InputBuilder::new(&mut value, &font));
+                let id = ComponentId::new::<Input>(loc!());
+                let (password_node, _) = window.query::<Input>(id).update(
+                    InputBuilder::new(&mut password, &font)
+                        .placeholder("Password".to_string())
+                        .mask('*'),
+                );
+                result = Some((feedback, password_node));
+            });
+            result.unwrap()
+        };
+        let type_text = |ui: &mut Interface, text: &str| {
+            for c in text.chars() {
+                ui.register_event(Event::Character(c), None);
+            }
+        };
+        let press = |ui: &mut Interface, y: f32| {
+            press_at(ui, -4., y);
+            ui.register_event(
+                Event::MouseButtonReleased(MouseButton::Left),
+                Some(&ray_at(-4., y)),
+            );
+        };
+        let unchanged = InputFeedback::default();
+
+        // The fields are 1.4 high, the first one from y = 3.6 to y = 5
+        let (_, password_node) = build(&mut ui);
+        // While empty and unfocused, the password field shows its placeholder
+        let placeholder = password_node.draw().commands;
+        assert_eq!(placeholder.len(), 2);
+        assert_eq!(placeholder[1].vertex_buffer.len(), 4 * "Password".len());
+
+        press(&mut ui, 4.3);
+        assert_eq!(build(&mut ui).0, unchanged);
+        ui.register_event(Event::KeyPressed(Key::LCtrl), None);
+        ui.register_event(Event::KeyPressed(Key::A), None);
+        ui.register_event(Event::KeyReleased(Key::LCtrl), None);
+        type_text(&mut ui, "1x");
+        type_text(&mut ui, "e-5");
+        let (feedback, _) = build(&mut ui);
+        assert_eq!(
+            feedback,
+            InputFeedback {
+                changed: true,
+                committed: false
+            }
+        );
+
+        // Deleting the exponent would leave "1-5", which is not a number
+        ui.register_event(Event::KeyPressed(Key::Home), None);
+        ui.register_event(Event::KeyPressed(Key::ArrowRight), None);
+        ui.register_event(Event::KeyPressed(Key::Delete), None);
+        assert_eq!(build(&mut ui).0, unchanged);
+        ui.register_event(Event::KeyPressed(Key::End), None);
+        ui.register_event(Event::KeyPressed(Key::Backspace), None);
+        type_text(&mut ui, "6");
+        let (feedback, _) = build(&mut ui);
+        assert!(feedback.changed);
+
+        // Enter commits the field, whose text is then formatted again
+        ui.register_event(Event::KeyPressed(Key::Return), None);
+        let (feedback, _) = build(&mut ui);
+        assert_eq!(
+            feedback,
+            InputFeedback {
+                changed: false,
+                committed: true
+            }
+        );
+
+        // Leaving the field for the password one commits it too
+        press(&mut ui, 4.3);
+        build(&mut ui);
+        press(&mut ui, 2.4);
+        assert!(build(&mut ui).0.committed);
+
+        // The password is masked, and cannot be copied: pasting after it
+        // leaves it as it was
+        type_text(&mut ui, "secret");
+        ui.register_event(Event::KeyPressed(Key::LCtrl), None);
+        ui.register_event(Event::KeyPressed(Key::A), None);
+        ui.register_event(Event::KeyPressed(Key::C), None);
+        ui.register_event(Event::KeyPressed(Key::End), None);
+        ui.register_event(Event::KeyPressed(Key::V), None);
+        ui.register_event(Event::KeyReleased(Key::LCtrl), None);
+        let (_, password_node) = build(&mut ui);
+        password_node
+            .apply_to_widget::<Input, _>(|_, widget| assert_eq!(widget.displayed_text(), "******"));
+        assert_eq!(value, 1e-6);
+        assert_eq!(password, "secret");
+    }
+}
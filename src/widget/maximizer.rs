@@ -109,7 +109,10 @@ impl WidgetLogic for Maximize {
         _self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         self.content
             .iter()
             .map(|content| content.interaction_distance(&new_ray, content.clone()))
@@ -0,0 +1,976 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3, Vector2, Vector3};
+
+use crate::core::*;
+use crate::widget::Wrapped;
+
+/// Builds the menus of a bar, or the items of a menu, in its node
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// A horizontal bar of menus, usually placed at the top of a `Window`.
+///
+/// The generator builds the menus of the bar with `MenuBuilder`.
+/// The bar takes all the width available.
+pub struct MenuBarBuilder<'a> {
+    color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const SHORTCUT: (f32, f32, f32, f32) = (0.6, 0.6, 0.6, 1.);
+
+impl<'a> MenuBarBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(generator: F) -> Self {
+        Self {
+            color: BACKGROUND,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for MenuBarBuilder<'a> {
+    type AchievedType = MenuBar;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.color = self.color;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        MenuBar {
+            color: self.color,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid());
+        });
+    }
+}
+
+/// The widget behind a `MenuBarBuilder`
+pub struct MenuBar {
+    color: (f32, f32, f32, f32),
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+const PADDING: f32 = 0.2;
+/// Distance between a popup and the widget opening it, towards the viewer
+const POPUP_DEPTH: f32 = 0.1;
+/// Added to the distance of backgrounds, so that the content is always in front
+const DEPTH_BIAS: f32 = 1e-3;
+/// Space between the label of an item and its shortcut, relative to the font size
+const SHORTCUT_GAP: f32 = 2.;
+
+impl WidgetLogic for MenuBar {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let responses = self
+            .content
+            .iter()
+            .map(|node| {
+                node.layout(&LayoutQuery {
+                    available_space: (None, None),
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                })
+            })
+            .collect::<Vec<_>>();
+        let height = responses
+            .iter()
+            .map(|response| response.size.1)
+            .fold(0., f32::max);
+
+        let mut x = 0.;
+        for (node, response) in self.content.iter().zip(responses) {
+            node.set_size(response.size);
+            node.set_transform(
+                Similarity3::identity() * Translation3::new(x, height - response.size.1, 0.),
+            );
+            x += response.size.0;
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+        }
+
+        let width = query.available_space.0.unwrap_or(x);
+        if x > width {
+            status.0 = LayoutStatus::and(status.0, LayoutStatus::Inconsistencies);
+        }
+        if let Some(available_height) = query.available_space.1 {
+            if height > available_height {
+                status.1 = LayoutStatus::and(status.1, LayoutStatus::Inconsistencies);
+            }
+        }
+        LayoutResponse {
+            size: (width, height),
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect()
+    }
+}
+
+/// A menu, displaying its label and opening its items in a popup when clicked.
+///
+/// The generator builds the items with `MenuItemBuilder`. Menus built in it are
+/// submenus, opening next to their label while keeping the parent menu open.
+/// Menus of a `MenuBarBuilder` open below their label.
+/// `build` returns whether the menu is open.
+pub struct MenuBuilder<'a> {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a> MenuBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(
+        label: String,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+        generator: F,
+    ) -> Self {
+        Self {
+            label,
+            font: font.clone(),
+            font_size: 1.,
+            color: BACKGROUND,
+            text_color: TEXT,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Sets the color of the popup holding the items
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for MenuBuilder<'a> {
+    type AchievedType = Menu;
+    type UpdateFeedback = ();
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.label = self.label;
+        widget.font_size = self.font_size;
+        widget.text_color = self.text_color;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Menu {
+            label: self.label,
+            font: self.font,
+            font_size: self.font_size,
+            text_color: self.text_color,
+            nested: false,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let generator = self.generator.take().unwrap();
+        let color = self.color;
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut popup = None;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, widget| {
+            widget.nested = metadata.is_in_popup();
+            if metadata.is_popup_open() {
+                let corner = if widget.nested {
+                    Translation3::new(metadata.size.0, metadata.size.1, POPUP_DEPTH)
+                } else {
+                    Translation3::new(0., 0., POPUP_DEPTH)
+                };
+                popup = metadata
+                    .popup_layer()
+                    .map(|layer| (layer, metadata.global_transform() * corner));
+            }
+        });
+
+        let open = popup.is_some();
+        if let Some((layer, transform)) = popup {
+            MenuPopupBuilder {
+                owner: node_ref,
+                transform,
+                color,
+                generator: Some(generator),
+            }
+            .build(loc, &layer);
+        }
+        open
+    }
+}
+
+/// The widget behind a `MenuBuilder`, displaying the label of the menu
+pub struct Menu {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    text_color: (f32, f32, f32, f32),
+    /// Whether the menu is a submenu, in the popup of another menu
+    nested: bool,
+}
+
+impl WidgetLogic for Menu {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (label_width, _) = self
+            .font
+            .borrow()
+            .size_of(self.label.as_str(), self.font_size);
+        // Submenus keep room for their arrow, and fill the width of the popup
+        let mut width = label_width + 2. * PADDING;
+        if self.nested {
+            width += self.font_size;
+            width = query.available_space.0.unwrap_or(width).max(width);
+        }
+        row_layout(query, width, self.font_size + 2. * PADDING)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        if metadata.is_popup_open() {
+            list.commands
+                .push(quad(size.0, size.1, None, SELECTED, metadata.transform));
+        }
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (metadata.transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+        ));
+
+        // A small triangle pointing right, towards the submenu
+        if self.nested {
+            let half = self.font_size / 4.;
+            let (x, y) = (size.0 - PADDING - half, size.1 / 2.);
+            list.commands.push(triangle(
+                [
+                    (x - half / 2., y + half),
+                    (x - half / 2., y - half),
+                    (x + half / 2., y),
+                ],
+                self.text_color,
+                metadata.transform,
+            ));
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        rectangle_distance(metadata, ray, 0.)
+            .map(|d| vec![(d, self_node)])
+            .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if metadata.is_popup_open() {
+                    metadata.close_popup();
+                } else {
+                    metadata.open_popup();
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// An item of a menu, with an optional keyboard shortcut label.
+///
+/// The shortcut is only displayed: the application handles the keys itself.
+/// A `checked` item toggles the `bool` it is bound to when clicked.
+/// Clicking an item closes all the menus, and `build` returns whether it was clicked.
+pub struct MenuItemBuilder<'a> {
+    label: String,
+    shortcut: String,
+    checked: Option<&'a mut bool>,
+    check: Option<bool>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    text_color: (f32, f32, f32, f32),
+    shortcut_color: (f32, f32, f32, f32),
+}
+
+impl<'a> MenuItemBuilder<'a> {
+    pub fn new(label: String, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            label,
+            shortcut: String::new(),
+            checked: None,
+            check: None,
+            font: font.clone(),
+            font_size: 1.,
+            text_color: TEXT,
+            shortcut_color: SHORTCUT,
+        }
+    }
+
+    /// Displays a keyboard shortcut on the right of the item, like `"Ctrl+S"`
+    pub fn shortcut(self, shortcut: String) -> Self {
+        Self { shortcut, ..self }
+    }
+
+    /// Makes the item checkable, bound to the given `bool`
+    pub fn checked(self, checked: &'a mut bool) -> Self {
+        Self {
+            checked: Some(checked),
+            ..self
+        }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+
+    pub fn shortcut_color(self, shortcut_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            shortcut_color,
+            ..self
+        }
+    }
+}
+
+impl<'a> WidgetBuilder for MenuItemBuilder<'a> {
+    type AchievedType = MenuItem;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let clicked = widget.clicked;
+        widget.clicked = false;
+        widget.label = self.label;
+        widget.shortcut = self.shortcut;
+        widget.check = self.check;
+        widget.font_size = self.font_size;
+        widget.text_color = self.text_color;
+        widget.shortcut_color = self.shortcut_color;
+        clicked
+    }
+
+    fn create(self) -> Self::AchievedType {
+        MenuItem {
+            label: self.label,
+            shortcut: self.shortcut,
+            check: self.check,
+            font: self.font,
+            font_size: self.font_size,
+            text_color: self.text_color,
+            shortcut_color: self.shortcut_color,
+            clicked: false,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let checked = self.checked.take();
+        self.check = checked.as_ref().map(|checked| **checked);
+        let (node_ref, clicked) = parent.query::<Self::AchievedType>(id).update(self);
+
+        if clicked {
+            if let Some(checked) = checked {
+                *checked = !*checked;
+            }
+            node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, _| {
+                metadata.close_all_popups()
+            });
+        }
+        clicked
+    }
+}
+
+/// The widget behind a `MenuItemBuilder`
+pub struct MenuItem {
+    label: String,
+    shortcut: String,
+    /// The state of a checkable item
+    check: Option<bool>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    text_color: (f32, f32, f32, f32),
+    shortcut_color: (f32, f32, f32, f32),
+    /// Whether the item was clicked since the last update
+    clicked: bool,
+}
+
+impl WidgetLogic for MenuItem {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let font = self.font.borrow();
+        let (label_width, _) = font.size_of(self.label.as_str(), self.font_size);
+        // Room for the check mark is kept on all items, so that labels are aligned
+        let mut width = self.font_size + label_width + 2. * PADDING;
+        if !self.shortcut.is_empty() {
+            let (shortcut_width, _) = font.size_of(self.shortcut.as_str(), self.font_size);
+            width += SHORTCUT_GAP * self.font_size + shortcut_width;
+        }
+        let width = query.available_space.0.unwrap_or(width).max(width);
+        row_layout(query, width, self.font_size + 2. * PADDING)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        if let Some(true) = self.check {
+            let quarter = self.font_size / 4.;
+            list.commands.push(quad(
+                2. * quarter,
+                2. * quarter,
+                None,
+                self.text_color,
+                metadata.transform
+                    * Translation3::new(PADDING + quarter, size.1 / 2. - quarter, 0.01),
+            ));
+        }
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (metadata.transform * Translation3::new(PADDING + self.font_size, PADDING, 0.01))
+                .to_homogeneous(),
+        ));
+        if !self.shortcut.is_empty() {
+            let (shortcut_width, _) = self
+                .font
+                .borrow()
+                .size_of(self.shortcut.as_str(), self.font_size);
+            list.commands.push(draw_text(
+                self.shortcut.as_str(),
+                &self.font,
+                self.font_size,
+                self.shortcut_color,
+                (metadata.transform
+                    * Translation3::new(size.0 - PADDING - shortcut_width, PADDING, 0.01))
+                .to_homogeneous(),
+            ));
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        rectangle_distance(metadata, ray, 0.)
+            .map(|d| vec![(d, self_node)])
+            .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, _metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                self.clicked = true;
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// Adds a context menu to a widget, opened by a right click on it.
+///
+/// The menu opens at the pointer, and its generator builds items like the one
+/// of a `MenuBuilder`. `build` returns the feedback of the widget.
+pub struct ContextMenuBuilder<'a, T: WidgetBuilder> {
+    content: Option<T>,
+    color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a, T: WidgetBuilder> ContextMenuBuilder<'a, T> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(content: T, generator: F) -> Self {
+        Self {
+            content: Some(content),
+            color: BACKGROUND,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the color of the popup holding the items
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl<'a, T: WidgetBuilder> WidgetBuilder for ContextMenuBuilder<'a, T> {
+    type AchievedType = ContextMenu;
+    type UpdateFeedback = ();
+    type BuildFeedback = T::BuildFeedback;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        _widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+    }
+
+    fn create(self) -> Self::AchievedType {
+        ContextMenu {
+            content: Wrapped::new(),
+            anchor: (0., 0.),
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let content = self.content.take().unwrap();
+        let generator = self.generator.take().unwrap();
+        let color = self.color;
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        let feedback = content.build(loc, &node_ref);
+
+        let mut popup = None;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, widget| {
+            if metadata.is_popup_open() {
+                let corner = Translation3::new(widget.anchor.0, widget.anchor.1, POPUP_DEPTH);
+                popup = metadata
+                    .popup_layer()
+                    .map(|layer| (layer, metadata.global_transform() * corner));
+            }
+        });
+        if let Some((layer, transform)) = popup {
+            MenuPopupBuilder {
+                owner: node_ref,
+                transform,
+                color,
+                generator: Some(generator),
+            }
+            .build(loc, &layer);
+        }
+        feedback
+    }
+}
+
+/// The widget behind a `ContextMenuBuilder`, holding the widget the menu is attached to
+pub struct ContextMenu {
+    content: Wrapped,
+    /// Where the menu was opened, in the local space of the widget
+    anchor: (f32, f32),
+}
+
+/// Added to the distance of a widget with a context menu, so that the widget
+/// gets the events first, but before the background of its window
+const CONTEXT_BIAS: f32 = 1e-4;
+
+impl WidgetLogic for ContextMenu {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        self.content.query(metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        self.content.layout(query)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        self.content.draw(metadata)
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let mut distances = self.content.interaction_distance(metadata, ray);
+        distances.extend(rectangle_distance(metadata, ray, CONTEXT_BIAS).map(|d| (d, self_node)));
+        distances
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Right) => {
+                self.anchor = metadata.pointer_position().unwrap_or((0., metadata.size.1));
+                metadata.open_popup();
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// Builds the popup of a menu in the popup layer, stacking its items from the top
+struct MenuPopupBuilder<'a> {
+    /// The widget opening the popup
+    owner: NodeReference,
+    /// The placement of the top left corner of the popup, in the space of the interface
+    transform: Similarity3<f32>,
+    color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a> WidgetBuilder for MenuPopupBuilder<'a> {
+    type AchievedType = MenuPopup;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.color = self.color;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        MenuPopup {
+            color: self.color,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (owner, transform) = (self.owner.clone(), self.transform);
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        // Set before building the items, so that submenus know they are in a popup
+        node_ref.set_popup_owner(&owner);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid());
+        });
+        node_ref.set_transform(transform);
+    }
+}
+
+/// The popup of a menu.
+///
+/// Its origin is its top left corner, so that it can be placed before its size is known.
+pub struct MenuPopup {
+    color: (f32, f32, f32, f32),
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl WidgetLogic for MenuPopup {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
+        // The items are laid out a first time to find the widest one,
+        // then a second time to fill the width of the popup
+        let width = self
+            .content
+            .iter()
+            .map(|node| {
+                node.layout(&LayoutQuery {
+                    available_space: (None, None),
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                })
+                .size
+                .0
+            })
+            .fold(0., f32::max);
+
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut height = 0.;
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (Some(width), None),
+                objectives: (Objective::Maximize, Objective::Minimize),
+            });
+            height += response.size.1;
+            node.set_size(response.size);
+            node.set_transform(Similarity3::identity() * Translation3::new(0., -height, 0.));
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+        }
+        LayoutResponse {
+            size: (width, height),
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            size.0,
+            size.1,
+            None,
+            self.color,
+            metadata.transform * Translation3::new(0., -size.1, 0.),
+        ));
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let mut distances = self
+            .content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect::<Vec<_>>();
+        // The background catches the clicks between items, so that they do not
+        // reach the windows behind the popup
+        let size = metadata.size;
+        let background = [
+            Point3::new(0., -size.1, 0.),
+            Point3::new(size.0, -size.1, 0.),
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+        ];
+        if let Some(d) = [
+            [background[0], background[1], background[2]],
+            [background[1], background[2], background[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        {
+            distances.push((d + DEPTH_BIAS, self_node));
+        }
+        distances
+    }
+
+    fn send_event(&mut self, _metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(_) => EventResponse::Registered,
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The layout of a row of a menu, with the given preferred size
+fn row_layout(query: &LayoutQuery, width: f32, height: f32) -> LayoutResponse {
+    let status = |size: f32, available: Option<f32>| match available {
+        Some(available) if size > available => LayoutStatus::Inconsistencies,
+        _ => LayoutStatus::Ok,
+    };
+    LayoutResponse {
+        size: (width, height),
+        status: (
+            status(width, query.available_space.0),
+            status(height, query.available_space.1),
+        ),
+    }
+}
+
+/// Returns the distance from the ray to the rectangle covering the widget, plus `bias`
+fn rectangle_distance(metadata: &NodeMetadata, ray: &Ray, bias: f32) -> Option<f32> {
+    let transformation = metadata.transform.inverse();
+    let new_ray = Ray::new(
+        transformation * ray.direction(),
+        transformation * ray.origin(),
+    );
+    let size = metadata.size;
+    let points = [
+        Point3::new(0., 0., 0.),
+        Point3::new(size.0, 0., 0.),
+        Point3::new(0., size.1, 0.),
+        Point3::new(size.0, size.1, 0.),
+    ];
+    [
+        [points[0], points[1], points[2]],
+        [points[1], points[2], points[3]],
+    ]
+    .iter()
+    .filter_map(|triangle| intersection(&new_ray, triangle))
+    .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+    .map(|d| d + bias)
+}
+
+fn triangle(
+    points: [(f32, f32); 3],
+    color: (f32, f32, f32, f32),
+    transform: Similarity3<f32>,
+) -> DrawCommand {
+    let mut uniforms = Uniforms::new();
+    uniforms.model_matrix = transform.to_homogeneous();
+    DrawCommand {
+        vertex_buffer: points
+            .iter()
+            .map(|&(x, y)| Vertex {
+                position: Vector3::new(x, y, 0.01),
+                color,
+                tex_uv: Vector2::new(0., 0.),
+            })
+            .collect(),
+        index_buffer: vec![0, 1, 2],
+        draw_mode: DrawMode::Triangles,
+        uniforms,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+    use crate::widget::LabelBuilder;
+
+    #[derive(Default)]
+    struct Clicks {
+        file_open: bool,
+        recent: bool,
+        copy: bool,
+    }
+
+    #[test]
+    fn menus_and_context_menu() {
+        let font = mock_font();
+        let mut autosave = false;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, autosave: &mut bool| {
+            let mut clicks = Clicks::default();
+            build_in_window(ui, |window| {
+                MenuBarBuilder::new(|bar| {
+                    clicks.file_open = MenuBuilder::new("File".to_string(), &font, |menu| {
+                        MenuItemBuilder::new("Open".to_string(), &font)
+                            .shortcut("Ctrl+O".to_string())
+                            .build(loc!(), menu);
+                        MenuItemBuilder::new("Autosave".to_string(), &font)
+                            .checked(autosave)
+                            .build(loc!(), menu);
+                        MenuBuilder::new("Recent".to_string(), &font, |submenu| {
+                            clicks.recent = MenuItemBuilder::new("a.txt".to_string(), &font)
+                                .build(loc!(), submenu);
+                        })
+                        .build(loc!(), menu);
+                    })
+                    .build(loc!(), bar);
+                })
+                .build(loc!(), window);
+                ContextMenuBuilder::new(LabelBuilder::new("Hello", &font), |menu| {
+                    clicks.copy =
+                        MenuItemBuilder::new("Copy".to_string(), &font).build(loc!(), menu);
+                })
+                .build(loc!(), window);
+            });
+            clicks
+        };
+
+        build(&mut ui, &mut autosave);
+        // The bar goes from y = 3.6 to 5, with the "File" menu on its left.
+        // Its popup opens below it, with rows 1.4 high: "Open", "Autosave" and "Recent".
+        // The popup is 8.4 wide, as wide as the "Open" item and its shortcut
+        press_at(&mut ui, -4., 4.3);
+        assert!(build(&mut ui, &mut autosave).file_open);
+        build(&mut ui, &mut autosave);
+
+        // The submenu opens on the right of "Recent", keeping the menu open
+        press_at(&mut ui, -4., 0.1);
+        assert!(build(&mut ui, &mut autosave).file_open);
+        build(&mut ui, &mut autosave);
+        press_at(&mut ui, 5., 0.1);
+        assert!(build(&mut ui, &mut autosave).recent);
+        // Clicking an item closes all the menus
+        assert!(!build(&mut ui, &mut autosave).file_open);
+
+        press_at(&mut ui, -4., 4.3);
+        build(&mut ui, &mut autosave);
+        press_at(&mut ui, -4., 1.5);
+        build(&mut ui, &mut autosave);
+        assert!(autosave);
+        assert!(!build(&mut ui, &mut autosave).file_open);
+
+        // The label below the bar, from y = 1.7 to 3.1, opens its menu at the pointer
+        ui.register_event(
+            Event::MouseButtonPressed(MouseButton::Right),
+            Some(&ray_at(-4., 2.5)),
+        );
+        build(&mut ui, &mut autosave);
+        press_at(&mut ui, -3., 2.);
+        assert!(build(&mut ui, &mut autosave).copy);
+    }
+}
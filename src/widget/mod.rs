@@ -1,27 +1,87 @@
 //! Module containing the default and basic widgets offered by penGui
 
+pub use self::bar_chart_3d::*;
 pub use self::button::*;
 pub use self::button_3d::*;
 pub use self::center::*;
 pub use self::checkbox::*;
+pub use self::collapsing_header::*;
+pub use self::color_picker::*;
+pub use self::combo_box::*;
+pub use self::drag_value::*;
 pub use self::frame_counter::*;
+pub use self::grid::*;
+pub use self::image::*;
 pub use self::inline::*;
+pub use self::input::*;
 pub use self::label::*;
 pub use self::maximizer::*;
+pub use self::menu::*;
+pub use self::modal::*;
+pub use self::numeric::*;
 pub use self::padding::*;
+pub use self::plot::*;
+pub use self::popup_layer::*;
+pub use self::progress_bar::*;
+pub use self::radio::*;
+pub use self::scroll_area::*;
+pub use self::selectable::*;
+pub use self::separator::*;
+pub use self::sized_box::*;
+pub use self::slider::*;
+pub use self::spacer::*;
+pub use self::spinner::*;
+pub use self::surface_plot::*;
+pub use self::tab_bar::*;
+pub use self::table::*;
 pub use self::text::*;
+pub use self::tooltip::*;
+pub use self::tree_node::*;
+pub use self::virtual_list::*;
+pub use self::vstack::*;
 pub use self::window::*;
 pub use self::window_handler::*;
+pub use self::wrapped::*;
 
+pub mod bar_chart_3d;
 pub mod button;
 pub mod button_3d;
 pub mod center;
 pub mod checkbox;
+pub mod collapsing_header;
+pub mod color_picker;
+pub mod combo_box;
+pub mod drag_value;
 pub mod frame_counter;
+pub mod grid;
+pub mod image;
 pub mod inline;
+pub mod input;
 pub mod label;
 pub mod maximizer;
+pub mod menu;
+pub mod modal;
+pub mod numeric;
 pub mod padding;
+pub mod plot;
+pub mod popup_layer;
+pub mod progress_bar;
+pub mod radio;
+pub mod scroll_area;
+pub mod selectable;
+pub mod separator;
+pub mod sized_box;
+pub mod slider;
+pub mod spacer;
+pub mod spinner;
+pub mod surface_plot;
+pub mod tab_bar;
+pub mod table;
 pub mod text;
+pub mod tooltip;
+pub mod tree_node;
+pub mod virtual_list;
+pub mod vstack;
 pub mod window;
 pub mod window_handler;
+pub mod wrapped;
@@ -0,0 +1,237 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::Similarity3;
+
+use crate::core::*;
+use crate::loc;
+use crate::widget::{Button, InlineBuilder, LabelBuilder, Window, WindowBuilder};
+
+/// Builds the content of a modal, returning its response once the user answered
+type ContentGenerator<'a, T> = Box<dyn 'a + FnMut(&NodeReference) -> Option<ModalResponse<T>>>;
+
+/// The answer given to a modal dialog
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ModalResponse<T = ()> {
+    Ok,
+    Cancel,
+    Custom(T),
+}
+
+/// A modal dialog, like a confirmation, blocking the rest of the interface while it is open.
+///
+/// Modals are built in `Interface::modals`, like windows in `Interface::root`.
+/// A modal is open as long as it is built: while one is open, the rest of the interface
+/// is dimmed, and only the modal and the popups opened from it get input.
+///
+/// The generator builds the content like the one of a window, and returns a response
+/// once the user gave one. `build` returns it, so that the application can stop
+/// building the modal.
+pub struct ModalBuilder<'a, T> {
+    title: String,
+    size: (f32, f32),
+    transform: Similarity3<f32>,
+    title_font: Option<Rc<RefCell<dyn FontAtlas>>>,
+    generator: Option<ContentGenerator<'a, T>>,
+}
+
+impl<'a, T> ModalBuilder<'a, T> {
+    pub fn new<F: 'a + FnMut(&NodeReference) -> Option<ModalResponse<T>>>(generator: F) -> Self {
+        Self {
+            title: String::new(),
+            size: (10., 5.),
+            transform: Similarity3::identity(),
+            title_font: None,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    pub fn title(self, title: String) -> Self {
+        Self { title, ..self }
+    }
+
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self { size, ..self }
+    }
+
+    pub fn transform(self, transform: Similarity3<f32>) -> Self {
+        Self { transform, ..self }
+    }
+
+    /// Displays the title in a bar at the top of the modal, that can be dragged to move it
+    pub fn title_bar(self, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            title_font: Some(font.clone()),
+            ..self
+        }
+    }
+
+    /// The window displaying the modal, with the given generator
+    fn into_window<'b, F: 'b + FnMut(&NodeReference)>(self, generator: F) -> WindowBuilder<'b> {
+        let window = WindowBuilder::new(generator)
+            .title(self.title)
+            .size(self.size)
+            .transform(self.transform);
+        match &self.title_font {
+            Some(font) => window.title_bar(font),
+            None => window,
+        }
+    }
+}
+
+impl<'a> ModalBuilder<'a, ()> {
+    /// A dialog displaying the message, with "OK" and "Cancel" buttons
+    pub fn confirm(message: &'a str, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        let font = font.clone();
+        Self::new(move |modal| {
+            LabelBuilder::new(message, &font).build(loc!(), modal);
+            let mut response = None;
+            InlineBuilder::new(|row| {
+                if Button::new("OK".to_string(), &font).build(loc!(), row) {
+                    response = Some(ModalResponse::Ok);
+                }
+                if Button::new("Cancel".to_string(), &font).build(loc!(), row) {
+                    response = Some(ModalResponse::Cancel);
+                }
+            })
+            .build(loc!(), modal);
+            response
+        })
+    }
+}
+
+impl<'a, T> WidgetBuilder for ModalBuilder<'a, T> {
+    type AchievedType = Window;
+    type UpdateFeedback = ();
+    type BuildFeedback = Option<ModalResponse<T>>;
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        self.into_window(|_| ()).update(metadata, widget)
+    }
+
+    fn create(self) -> Self::AchievedType {
+        self.into_window(|_| ()).create()
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let mut generator = self.generator.take().unwrap();
+        let mut response = None;
+        self.into_window(|window| {
+            if let Some(answer) = (generator)(window) {
+                response = Some(answer);
+            }
+        })
+        .build(loc, parent);
+        response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_font, press_at, ray_at};
+    use crate::widget::{SliderBuilder, TextBuilder};
+
+    #[test]
+    fn block_input_behind() {
+        let font = mock_font();
+        let mut text = "Hi".to_string();
+        let mut ui = Interface::new();
+        // Returns whether the button behind the modal was pressed, and the response of the modal
+        let build = |ui: &mut Interface, text: &mut String, with_modal: bool| {
+            ui.new_frame();
+            let mut pressed = false;
+            WindowBuilder::new(|window| {
+                pressed = Button::new("Behind".to_string(), &font).build(loc!(), window);
+                TextBuilder::new(text, &font).build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            let mut response = None;
+            if with_modal {
+                response = ModalBuilder::confirm("Sure?", &font)
+                    .size((10., 6.))
+                    .build(loc!(), &ui.modals);
+            }
+            ui.end_frame();
+            ui.generate_layout();
+            (pressed, response)
+        };
+
+        // The button goes from y = 3.6 to 5, and the text from y = 2.1 to 3.1
+        build(&mut ui, &mut text, false);
+        press_at(&mut ui, -4.9, 2.6);
+        ui.register_event(Event::Character('a'), None);
+        build(&mut ui, &mut text, false);
+        assert_eq!(text, "aHi");
+        assert!(!ui.draw().list[0].dimmed);
+
+        // The modal goes from y = -3 to 3: the button and the text are outside of it
+        build(&mut ui, &mut text, true);
+        assert!(ui.is_modal_open());
+        assert!(ui.draw().list[0].dimmed);
+        ui.register_event(Event::Character('b'), None);
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui, &mut text, true), (false, None));
+        assert_eq!(text, "aHi");
+
+        // Its buttons are below the message, "OK" going from x = -5 to -3.6
+        press_at(&mut ui, -4.3, 0.4);
+        assert_eq!(
+            build(&mut ui, &mut text, true),
+            (false, Some(ModalResponse::Ok))
+        );
+
+        build(&mut ui, &mut text, false);
+        assert!(!ui.is_modal_open());
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui, &mut text, false), (true, None));
+    }
+
+    #[test]
+    fn release_capture_behind() {
+        let font = mock_font();
+        let mut value = 0i32;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, value: &mut i32, with_modal: bool| {
+            ui.new_frame();
+            WindowBuilder::new(|window| {
+                SliderBuilder::new(value, 0, 10, &font)
+                    .length(10.)
+                    .build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            if with_modal {
+                ModalBuilder::confirm("Sure?", &font)
+                    .size((10., 6.))
+                    .build(loc!(), &ui.modals);
+            }
+            ui.end_frame();
+            ui.generate_layout();
+        };
+
+        // The slider is being dragged when the modal opens
+        build(&mut ui, &mut value, false);
+        press_at(&mut ui, 0., 4.5);
+        build(&mut ui, &mut value, true);
+        assert_eq!(value, 5);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(20., 20.)));
+        build(&mut ui, &mut value, true);
+        assert_eq!(value, 5);
+
+        // The release still ends the drag, so the slider ignores the pointer once the modal is closed
+        ui.register_event(
+            Event::MouseButtonReleased(MouseButton::Left),
+            Some(&ray_at(20., 20.)),
+        );
+        build(&mut ui, &mut value, false);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-20., 4.5)));
+        build(&mut ui, &mut value, false);
+        assert_eq!(value, 5);
+    }
+}
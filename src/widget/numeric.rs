@@ -0,0 +1,169 @@
+//! Helpers shared by the widgets editing numbers, like `SliderBuilder` and `DragValueBuilder`
+
+/// A numeric type that can be edited by sliders and drag values.
+/// Computations are done in `f64`, and converted back afterwards
+pub trait Numeric: Copy + PartialEq {
+    /// Whether the type can only hold integers
+    const INTEGER: bool;
+
+    fn to_f64(self) -> f64;
+
+    /// Converts back a value, rounding it if the type holds integers
+    fn from_f64(value: f64) -> Self;
+}
+
+macro_rules! integer_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                const INTEGER: bool = true;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value.round() as Self
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! float_numeric {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                const INTEGER: bool = false;
+
+                fn to_f64(self) -> f64 {
+                    self as f64
+                }
+
+                fn from_f64(value: f64) -> Self {
+                    value as Self
+                }
+            }
+        )*
+    };
+}
+
+integer_numeric!(i32, i64);
+float_numeric!(f32, f64);
+
+/// The range a numeric value can take:
+/// - `min` and `max` are the bounds, which can be infinite
+/// - `step`, if any, is the interval between two possible values, starting at `min`
+/// - `logarithmic` indicates that the value evolves exponentially along a slider.
+///   It is ignored if `min` is not strictly positive
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NumericRange {
+    pub min: f64,
+    pub max: f64,
+    pub step: Option<f64>,
+    pub logarithmic: bool,
+}
+
+impl NumericRange {
+    pub fn new(min: f64, max: f64) -> Self {
+        Self {
+            min: min.min(max),
+            max: max.max(min),
+            step: None,
+            logarithmic: false,
+        }
+    }
+
+    /// A range without bounds
+    pub fn unbounded() -> Self {
+        Self::new(f64::NEG_INFINITY, f64::INFINITY)
+    }
+
+    fn is_logarithmic(&self) -> bool {
+        self.logarithmic && self.min > 0.
+    }
+
+    /// Converts a value to its relative position in the range, between 0 and 1
+    pub fn normalize(&self, value: f64) -> f64 {
+        let value = value.max(self.min).min(self.max);
+        let t = if self.is_logarithmic() {
+            (value / self.min).ln() / (self.max / self.min).ln()
+        } else {
+            (value - self.min) / (self.max - self.min)
+        };
+        if t.is_finite() {
+            t
+        } else {
+            0.
+        }
+    }
+
+    /// Converts a relative position in the range, between 0 and 1, to a value
+    pub fn denormalize(&self, t: f64) -> f64 {
+        let t = t.clamp(0., 1.);
+        if self.is_logarithmic() {
+            self.min * (self.max / self.min).powf(t)
+        } else {
+            self.min + t * (self.max - self.min)
+        }
+    }
+
+    /// Clamps the value in the range, and rounds it to the closest step
+    pub fn snap(&self, value: f64) -> f64 {
+        let value = match self.step {
+            Some(step) if step > 0. => {
+                let origin = if self.min.is_finite() { self.min } else { 0. };
+                origin + ((value - origin) / step).round() * step
+            }
+            _ => value,
+        };
+        value.max(self.min).min(self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        assert_eq!(i32::from_f64(2.6), 3);
+        assert_eq!(i64::from_f64(-2.6), -3);
+        assert_eq!(f32::from_f64(2.5), 2.5);
+    }
+
+    #[test]
+    fn linear_range() {
+        let range = NumericRange::new(-10., 10.);
+        assert_eq!(range.normalize(0.), 0.5);
+        assert_eq!(range.normalize(20.), 1.);
+        assert_eq!(range.denormalize(0.25), -5.);
+        assert_eq!(range.denormalize(-1.), -10.);
+    }
+
+    #[test]
+    fn logarithmic_range() {
+        let mut range = NumericRange::new(1., 100.);
+        range.logarithmic = true;
+        assert!((range.normalize(10.) - 0.5).abs() < 1e-9);
+        assert!((range.denormalize(0.5) - 10.).abs() < 1e-9);
+
+        // Not strictly positive, so linear
+        let mut range = NumericRange::new(0., 100.);
+        range.logarithmic = true;
+        assert_eq!(range.denormalize(0.5), 50.);
+    }
+
+    #[test]
+    fn steps() {
+        let mut range = NumericRange::new(1., 10.);
+        range.step = Some(2.);
+        assert_eq!(range.snap(4.2), 5.);
+        assert_eq!(range.snap(11.), 10.);
+        assert_eq!(range.snap(-3.), 1.);
+
+        let mut range = NumericRange::unbounded();
+        range.step = Some(0.5);
+        assert_eq!(range.snap(-1.3), -1.5);
+    }
+}
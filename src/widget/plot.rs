@@ -0,0 +1,650 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3, Vector2, Vector3};
+
+use crate::core::*;
+
+/// A plot of series of `(x, y)` points, drawn as lines or as scattered points.
+///
+/// The plot draws its axes, with grid lines and labels on regularly spaced ticks.
+/// The ranges of the axes fit the points, unless they are given with `x_range` and `y_range`.
+/// When the pointer is near a point, its coordinates are displayed over the plot, and `build`
+/// returns the index of its series with the point.
+pub struct PlotBuilder {
+    series: Vec<Series>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    size: (f32, f32),
+    x_range: Option<(f32, f32)>,
+    y_range: Option<(f32, f32)>,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const GRID: (f32, f32, f32, f32) = (0.25, 0.27, 0.32, 1.);
+const AXES: (f32, f32, f32, f32) = (0.6, 0.6, 0.6, 1.);
+
+/// How the points of a series are drawn
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum SeriesStyle {
+    Line,
+    Scatter,
+}
+
+struct Series {
+    points: Vec<(f32, f32)>,
+    color: (f32, f32, f32, f32),
+    style: SeriesStyle,
+}
+
+impl PlotBuilder {
+    pub fn new(font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            series: Vec::new(),
+            font: font.clone(),
+            font_size: 0.5,
+            size: (12., 8.),
+            x_range: None,
+            y_range: None,
+            color: BACKGROUND,
+            text_color: TEXT,
+        }
+    }
+
+    /// Adds a series drawn as a line joining its points, in order
+    pub fn line<I: IntoIterator<Item = (f32, f32)>>(
+        self,
+        points: I,
+        color: (f32, f32, f32, f32),
+    ) -> Self {
+        self.with_series(points, color, SeriesStyle::Line)
+    }
+
+    /// Adds a series drawn as separate points
+    pub fn scatter<I: IntoIterator<Item = (f32, f32)>>(
+        self,
+        points: I,
+        color: (f32, f32, f32, f32),
+    ) -> Self {
+        self.with_series(points, color, SeriesStyle::Scatter)
+    }
+
+    fn with_series<I: IntoIterator<Item = (f32, f32)>>(
+        mut self,
+        points: I,
+        color: (f32, f32, f32, f32),
+        style: SeriesStyle,
+    ) -> Self {
+        self.series.push(Series {
+            points: points.into_iter().collect(),
+            color,
+            style,
+        });
+        self
+    }
+
+    /// Sets the size of the plot, when it is not maximized by its parent
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets the size of the labels
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Fixes the range of the horizontal axis, instead of fitting the points
+    pub fn x_range(self, min: f32, max: f32) -> Self {
+        Self {
+            x_range: Some((min, max)),
+            ..self
+        }
+    }
+
+    /// Fixes the range of the vertical axis, instead of fitting the points
+    pub fn y_range(self, min: f32, max: f32) -> Self {
+        Self {
+            y_range: Some((min, max)),
+            ..self
+        }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl WidgetBuilder for PlotBuilder {
+    type AchievedType = Plot;
+    type UpdateFeedback = Option<(usize, (f32, f32))>;
+    type BuildFeedback = Option<(usize, (f32, f32))>;
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let pointer = metadata.hover_duration().and(metadata.pointer_position());
+        *widget = self.create();
+        widget.hovered = pointer.and_then(|pointer| widget.nearest(metadata.size, pointer));
+        widget
+            .hovered
+            .map(|(series, index)| (series, widget.series[series].points[index]))
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let fit = |range: Option<(f32, f32)>, coordinate: fn(&(f32, f32)) -> f32| match range {
+            Some((min, max)) => Axis::fixed(min, max),
+            None => {
+                let values = self.series.iter().flat_map(|series| &series.points);
+                let (min, max) = values
+                    .map(coordinate)
+                    .filter(|value| value.is_finite())
+                    .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                        (min.min(value), max.max(value))
+                    });
+                Axis::fit(min, max)
+            }
+        };
+        let x_axis = fit(self.x_range, |point| point.0);
+        let y_axis = fit(self.y_range, |point| point.1);
+        Plot {
+            series: self.series,
+            font: self.font,
+            font_size: self.font_size,
+            size: self.size,
+            x_axis,
+            y_axis,
+            hovered: None,
+            color: self.color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, hovered) = parent.query::<Self::AchievedType>(id).update(self);
+        hovered
+    }
+}
+
+/// The range of an axis of a plot, with the interval between its ticks
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Axis {
+    min: f32,
+    max: f32,
+    step: f32,
+}
+
+/// Number of intervals between ticks aimed at on each axis
+const TICKS: f32 = 5.;
+
+impl Axis {
+    /// An axis with the given range, with ticks inside it
+    fn fixed(min: f32, max: f32) -> Self {
+        let (min, max) = if max > min {
+            (min, max)
+        } else {
+            (min, min + 1.)
+        };
+        Self {
+            min,
+            max,
+            step: nice_step((max - min) / TICKS),
+        }
+    }
+
+    /// An axis fitting the values between `min` and `max`, extended to the nearest ticks
+    fn fit(min: f32, max: f32) -> Self {
+        let (min, max) = if !min.is_finite() || !max.is_finite() {
+            (0., 1.)
+        } else if max - min < f32::EPSILON * max.abs().max(1.) {
+            (min - 0.5, max + 0.5)
+        } else {
+            (min, max)
+        };
+        let step = nice_step((max - min) / TICKS);
+        Self {
+            min: (min / step).floor() * step,
+            max: (max / step).ceil() * step,
+            step,
+        }
+    }
+
+    /// The values of the ticks inside the range
+    fn ticks(&self) -> impl Iterator<Item = f32> {
+        let step = self.step;
+        let first = (self.min / step - 1e-3).ceil() as i64;
+        let last = (self.max / step + 1e-3).floor() as i64;
+        (first..=last).map(move |tick| tick as f32 * step)
+    }
+
+    /// Where the value is on the axis, from 0 at its minimum to 1 at its maximum
+    fn normalize(&self, value: f32) -> f32 {
+        (value - self.min) / (self.max - self.min)
+    }
+
+    /// The number of decimals needed to tell the ticks apart
+    fn precision(&self) -> usize {
+        (-(self.step.log10() + 1e-3).floor()).max(0.) as usize
+    }
+}
+
+/// Rounds an interval to 1, 2 or 5 times a power of ten
+fn nice_step(raw: f32) -> f32 {
+    let magnitude = 10f32.powf(raw.log10().floor());
+    let normalized = raw / magnitude;
+    let nice = if normalized < 1.5 {
+        1.
+    } else if normalized < 3. {
+        2.
+    } else if normalized < 7. {
+        5.
+    } else {
+        10.
+    };
+    nice * magnitude
+}
+
+/// The widget behind a `PlotBuilder`
+pub struct Plot {
+    series: Vec<Series>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    size: (f32, f32),
+    x_axis: Axis,
+    y_axis: Axis,
+    /// The series and the index of the point near the pointer
+    hovered: Option<(usize, usize)>,
+    color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+/// Distance between the labels and the axes
+const LABEL_GAP: f32 = 0.2;
+/// Size of the points of a scatter series, and of the marker on the point hovered
+const POINT_SIZE: f32 = 0.2;
+/// Maximal distance from the pointer to a point for it to be hovered
+const HOVER_DISTANCE: f32 = 0.5;
+
+impl Plot {
+    /// The labels of the ticks of an axis, with their value
+    fn labels(&self, axis: &Axis) -> Vec<(f32, String)> {
+        let precision = axis.precision();
+        axis.ticks()
+            .map(|tick| (tick, format!("{:.*}", precision, tick)))
+            .collect()
+    }
+
+    /// The rectangle where the points are drawn, inside the labels, as its bottom left corner
+    /// and its top right corner
+    fn area(&self, size: (f32, f32)) -> ((f32, f32), (f32, f32)) {
+        let font = self.font.borrow();
+        let label_width = self
+            .labels(&self.y_axis)
+            .iter()
+            .map(|(_, label)| font.size_of(label.as_str(), self.font_size).0)
+            .fold(0., f32::max);
+        (
+            (
+                PADDING + label_width + LABEL_GAP,
+                PADDING + self.font_size + LABEL_GAP,
+            ),
+            (
+                size.0 - PADDING - self.font_size,
+                size.1 - PADDING - self.font_size / 2.,
+            ),
+        )
+    }
+
+    /// Places a point of the plot in the local space of the widget
+    fn place(&self, area: ((f32, f32), (f32, f32)), (x, y): (f32, f32)) -> (f32, f32) {
+        let ((left, bottom), (right, top)) = area;
+        (
+            left + self.x_axis.normalize(x) * (right - left),
+            bottom + self.y_axis.normalize(y) * (top - bottom),
+        )
+    }
+
+    /// Finds the point nearest to the pointer, if it is close enough
+    fn nearest(&self, size: (f32, f32), pointer: (f32, f32)) -> Option<(usize, usize)> {
+        let area = self.area(size);
+        self.series
+            .iter()
+            .enumerate()
+            .flat_map(|(series, content)| {
+                content
+                    .points
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, point)| ((series, index), *point))
+            })
+            .map(|(indices, point)| {
+                let (x, y) = self.place(area, point);
+                let distance = ((x - pointer.0).powi(2) + (y - pointer.1).powi(2)).sqrt();
+                (indices, distance)
+            })
+            .filter(|(_, distance)| *distance <= HOVER_DISTANCE)
+            .min_by(|(_, d1), (_, d2)| d1.partial_cmp(d2).unwrap())
+            .map(|(indices, _)| indices)
+    }
+
+    fn text(&self, text: &str, position: (f32, f32), transform: Similarity3<f32>) -> DrawCommand {
+        draw_text(
+            text,
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (transform * Translation3::new(position.0, position.1, 0.02)).to_homogeneous(),
+        )
+    }
+}
+
+/// Builds a command drawing lines between the given points, joined if `strip` is set,
+/// or in disjoint pairs otherwise
+fn lines(
+    points: &[(f32, f32)],
+    color: (f32, f32, f32, f32),
+    strip: bool,
+    depth: f32,
+) -> DrawCommand {
+    DrawCommand {
+        vertex_buffer: points
+            .iter()
+            .map(|&(x, y)| Vertex {
+                position: Vector3::new(x, y, depth),
+                color,
+                tex_uv: Vector2::new(0., 0.),
+            })
+            .collect(),
+        index_buffer: (0..points.len() as u32).collect(),
+        draw_mode: if strip {
+            DrawMode::LineStrip
+        } else {
+            DrawMode::Lines
+        },
+        uniforms: Uniforms::new(),
+    }
+}
+
+impl WidgetLogic for Plot {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let axis = |size: f32, available: Option<f32>, objective: Objective| match available {
+            Some(available) if size > available => (available, LayoutStatus::Inconsistencies),
+            Some(available) if matches!(objective, Objective::Maximize) => {
+                (available, LayoutStatus::Ok)
+            }
+            _ => (size, LayoutStatus::Ok),
+        };
+        let (width, x_status) = axis(self.size.0, query.available_space.0, query.objectives.0);
+        let (height, y_status) = axis(self.size.1, query.available_space.1, query.objectives.1);
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let transform = metadata.transform;
+        let area = self.area(size);
+        let ((left, bottom), (right, top)) = area;
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, transform));
+
+        // Grid lines and labels on the ticks
+        let mut grid = Vec::new();
+        let width_of = |text: &str| self.font.borrow().size_of(text, self.font_size).0;
+        for (tick, label) in self.labels(&self.x_axis) {
+            let (x, _) = self.place(area, (tick, self.y_axis.min));
+            grid.extend_from_slice(&[(x, bottom), (x, top)]);
+            let position = (x - width_of(&label) / 2., PADDING);
+            list.commands.push(self.text(&label, position, transform));
+        }
+        for (tick, label) in self.labels(&self.y_axis) {
+            let (_, y) = self.place(area, (self.x_axis.min, tick));
+            grid.extend_from_slice(&[(left, y), (right, y)]);
+            let position = (left - LABEL_GAP - width_of(&label), y - self.font_size / 2.);
+            list.commands.push(self.text(&label, position, transform));
+        }
+        let mut grid = lines(&grid, GRID, false, 0.005);
+        grid.uniforms.model_matrix = transform.to_homogeneous();
+        list.commands.push(grid);
+        let mut axes = lines(
+            &[(left, top), (left, bottom), (right, bottom)],
+            AXES,
+            true,
+            0.01,
+        );
+        axes.uniforms.model_matrix = transform.to_homogeneous();
+        list.commands.push(axes);
+
+        // The series are clipped to the area, in front of the grid
+        let mut series = DrawList::new();
+        for content in &self.series {
+            let points = content
+                .points
+                .iter()
+                .map(|point| self.place(area, *point))
+                .collect::<Vec<_>>();
+            series.commands.push(match content.style {
+                SeriesStyle::Line => lines(&points, content.color, true, 0.015),
+                SeriesStyle::Scatter => {
+                    let half = POINT_SIZE / 2.;
+                    let rects = points
+                        .iter()
+                        .map(|(x, y)| (x - half, y - half, POINT_SIZE, POINT_SIZE))
+                        .collect::<Vec<_>>();
+                    rectangles(
+                        &rects,
+                        content.color,
+                        Similarity3::identity() * Translation3::new(0., 0., 0.015),
+                    )
+                }
+            });
+        }
+        list.list.push(series);
+        list.list_transform = transform.to_homogeneous();
+        list.clip = Some((left, bottom, right - left, top - bottom));
+
+        // The coordinates of the point hovered, in the top left corner of the area
+        if let Some((series, index)) = self.hovered {
+            let content = &self.series[series];
+            let point = content.points[index];
+            let (x, y) = self.place(area, point);
+            let half = POINT_SIZE;
+            list.commands.push(quad(
+                2. * half,
+                2. * half,
+                None,
+                content.color,
+                transform * Translation3::new(x - half, y - half, 0.02),
+            ));
+
+            let precision = (self.x_axis.precision() + 1, self.y_axis.precision() + 1);
+            let readout = format!("{:.*}, {:.*}", precision.0, point.0, precision.1, point.1);
+            let (width, height) = self.font.borrow().size_of(readout.as_str(), self.font_size);
+            let corner = (left + PADDING, top - PADDING - height - 2. * PADDING);
+            list.commands.push(quad(
+                width + 2. * PADDING,
+                height + 2. * PADDING,
+                None,
+                self.color,
+                transform * Translation3::new(corner.0, corner.1, 0.025),
+            ));
+            let position = (corner.0 + PADDING, corner.1 + PADDING);
+            let mut text = self.text(&readout, position, transform);
+            text.uniforms.model_matrix *= Translation3::new(0., 0., 0.01).to_homogeneous();
+            list.commands.push(text);
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+}
+
+/// A buffer keeping the last values pushed, up to its capacity, to plot streamed values
+/// like per-frame metrics: `PlotBuilder::line(buffer.iter().copied(), color)` plots them
+pub struct RingBuffer<T> {
+    values: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            values: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Adds a value, removing the oldest one if the buffer is full
+    pub fn push(&mut self, value: T) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.values.len() == self.capacity {
+            self.values.pop_front();
+        }
+        self.values.push_back(value);
+    }
+
+    /// Iterates over the values, from the oldest to the newest
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.values.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the newest value
+    pub fn last(&self) -> Option<&T> {
+        self.values.back()
+    }
+
+    pub fn clear(&mut self) {
+        self.values.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, ray_at};
+
+    #[test]
+    fn axes() {
+        let axis = Axis::fit(0.3, 9.2);
+        assert_eq!(
+            axis,
+            Axis {
+                min: 0.,
+                max: 10.,
+                step: 2.
+            }
+        );
+        assert_eq!(axis.ticks().collect::<Vec<_>>(), [0., 2., 4., 6., 8., 10.]);
+        assert_eq!(axis.precision(), 0);
+        let flat = Axis::fit(1., 1.);
+        assert!(flat.min < 1. && flat.max > 1.);
+        assert_eq!(Axis::fit(f32::INFINITY, f32::NEG_INFINITY).min, 0.);
+
+        let axis = Axis::fixed(0., 0.5);
+        assert!((axis.step - 0.1).abs() < 1e-6);
+        assert_eq!(axis.precision(), 1);
+        assert_eq!(axis.normalize(0.25), 0.5);
+    }
+
+    #[test]
+    fn hover_nearest_point() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            let mut hovered = None;
+            build_in_window(ui, |window| {
+                hovered = PlotBuilder::new(&font)
+                    .size((10., 10.))
+                    .font_size(1.)
+                    .x_range(0., 10.)
+                    .y_range(0., 10.)
+                    .line(vec![(0., 0.), (5., 5.)], (1., 0., 0., 1.))
+                    .scatter(vec![(5., 6.)], (0., 1., 0., 1.))
+                    .build(loc!(), window);
+            });
+            hovered
+        };
+
+        // The labels of the vertical axis are at most 1 wide, for "10": the area goes
+        // from x = 1.4 to 8.8, and from y = 1.4 to 9.3, in the window going from -5 to 5
+        assert_eq!(build(&mut ui), None);
+        let (x, y) = (-5. + 1.4 + 0.5 * 7.4, -5. + 1.4 + 0.5 * 7.9);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(x + 0.1, y)));
+        assert_eq!(build(&mut ui), Some((0, (5., 5.))));
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(x, y + 0.7)));
+        assert_eq!(build(&mut ui), Some((1, (5., 6.))));
+
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-4., 4.)));
+        assert_eq!(build(&mut ui), None);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(20., 20.)));
+        assert_eq!(build(&mut ui), None);
+    }
+
+    #[test]
+    fn ring_buffer() {
+        let mut buffer = RingBuffer::new(2);
+        assert!(buffer.is_empty());
+        buffer.push(1);
+        buffer.push(2);
+        buffer.push(3);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), [2, 3]);
+        assert_eq!((buffer.len(), buffer.last()), (2, Some(&3)));
+    }
+}
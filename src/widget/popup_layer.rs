@@ -0,0 +1,104 @@
+use crate::core::*;
+
+/// The layer of an interface holding popups, like the list of a `ComboBoxBuilder`.
+///
+/// Popups are usually windows, placed directly in the space of the interface,
+/// slightly in front of the widget opening them. They are rebuilt every frame
+/// by their owner, and removed at the end of the frames they were not built.
+pub struct PopupLayer {
+    popups: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl PopupLayer {
+    pub fn new() -> Self {
+        Self {
+            popups: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    /// Invalidates all popups, at the beginning of a frame
+    pub fn invalidate_content(&mut self) {
+        self.popups.iter().for_each(|popup| popup.invalidate());
+        self.valid_index = 0;
+    }
+
+    /// Removes the popups that were not rebuilt, at the end of a frame
+    pub fn remove_invalid(&mut self) {
+        self.popups.retain(|popup| popup.is_valid());
+    }
+}
+
+impl Default for PopupLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetBuilder for PopupLayer {
+    type AchievedType = PopupLayer;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        _widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+    }
+
+    fn create(self) -> Self::AchievedType {
+        self
+    }
+
+    fn build(self, _loc: CodeLocation, _parent: &NodeReference) -> Self::BuildFeedback {}
+}
+
+impl WidgetLogic for PopupLayer {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.popups, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
+        // Like windows, popups are placed by their builder
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        for node in &self.popups {
+            let response = node.layout(&LayoutQuery {
+                available_space: (None, None),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+        }
+        LayoutResponse {
+            size: (0., 0.),
+            status,
+        }
+    }
+
+    fn draw(&self, _metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        self.popups.iter().for_each(|node| {
+            list.list.push(node.draw());
+        });
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.popups
+            .iter()
+            .flat_map(|popup| popup.interaction_distance(&new_ray, popup.clone()))
+            .collect()
+    }
+}
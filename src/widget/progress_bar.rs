@@ -0,0 +1,262 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::Translation3;
+
+use crate::core::*;
+
+/// A bar showing the progress of an operation, as a fraction between 0 and 1.
+///
+/// A text, like a percentage, can be displayed over the bar. When the progress is unknown,
+/// the bar is `indeterminate`: a chunk of it goes back and forth, driven by the time
+/// elapsed between frames.
+pub struct ProgressBarBuilder {
+    fraction: f32,
+    indeterminate: bool,
+    text: Option<(String, Rc<RefCell<dyn FontAtlas>>)>,
+    font_size: f32,
+    width: f32,
+    color: (f32, f32, f32, f32),
+    bar_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const BAR: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl ProgressBarBuilder {
+    pub fn new(fraction: f32) -> Self {
+        Self {
+            fraction,
+            indeterminate: false,
+            text: None,
+            font_size: 1.,
+            width: 8.,
+            color: BACKGROUND,
+            bar_color: BAR,
+            text_color: TEXT,
+        }
+    }
+
+    /// Animates the bar instead of showing the fraction, for operations of unknown length
+    pub fn indeterminate(self) -> Self {
+        Self {
+            indeterminate: true,
+            ..self
+        }
+    }
+
+    /// Displays the text centered over the bar
+    pub fn text(self, text: String, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            text: Some((text, font.clone())),
+            ..self
+        }
+    }
+
+    /// Sets the width of the bar, when it is not maximized by its parent
+    pub fn width(self, width: f32) -> Self {
+        Self { width, ..self }
+    }
+
+    /// Sets the size of the text, the height of the bar following it
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn bar_color(self, bar_color: (f32, f32, f32, f32)) -> Self {
+        Self { bar_color, ..self }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl WidgetBuilder for ProgressBarBuilder {
+    type AchievedType = ProgressBar;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        if self.indeterminate {
+            let elapsed = metadata.delta_time().as_secs_f32() / PERIOD;
+            widget.phase = (widget.phase + elapsed) % 1.;
+        } else {
+            widget.phase = 0.;
+        }
+        widget.fraction = self.fraction.clamp(0., 1.);
+        widget.indeterminate = self.indeterminate;
+        widget.text = self.text;
+        widget.font_size = self.font_size;
+        widget.width = self.width;
+        widget.color = self.color;
+        widget.bar_color = self.bar_color;
+        widget.text_color = self.text_color;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        ProgressBar {
+            fraction: self.fraction.clamp(0., 1.),
+            indeterminate: self.indeterminate,
+            phase: 0.,
+            text: self.text,
+            font_size: self.font_size,
+            width: self.width,
+            color: self.color,
+            bar_color: self.bar_color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+/// The widget behind a `ProgressBarBuilder`
+pub struct ProgressBar {
+    fraction: f32,
+    indeterminate: bool,
+    /// Position in the back and forth of an indeterminate bar, between 0 and 1
+    phase: f32,
+    text: Option<(String, Rc<RefCell<dyn FontAtlas>>)>,
+    font_size: f32,
+    width: f32,
+    color: (f32, f32, f32, f32),
+    bar_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+/// Duration of a back and forth of an indeterminate bar, in seconds
+const PERIOD: f32 = 2.;
+/// Part of the bar filled by the chunk of an indeterminate bar
+const CHUNK: f32 = 0.25;
+
+impl ProgressBar {
+    /// The start and the end of the filled part of the bar, as fractions of its width
+    fn filled(&self) -> (f32, f32) {
+        if !self.indeterminate {
+            return (0., self.fraction);
+        }
+        // The chunk goes to the right during the first half of the period, and comes back
+        let position = 1. - (2. * self.phase - 1.).abs();
+        let start = position * (1. - CHUNK);
+        (start, start + CHUNK)
+    }
+}
+
+impl WidgetLogic for ProgressBar {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = (self.width, self.font_size + 2. * PADDING);
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(size.0),
+            query.available_space.1.unwrap_or(size.1),
+        );
+        let status = |size: f32, available: f32| {
+            if size <= available {
+                LayoutStatus::Ok
+            } else {
+                LayoutStatus::Inconsistencies
+            }
+        };
+        let width = match query.objectives.0 {
+            Objective::Maximize => available_width,
+            _ => size.0.min(available_width),
+        };
+
+        LayoutResponse {
+            size: (width, size.1.min(available_height)),
+            status: (
+                status(size.0, available_width),
+                status(size.1, available_height),
+            ),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let (start, end) = self.filled();
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+        if end > start {
+            list.commands.push(quad(
+                (end - start) * size.0,
+                size.1,
+                None,
+                self.bar_color,
+                metadata.transform * Translation3::new(start * size.0, 0., 0.01),
+            ));
+        }
+        if let Some((text, font)) = &self.text {
+            let (text_width, _) = font.borrow().size_of(text.as_str(), self.font_size);
+            let position = Translation3::new(
+                (size.0 - text_width) / 2.,
+                (size.1 - self.font_size) / 2.,
+                0.02,
+            );
+            list.commands.push(draw_text(
+                text.as_str(),
+                font,
+                self.font_size,
+                self.text_color,
+                (metadata.transform * position).to_homogeneous(),
+            ));
+        }
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::mock_font;
+    use crate::widget::WindowBuilder;
+    use std::time::Duration;
+
+    #[test]
+    fn fraction_and_animation() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // Builds a frame at the given time, and returns the part of the bar filled
+        let build = |ui: &mut Interface, millis: u64, bar: ProgressBarBuilder| {
+            ui.new_frame_at(Duration::from_millis(millis));
+            let (mut bar, mut filled) = (Some(bar), (0., 0.));
+            WindowBuilder::new(|window| {
+                let id = ComponentId::new::<ProgressBar>(loc!());
+                let (node_ref, _) = window.query::<ProgressBar>(id).update(bar.take().unwrap());
+                node_ref.apply_to_widget::<ProgressBar, _>(|_, widget| filled = widget.filled());
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            filled
+        };
+
+        let bar = || ProgressBarBuilder::new(0.3).text("30 %".to_string(), &font);
+        assert_eq!(build(&mut ui, 0, bar()), (0., 0.3));
+        assert_eq!(build(&mut ui, 100, ProgressBarBuilder::new(1.5)), (0., 1.));
+
+        // A quarter of the period brings the chunk halfway, and half of it to the right end
+        let bar = || ProgressBarBuilder::new(0.).indeterminate();
+        assert_eq!(build(&mut ui, 100, bar()), (0., CHUNK));
+        assert_eq!(build(&mut ui, 600, bar()), (0.375, 0.375 + CHUNK));
+        assert_eq!(build(&mut ui, 1100, bar()), (1. - CHUNK, 1.));
+        assert_eq!(build(&mut ui, 2100, bar()), (0., CHUNK));
+    }
+}
@@ -0,0 +1,490 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3};
+
+use crate::core::*;
+
+/// A group of radio buttons, to choose one of several options.
+///
+/// The group is bound to a value, which is set to the option clicked.
+/// `build` returns whether the value was changed by the user.
+pub struct RadioGroupBuilder<'a, T: PartialEq> {
+    value: Option<&'a mut T>,
+    options: Vec<(T, String)>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    horizontal: bool,
+    selected_color: (f32, f32, f32, f32),
+    unselected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const UNSELECTED: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.349, 0.475, 0.765, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a, T: PartialEq> RadioGroupBuilder<'a, T> {
+    pub fn new(value: &'a mut T, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            value: Some(value),
+            options: Vec::new(),
+            font: font.clone(),
+            horizontal: false,
+            selected_color: SELECTED,
+            unselected_color: UNSELECTED,
+            text_color: TEXT,
+        }
+    }
+
+    /// Adds an option to the group, displayed with the given label
+    pub fn option(mut self, value: T, label: String) -> Self {
+        self.options.push((value, label));
+        self
+    }
+
+    /// Places the options from left to right, instead of from top to bottom
+    pub fn horizontal(self) -> Self {
+        Self {
+            horizontal: true,
+            ..self
+        }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn unselected_color(self, unselected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            unselected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: PartialEq> WidgetBuilder for RadioGroupBuilder<'a, T> {
+    type AchievedType = RadioGroup;
+    type UpdateFeedback = ();
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+        widget.horizontal = self.horizontal;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Self::AchievedType {
+            content: Vec::new(),
+            valid_index: 0,
+            horizontal: self.horizontal,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let value = self.value.take().unwrap();
+        let options = std::mem::take(&mut self.options);
+        let (font, colors) = (
+            self.font.clone(),
+            (self.selected_color, self.unselected_color, self.text_color),
+        );
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut changed = false;
+        for (index, (option, label)) in options.into_iter().enumerate() {
+            let button = RadioButton::new(label, *value == option, &font)
+                .selected_color(colors.0)
+                .unselected_color(colors.1)
+                .text_color(colors.2);
+            let id = ComponentId::new_biased::<RadioButton>(loc, index as UniqueId);
+            let (_, clicked) = node_ref.query::<RadioButton>(id).update(button);
+            if clicked && *value != option {
+                *value = option;
+                changed = true;
+            }
+        }
+
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+        changed
+    }
+}
+
+/// The container behind a `RadioGroupBuilder`, stacking its radio buttons
+pub struct RadioGroup {
+    content: Vec<NodeReference>,
+    valid_index: usize,
+    horizontal: bool,
+}
+
+const OPTION_SEPARATOR: f32 = 0.2;
+
+impl WidgetLogic for RadioGroup {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (mut horizontal_space, mut vertical_space) = query.available_space;
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut responses = Vec::with_capacity(self.content.len());
+        let (mut width, mut height) = (0f32, 0f32);
+
+        for (index, node) in self.content.iter_mut().enumerate() {
+            // Every option but the first is preceded by a separator
+            let separator = if index == 0 { 0. } else { OPTION_SEPARATOR };
+            if self.horizontal {
+                horizontal_space = horizontal_space.map(|x| x - separator);
+            } else {
+                vertical_space = vertical_space.map(|y| y - separator);
+            }
+            let response = node.layout(&LayoutQuery {
+                available_space: (
+                    horizontal_space.map(|x| x.max(0.)),
+                    vertical_space.map(|y| y.max(0.)),
+                ),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            if self.horizontal {
+                width += separator + response.size.0;
+                height = height.max(response.size.1);
+                horizontal_space = horizontal_space.map(|x| x - response.size.0);
+            } else {
+                width = width.max(response.size.0);
+                height += separator + response.size.1;
+                vertical_space = vertical_space.map(|y| y - response.size.1);
+            }
+            responses.push(response.size);
+        }
+
+        // The first option is at the left, or at the top
+        let mut cursor = if self.horizontal { 0. } else { height };
+        for (node, size) in self.content.iter_mut().zip(responses) {
+            node.set_size(size);
+            let translation = if self.horizontal {
+                let translation = Translation3::new(cursor, height - size.1, 0.);
+                cursor += size.0 + OPTION_SEPARATOR;
+                translation
+            } else {
+                cursor -= size.1;
+                let translation = Translation3::new(0., cursor, 0.);
+                cursor -= OPTION_SEPARATOR;
+                translation
+            };
+            node.set_transform(Similarity3::identity() * translation);
+        }
+
+        if horizontal_space.unwrap_or(0.) < 0. {
+            status.0 = LayoutStatus::and(LayoutStatus::Inconsistencies, status.0);
+        }
+        if vertical_space.unwrap_or(0.) < 0. {
+            status.1 = LayoutStatus::and(LayoutStatus::Inconsistencies, status.1);
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status,
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        self.content.iter().for_each(|node| {
+            list.list.push(node.draw());
+        });
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+/// A single radio button, with a label.
+/// It is usually built by a `RadioGroupBuilder`, and returns whether it was clicked
+pub struct RadioButton {
+    label: String,
+    selected: bool,
+    clicked: bool,
+    selected_color: (f32, f32, f32, f32),
+    unselected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    font: Rc<RefCell<dyn FontAtlas>>,
+}
+
+impl RadioButton {
+    pub fn new(label: String, selected: bool, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            label,
+            selected,
+            clicked: false,
+            selected_color: SELECTED,
+            unselected_color: UNSELECTED,
+            text_color: TEXT,
+            font: font.clone(),
+        }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn unselected_color(self, unselected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            unselected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl WidgetBuilder for RadioButton {
+    type AchievedType = RadioButton;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let clicked = widget.clicked;
+        widget.clicked = false;
+        widget.label = self.label;
+        widget.selected = self.selected;
+        widget.selected_color = self.selected_color;
+        widget.unselected_color = self.unselected_color;
+        widget.text_color = self.text_color;
+        clicked
+    }
+
+    fn create(self) -> Self::AchievedType {
+        self
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
+const PADDING: f32 = 0.2;
+const DISC_SIDES: u32 = 24;
+
+impl WidgetLogic for RadioButton {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (label_width, label_height) = self.font.borrow().size_of(self.label.as_str(), 1.);
+
+        let mut width = label_height + PADDING + label_width + 2. * PADDING;
+        let mut height = label_height + 2. * PADDING;
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        if width > available_width {
+            width = available_width;
+        }
+        if height > available_height {
+            height = available_height;
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let radius = size.1 / 2. - PADDING;
+        let center = metadata.transform * Translation3::new(size.1 / 2., size.1 / 2., 0.01);
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(disc(radius, DISC_SIDES, self.unselected_color, center));
+        if self.selected {
+            list.commands.push(disc(
+                radius / 2.,
+                DISC_SIDES,
+                self.selected_color,
+                center * Translation3::new(0., 0., 0.01),
+            ));
+        }
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            1.,
+            self.text_color,
+            (metadata.transform * Translation3::new(size.1, PADDING, 0.01)).to_homogeneous(),
+        ));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, _metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                self.clicked = true;
+                self.selected = true;
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+    use crate::widget::*;
+
+    #[derive(PartialEq, Debug)]
+    enum Shape {
+        Cube,
+        Sphere,
+        Torus,
+    }
+
+    #[test]
+    fn select_option() {
+        let font = mock_font();
+        let mut shape = Shape::Cube;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, shape: &mut Shape| {
+            let mut changed = false;
+            build_in_window(ui, |window| {
+                changed = RadioGroupBuilder::new(shape, &font)
+                    .option(Shape::Cube, "Cube".to_string())
+                    .option(Shape::Sphere, "Sphere".to_string())
+                    .option(Shape::Torus, "Torus".to_string())
+                    .build(loc!(), window);
+            });
+            changed
+        };
+
+        assert!(!build(&mut ui, &mut shape));
+        // Each option is 1.4 high, separated by 0.2, from the top of the window
+        press_at(&mut ui, -4., 5. - 3.2 - 0.7);
+        assert!(build(&mut ui, &mut shape));
+        assert_eq!(shape, Shape::Torus);
+
+        // Clicking the selected option changes nothing
+        press_at(&mut ui, -4., 5. - 3.2 - 0.7);
+        assert!(!build(&mut ui, &mut shape));
+        assert_eq!(shape, Shape::Torus);
+    }
+
+    #[test]
+    fn horizontal_layout() {
+        let font = mock_font();
+        let mut choice = 0;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, choice: &mut usize, width: f32| {
+            ui.new_frame();
+            WindowBuilder::new(|window| {
+                RadioGroupBuilder::new(choice, &font)
+                    .option(0, "A".to_string())
+                    .option(1, "B".to_string())
+                    .option(2, "C".to_string())
+                    .horizontal()
+                    .build(loc!(), window);
+            })
+            .size((width, 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout().status
+        };
+
+        // Each option is 2.1 wide, so the row is 6.7 wide with the separators,
+        // and fits in a slightly larger window
+        let status = build(&mut ui, &mut choice, 6.75);
+        assert_eq!(status, (LayoutStatus::Ok, LayoutStatus::Ok));
+        press_at(&mut ui, -3.375 + 2.3 + 1., 4.3);
+        build(&mut ui, &mut choice, 6.75);
+        assert_eq!(choice, 1);
+
+        // In a narrower window, the row overflows horizontally
+        let status = build(&mut ui, &mut choice, 6.);
+        assert_eq!(status, (LayoutStatus::Inconsistencies, LayoutStatus::Ok));
+    }
+}
@@ -0,0 +1,458 @@
+use nalgebra::*;
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// Builds the widgets of a scroll area in its node
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// The builder for a scrollable area, holding an unlimited number of widgets
+/// displayed from top to bottom.
+///
+/// The content is scrolled with the wheel (horizontally while Shift is held),
+/// by dragging the scrollbars, or by dragging the content itself.
+/// Anything outside of the area is neither drawn nor clickable.
+///
+/// By default, the area takes all the space given by its parent.
+pub struct ScrollAreaBuilder<'a> {
+    size: Option<(f32, f32)>,
+    horizontal: bool,
+    vertical: bool,
+    spacing: f32,
+    generator: Option<ContentGenerator<'a>>,
+}
+
+const SCROLLBAR: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const THUMB: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+
+impl<'a> ScrollAreaBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(generator: F) -> Self {
+        Self {
+            size: None,
+            horizontal: false,
+            vertical: true,
+            spacing: WIDGET_SEPARATOR,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the size of the visible part of the area
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    /// Enables or disables horizontal scrolling, which is disabled by default.
+    /// Without it, the content is as wide as the area
+    pub fn horizontal(self, horizontal: bool) -> Self {
+        Self { horizontal, ..self }
+    }
+
+    /// Enables or disables vertical scrolling, which is enabled by default
+    pub fn vertical(self, vertical: bool) -> Self {
+        Self { vertical, ..self }
+    }
+
+    /// Sets the space between two widgets of the area
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for ScrollAreaBuilder<'a> {
+    type AchievedType = ScrollArea;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.size = self.size;
+        widget.horizontal = self.horizontal;
+        widget.vertical = self.vertical;
+        widget.spacing = self.spacing;
+
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        ScrollArea {
+            size: self.size,
+            horizontal: self.horizontal,
+            vertical: self.vertical,
+            spacing: self.spacing,
+            content: Vec::new(),
+            valid_index: 0,
+            offset: (0., 0.),
+            content_size: (0., 0.),
+            drag: None,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+    }
+}
+
+/// What is being dragged in a scroll area, with the position of the pointer
+/// and the offset when the drag started
+#[derive(Copy, Clone)]
+enum Drag {
+    Content((f32, f32), (f32, f32)),
+    VerticalThumb((f32, f32), (f32, f32)),
+    HorizontalThumb((f32, f32), (f32, f32)),
+}
+
+/// Internal scroll area structure
+pub struct ScrollArea {
+    size: Option<(f32, f32)>,
+    horizontal: bool,
+    vertical: bool,
+    /// The space between two widgets
+    spacing: f32,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+    /// How far the content is scrolled, to the right and to the bottom
+    offset: (f32, f32),
+    content_size: (f32, f32),
+    drag: Option<Drag>,
+}
+
+const SCROLLBAR_WIDTH: f32 = 0.3;
+const MIN_THUMB_LENGTH: f32 = 0.5;
+/// Distance scrolled for each line of the wheel
+const SCROLL_SPEED: f32 = 1.;
+/// Added to the distance of the area itself, so that its content is always in front
+const DEPTH_BIAS: f32 = 1e-3;
+
+impl ScrollArea {
+    /// Returns the size of the part of the area displaying the content
+    fn viewport(&self, size: (f32, f32)) -> (f32, f32) {
+        (
+            if self.vertical {
+                size.0 - SCROLLBAR_WIDTH
+            } else {
+                size.0
+            },
+            if self.horizontal {
+                size.1 - SCROLLBAR_WIDTH
+            } else {
+                size.1
+            },
+        )
+    }
+
+    fn max_offset(&self, size: (f32, f32)) -> (f32, f32) {
+        let viewport = self.viewport(size);
+        (
+            (self.content_size.0 - viewport.0).max(0.),
+            (self.content_size.1 - viewport.1).max(0.),
+        )
+    }
+
+    fn scroll_to(&mut self, offset: (f32, f32), size: (f32, f32)) {
+        let max = self.max_offset(size);
+        self.offset = (offset.0.max(0.).min(max.0), offset.1.max(0.).min(max.1));
+    }
+
+    /// Returns the position and the length of the thumb along a scrollbar
+    fn thumb(offset: f32, content: f32, viewport: f32) -> (f32, f32) {
+        let length = (viewport * viewport / content)
+            .max(MIN_THUMB_LENGTH)
+            .min(viewport);
+        let max_offset = content - viewport;
+        let position = if max_offset > 0. {
+            offset / max_offset * (viewport - length)
+        } else {
+            0.
+        };
+        (position, length)
+    }
+
+    /// How much the content moves when a thumb moves by one unit
+    fn thumb_ratio(content: f32, viewport: f32) -> f32 {
+        let (_, length) = Self::thumb(0., content, viewport);
+        if viewport - length > 0. {
+            (content - viewport) / (viewport - length)
+        } else {
+            0.
+        }
+    }
+}
+
+impl WidgetLogic for ScrollArea {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = self.size.unwrap_or((
+            query.available_space.0.unwrap_or(5.),
+            query.available_space.1.unwrap_or(5.),
+        ));
+        let viewport = self.viewport(size);
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+
+        // The content is laid out without limit in the scrollable directions,
+        // from top to bottom like in a window
+        let mut responses = Vec::with_capacity(self.content.len());
+        let (mut width, mut height) = (0f32, 0f32);
+        for node in &mut self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (
+                    if self.horizontal {
+                        None
+                    } else {
+                        Some(viewport.0)
+                    },
+                    if self.vertical {
+                        None
+                    } else {
+                        Some((viewport.1 - height).max(0.))
+                    },
+                ),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            width = width.max(response.size.0);
+            height += response.size.1 + self.spacing;
+            responses.push(response.size);
+        }
+        self.content_size = (width, (height - self.spacing).max(0.));
+        self.scroll_to(self.offset, size);
+
+        // The top of the content is at the top of the viewport when not scrolled
+        let mut cursor = viewport.1 + self.offset.1;
+        for (node, child_size) in self.content.iter_mut().zip(responses) {
+            cursor -= child_size.1;
+            node.set_size(child_size);
+            node.set_transform(
+                Similarity3::identity()
+                    * Translation3::new(-self.offset.0, cursor + size.1 - viewport.1, 0.),
+            );
+            cursor -= self.spacing;
+        }
+
+        LayoutResponse { size, status }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let viewport = self.viewport(size);
+
+        let mut list = DrawList::new();
+        self.content.iter().for_each(|node| {
+            list.list.push(node.draw());
+        });
+        list.list_transform = metadata.transform.to_homogeneous();
+        list.clip = Some((0., size.1 - viewport.1, viewport.0, viewport.1));
+
+        if self.vertical && self.content_size.1 > viewport.1 {
+            let (position, length) = Self::thumb(self.offset.1, self.content_size.1, viewport.1);
+            let x = size.0 - SCROLLBAR_WIDTH;
+            list.commands.push(quad(
+                SCROLLBAR_WIDTH,
+                viewport.1,
+                None,
+                SCROLLBAR,
+                metadata.transform * Translation3::new(x, size.1 - viewport.1, 0.),
+            ));
+            list.commands.push(quad(
+                SCROLLBAR_WIDTH,
+                length,
+                None,
+                THUMB,
+                metadata.transform * Translation3::new(x, size.1 - position - length, 0.01),
+            ));
+        }
+        if self.horizontal && self.content_size.0 > viewport.0 {
+            let (position, length) = Self::thumb(self.offset.0, self.content_size.0, viewport.0);
+            list.commands.push(quad(
+                viewport.0,
+                SCROLLBAR_WIDTH,
+                None,
+                SCROLLBAR,
+                metadata.transform,
+            ));
+            list.commands.push(quad(
+                length,
+                SCROLLBAR_WIDTH,
+                None,
+                THUMB,
+                metadata.transform * Translation3::new(position, 0., 0.01),
+            ));
+        }
+
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let distance = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap());
+
+        // The content is always reached, so that the rays stay up to date,
+        // but it can only be interacted with inside the viewport
+        let content: Vec<(f32, NodeReference)> = self
+            .content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect();
+
+        match distance {
+            Some(distance) => {
+                let viewport = self.viewport(size);
+                let inside = metadata
+                    .pointer_position()
+                    .map(|(x, y)| x <= viewport.0 && y >= size.1 - viewport.1)
+                    .unwrap_or(false);
+                let mut result = if inside { content } else { Vec::new() };
+                result.push((distance + DEPTH_BIAS, self_node));
+                result
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        let size = metadata.size;
+        let viewport = self.viewport(size);
+        match event {
+            Event::MouseScrolled(delta) => {
+                let distance = delta * SCROLL_SPEED;
+                let offset = if metadata.modifiers().shift {
+                    (self.offset.0 - distance, self.offset.1)
+                } else {
+                    (self.offset.0, self.offset.1 - distance)
+                };
+                self.scroll_to(offset, size);
+                EventResponse::Registered
+            }
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if let Some(position) = metadata.pointer_position() {
+                    self.drag = Some(if self.vertical && position.0 > viewport.0 {
+                        Drag::VerticalThumb(position, self.offset)
+                    } else if self.horizontal && position.1 < size.1 - viewport.1 {
+                        Drag::HorizontalThumb(position, self.offset)
+                    } else {
+                        Drag::Content(position, self.offset)
+                    });
+                    metadata.capture_pointer();
+                }
+                EventResponse::Registered
+            }
+            Event::MouseMoved(_, _) if metadata.has_pointer_capture() => {
+                if let (Some(drag), Some((x, y))) = (self.drag, metadata.pointer_position()) {
+                    let offset = match drag {
+                        Drag::Content((x0, y0), (ox, oy)) => (ox - (x - x0), oy + (y - y0)),
+                        Drag::VerticalThumb((_, y0), (ox, oy)) => (
+                            ox,
+                            oy - (y - y0) * Self::thumb_ratio(self.content_size.1, viewport.1),
+                        ),
+                        Drag::HorizontalThumb((x0, _), (ox, oy)) => (
+                            ox + (x - x0) * Self::thumb_ratio(self.content_size.0, viewport.0),
+                            oy,
+                        ),
+                    };
+                    self.scroll_to(offset, size);
+                }
+                EventResponse::Registered
+            }
+            Event::MouseButtonReleased(MouseButton::Left)
+            | Event::MouseButtonReleased(MouseButton::Touch)
+                if metadata.has_pointer_capture() =>
+            {
+                self.drag = None;
+                metadata.release_pointer();
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{mock_font, press_at, ray_at};
+    use crate::widget::*;
+
+    #[test]
+    fn scroll_window() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            ui.new_frame();
+            let mut pressed = None;
+            WindowBuilder::new(|window| {
+                for index in 0..10 {
+                    let button = Button::new(format!("Button {}", index), &font);
+                    let id = ComponentId::new_biased::<Button>(loc!(), index);
+                    if window.query::<Button>(id).update(button).1 {
+                        pressed = Some(index);
+                    }
+                }
+            })
+            .size((10., 10.))
+            .scrollable()
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            pressed
+        };
+        build(&mut ui);
+        // Buttons are 1.4 high, separated by 0.5, from the top of the window
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui), Some(0));
+
+        // The sixth button goes past the bottom of the window
+        press_at(&mut ui, -4., -5.2);
+        assert_eq!(build(&mut ui), None);
+
+        ui.register_event(Event::MouseScrolled(-1.9), Some(&ray_at(-4., 0.)));
+        build(&mut ui);
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui), Some(1));
+
+        // Scrolling stops at the end of the content, 18.5 high
+        ui.register_event(Event::MouseScrolled(-100.), Some(&ray_at(-4., 0.)));
+        build(&mut ui);
+        press_at(&mut ui, -4., -4.3);
+        assert_eq!(build(&mut ui), Some(9));
+    }
+}
@@ -0,0 +1,330 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Translation3};
+
+use crate::core::*;
+
+/// How a `SelectableBuilder` is bound to the selection of the application
+enum Selection<'a, T> {
+    /// A single element is selected at a time
+    Single(&'a mut T),
+    /// Several elements can be selected at the same time
+    Multiple(&'a mut Vec<T>),
+}
+
+/// A row of a list, which can be selected by clicking on it.
+/// It takes all the width available.
+///
+/// In single selection mode, clicking a row selects its value.
+/// In multiple selection mode, clicking a row selects only its value,
+/// and Ctrl+click adds it to, or removes it from, the selection.
+///
+/// `build` returns whether the selection was changed by the user.
+pub struct SelectableBuilder<'a, T: PartialEq> {
+    label: String,
+    selection: Selection<'a, T>,
+    value: T,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    color: (f32, f32, f32, f32),
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a, T: PartialEq> SelectableBuilder<'a, T> {
+    fn new(
+        label: String,
+        selection: Selection<'a, T>,
+        value: T,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+    ) -> Self {
+        Self {
+            label,
+            selection,
+            value,
+            font: font.clone(),
+            font_size: 1.,
+            color: BACKGROUND,
+            selected_color: SELECTED,
+            text_color: TEXT,
+        }
+    }
+
+    /// Creates a row selected when `selection` is equal to `value`
+    pub fn single(
+        label: String,
+        selection: &'a mut T,
+        value: T,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+    ) -> Self {
+        Self::new(label, Selection::Single(selection), value, font)
+    }
+
+    /// Creates a row selected when `selection` contains `value`
+    pub fn multiple(
+        label: String,
+        selection: &'a mut Vec<T>,
+        value: T,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+    ) -> Self {
+        Self::new(label, Selection::Multiple(selection), value, font)
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: PartialEq> WidgetBuilder for SelectableBuilder<'a, T> {
+    type AchievedType = Selectable;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let value = self.value;
+        let mut changed = false;
+        let selected = match self.selection {
+            Selection::Single(selection) => {
+                if widget.clicked.take().is_some() && *selection != value {
+                    *selection = value;
+                    changed = true;
+                    true
+                } else {
+                    *selection == value
+                }
+            }
+            Selection::Multiple(selection) => {
+                let position = selection.iter().position(|element| *element == value);
+                match (widget.clicked.take(), position) {
+                    (Some(true), Some(index)) => {
+                        selection.remove(index);
+                        changed = true;
+                        false
+                    }
+                    (Some(true), None) => {
+                        selection.push(value);
+                        changed = true;
+                        true
+                    }
+                    (Some(false), _) => {
+                        changed = selection.len() != 1 || position.is_none();
+                        selection.clear();
+                        selection.push(value);
+                        true
+                    }
+                    (None, position) => position.is_some(),
+                }
+            }
+        };
+
+        widget.label = self.label;
+        widget.selected = selected;
+        widget.font_size = self.font_size;
+        widget.color = self.color;
+        widget.selected_color = self.selected_color;
+        widget.text_color = self.text_color;
+        changed
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let selected = match &self.selection {
+            Selection::Single(selection) => **selection == self.value,
+            Selection::Multiple(selection) => selection.contains(&self.value),
+        };
+        Selectable {
+            label: self.label,
+            selected,
+            clicked: None,
+            font: self.font,
+            font_size: self.font_size,
+            color: self.color,
+            selected_color: self.selected_color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
+/// The widget behind a `SelectableBuilder`
+pub struct Selectable {
+    label: String,
+    selected: bool,
+    /// Set when clicked since the last update, to whether Ctrl was pressed
+    clicked: Option<bool>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    color: (f32, f32, f32, f32),
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+
+impl WidgetLogic for Selectable {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (label_width, _) = self
+            .font
+            .borrow()
+            .size_of(self.label.as_str(), self.font_size);
+        let width = label_width + 2. * PADDING;
+        let height = self.font_size + 2. * PADDING;
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        LayoutResponse {
+            size: (available_width, height.min(available_height)),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            size.0,
+            size.1,
+            None,
+            if self.selected {
+                self.selected_color
+            } else {
+                self.color
+            },
+            metadata.transform,
+        ));
+        list.commands.push(draw_text(
+            self.label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (metadata.transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+        ));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                self.clicked = Some(metadata.modifiers().ctrl);
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{build_in_window, mock_font, ray_at};
+
+    #[test]
+    fn multiple_selection() {
+        let font = mock_font();
+        let mut selection = vec![0];
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, selection: &mut Vec<usize>| {
+            let mut changed = false;
+            build_in_window(ui, |window| {
+                for index in 0..3 {
+                    changed |= SelectableBuilder::multiple(
+                        format!("Item {}", index),
+                        selection,
+                        index,
+                        &font,
+                    )
+                    .build(CodeLocation(file!(), line!(), index as u32), window);
+                }
+            });
+            changed
+        };
+        // Each row is 1.4 high, separated by 0.5, from the top of the window
+        let row = |index: usize| ray_at(0., 5. - 0.7 - 1.9 * index as f32);
+
+        assert!(!build(&mut ui, &mut selection));
+        ui.register_event(Event::MouseButtonPressed(MouseButton::Left), Some(&row(2)));
+        assert!(build(&mut ui, &mut selection));
+        assert_eq!(selection, vec![2]);
+
+        ui.register_event(Event::KeyPressed(Key::LCtrl), None);
+        ui.register_event(Event::MouseButtonPressed(MouseButton::Left), Some(&row(1)));
+        assert!(build(&mut ui, &mut selection));
+        assert_eq!(selection, vec![2, 1]);
+
+        ui.register_event(Event::MouseButtonPressed(MouseButton::Left), Some(&row(2)));
+        assert!(build(&mut ui, &mut selection));
+        assert_eq!(selection, vec![1]);
+    }
+}
@@ -0,0 +1,122 @@
+use nalgebra::Translation3;
+
+use crate::core::*;
+
+/// A line separating widgets, horizontal between the rows of a window,
+/// or vertical between the widgets of an `Inline`.
+///
+/// A horizontal separator is as wide as the space given by its parent, and a vertical one
+/// as high as a line of text, unless a `length` is given.
+pub struct SeparatorBuilder {
+    vertical: bool,
+    length: Option<f32>,
+    thickness: f32,
+    color: (f32, f32, f32, f32),
+}
+
+const LINE: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+/// The height of a line of text with its padding, as in a label or a button
+const LINE_HEIGHT: f32 = 1.4;
+
+impl SeparatorBuilder {
+    pub fn horizontal() -> Self {
+        Self {
+            vertical: false,
+            length: None,
+            thickness: 0.05,
+            color: LINE,
+        }
+    }
+
+    pub fn vertical() -> Self {
+        Self {
+            vertical: true,
+            ..Self::horizontal()
+        }
+    }
+
+    pub fn length(self, length: f32) -> Self {
+        Self {
+            length: Some(length),
+            ..self
+        }
+    }
+
+    pub fn thickness(self, thickness: f32) -> Self {
+        Self { thickness, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl WidgetBuilder for SeparatorBuilder {
+    type AchievedType = Separator;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self.create();
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Separator {
+            vertical: self.vertical,
+            length: self.length,
+            thickness: self.thickness,
+            color: self.color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+/// The widget behind a `SeparatorBuilder`
+pub struct Separator {
+    vertical: bool,
+    length: Option<f32>,
+    thickness: f32,
+    color: (f32, f32, f32, f32),
+}
+
+impl WidgetLogic for Separator {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = if self.vertical {
+            (self.thickness, self.length.unwrap_or(LINE_HEIGHT))
+        } else {
+            let width = self.length.or(query.available_space.0).unwrap_or(0.);
+            (width, self.thickness)
+        };
+        let fits = |size: f32, available: Option<f32>| match available {
+            Some(available) if size > available => LayoutStatus::Inconsistencies,
+            _ => LayoutStatus::Ok,
+        };
+        LayoutResponse {
+            size,
+            status: (
+                fits(size.0, query.available_space.0),
+                fits(size.1, query.available_space.1),
+            ),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            metadata.size.0,
+            metadata.size.1,
+            None,
+            self.color,
+            metadata.transform * Translation3::new(0., 0., 0.01),
+        ));
+        list
+    }
+}
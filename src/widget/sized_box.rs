@@ -0,0 +1,163 @@
+use nalgebra::*;
+
+use crate::core::*;
+use crate::loc;
+
+/// A widget that contains another one, and forces its size.
+///
+/// The content is laid out with the given size as available space, and maximized in it,
+/// then takes exactly this size whatever it asked for.
+pub struct SizedBoxBuilder<T: WidgetBuilder> {
+    size: (f32, f32),
+    content: Option<T>,
+}
+
+impl<T: WidgetBuilder> SizedBoxBuilder<T> {
+    pub fn new(size: (f32, f32), content: T) -> Self {
+        SizedBoxBuilder {
+            size,
+            content: Some(content),
+        }
+    }
+
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self { size, ..self }
+    }
+}
+
+impl<T: WidgetBuilder> WidgetBuilder for SizedBoxBuilder<T> {
+    type AchievedType = SizedBox;
+    type UpdateFeedback = ();
+    type BuildFeedback = T::BuildFeedback;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.size = self.size;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        SizedBox {
+            size: self.size,
+            content: None,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let content = self.content.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        content.build(loc!(), &node_ref)
+    }
+}
+
+/// The widget behind a `SizedBoxBuilder`
+pub struct SizedBox {
+    size: (f32, f32),
+    content: Option<NodeReference>,
+}
+
+impl WidgetLogic for SizedBox {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        match &self.content {
+            Some(other) if other.has_id(id) => WidgetQueryResult::Initialized(other.clone()),
+            _ => {
+                let node_ref = Node::new_reference(id, &metadata.ui_properties);
+                self.content = Some(node_ref.clone());
+                WidgetQueryResult::Uninitialized(node_ref)
+            }
+        }
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let fits = |size: f32, available: Option<f32>| match available {
+            Some(available) if size > available => LayoutStatus::Inconsistencies,
+            _ => LayoutStatus::Ok,
+        };
+        let mut status = (
+            fits(self.size.0, query.available_space.0),
+            fits(self.size.1, query.available_space.1),
+        );
+
+        if let Some(node) = &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (Some(self.size.0), Some(self.size.1)),
+                objectives: (Objective::Maximize, Objective::Maximize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            node.set_size(self.size);
+            node.set_transform(Similarity3::identity());
+        }
+
+        LayoutResponse {
+            size: self.size,
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_font, press_at};
+    use crate::widget::{Button, WindowBuilder};
+
+    #[test]
+    fn forced_size() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // Returns whether the button in the box was pressed, and the status of the layout
+        let build = |ui: &mut Interface, size: (f32, f32)| {
+            ui.new_frame();
+            let mut pressed = false;
+            WindowBuilder::new(|window| {
+                pressed = SizedBoxBuilder::new(size, Button::new("B".to_string(), &font))
+                    .build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            (pressed, ui.generate_layout().status)
+        };
+
+        // The button takes the size of the box, from x = -5 to -1 and y = 3 to 5,
+        // while it would only be 1.4 high on its own
+        let ok = (LayoutStatus::Ok, LayoutStatus::Ok);
+        assert_eq!(build(&mut ui, (4., 2.)), (false, ok));
+        press_at(&mut ui, -1.5, 3.2);
+        assert_eq!(build(&mut ui, (4., 2.)), (true, ok));
+        press_at(&mut ui, -0.5, 4.);
+        assert_eq!(build(&mut ui, (4., 2.)), (false, ok));
+
+        let (_, status) = build(&mut ui, (12., 2.));
+        assert_eq!(status.0, LayoutStatus::Inconsistencies);
+    }
+}
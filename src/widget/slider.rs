@@ -0,0 +1,373 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Translation3};
+
+use crate::core::*;
+use crate::widget::numeric::*;
+
+/// A slider editing a number inside a range, by dragging a thumb along a track.
+///
+/// While the thumb is dragged, the slider captures the pointer, so the thumb
+/// keeps following it even outside of the track.
+pub struct SliderBuilder<'a, T: Numeric> {
+    value: &'a mut T,
+    range: NumericRange,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    length: f32,
+    vertical: bool,
+    precision: usize,
+    color: (f32, f32, f32, f32),
+    thumb_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const THUMB: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a, T: Numeric> SliderBuilder<'a, T> {
+    pub fn new(value: &'a mut T, min: T, max: T, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        let mut range = NumericRange::new(min.to_f64(), max.to_f64());
+        if T::INTEGER {
+            range.step = Some(1.);
+        }
+        Self {
+            value,
+            range,
+            font: font.clone(),
+            font_size: 1.,
+            length: 8.,
+            vertical: false,
+            precision: if T::INTEGER { 0 } else { 2 },
+            color: BACKGROUND,
+            thumb_color: THUMB,
+            text_color: TEXT,
+        }
+    }
+
+    /// Sets the interval between two possible values
+    pub fn step(mut self, step: T) -> Self {
+        self.range.step = Some(step.to_f64());
+        self
+    }
+
+    /// Makes the value evolve exponentially along the track.
+    /// This has no effect if the minimum is not strictly positive
+    pub fn logarithmic(mut self) -> Self {
+        self.range.logarithmic = true;
+        self
+    }
+
+    /// Places the track vertically, the minimum being at the bottom
+    pub fn vertical(self) -> Self {
+        Self {
+            vertical: true,
+            ..self
+        }
+    }
+
+    /// Sets the length of the track, when it is not maximized by its parent
+    pub fn length(self, length: f32) -> Self {
+        Self { length, ..self }
+    }
+
+    /// Sets the number of decimals displayed
+    pub fn precision(self, precision: usize) -> Self {
+        Self { precision, ..self }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn thumb_color(self, thumb_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            thumb_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a, T: Numeric> WidgetBuilder for SliderBuilder<'a, T> {
+    type AchievedType = Slider;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let mut changed = false;
+        if let Some(value) = widget.pending.take() {
+            let value = T::from_f64(value);
+            if value != *self.value {
+                *self.value = value;
+                changed = true;
+            }
+        }
+
+        widget.value = self.value.to_f64();
+        widget.range = self.range;
+        widget.font_size = self.font_size;
+        widget.length = self.length;
+        widget.vertical = self.vertical;
+        widget.precision = self.precision;
+        widget.color = self.color;
+        widget.thumb_color = self.thumb_color;
+        widget.text_color = self.text_color;
+        changed
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Slider {
+            value: self.value.to_f64(),
+            pending: None,
+            range: self.range,
+            font: self.font,
+            font_size: self.font_size,
+            length: self.length,
+            vertical: self.vertical,
+            precision: self.precision,
+            color: self.color,
+            thumb_color: self.thumb_color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, feedback) = parent.query::<Self::AchievedType>(id).update(self);
+        feedback
+    }
+}
+
+/// The widget behind a `SliderBuilder`
+pub struct Slider {
+    value: f64,
+    /// The value chosen by the user, not yet written back
+    pending: Option<f64>,
+    range: NumericRange,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    length: f32,
+    vertical: bool,
+    precision: usize,
+    color: (f32, f32, f32, f32),
+    thumb_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+/// Size of the thumb along the track, relative to the thickness of the slider
+const THUMB_RATIO: f32 = 0.5;
+
+impl Slider {
+    /// Returns the length of the track and the size of the thumb along it
+    fn track(&self, size: (f32, f32)) -> (f32, f32) {
+        if self.vertical {
+            (size.1, size.0 * THUMB_RATIO)
+        } else {
+            (size.0, size.1 * THUMB_RATIO)
+        }
+    }
+
+    /// Moves the thumb under the pointer
+    fn follow_pointer(&mut self, metadata: &NodeMetadata) {
+        if let Some((x, y)) = metadata.pointer_position() {
+            let (length, thumb) = self.track(metadata.size);
+            let along = if self.vertical { y } else { x };
+            let t = (along - thumb / 2.) / (length - thumb).max(f32::EPSILON);
+            let value = self.range.snap(self.range.denormalize(t as f64));
+            self.value = value;
+            self.pending = Some(value);
+        }
+    }
+}
+
+impl WidgetLogic for Slider {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let thickness = self.font_size + 2. * PADDING;
+        let (mut width, mut height, main_objective) = if self.vertical {
+            (thickness, self.length, query.objectives.1)
+        } else {
+            (self.length, thickness, query.objectives.0)
+        };
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+
+        let x_status = if width <= available_width {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+        let y_status = if height <= available_height {
+            LayoutStatus::Ok
+        } else {
+            LayoutStatus::Inconsistencies
+        };
+
+        if let Objective::Maximize = main_objective {
+            if self.vertical {
+                height = available_height;
+            } else {
+                width = available_width;
+            }
+        }
+        width = width.min(available_width);
+        height = height.min(available_height);
+
+        LayoutResponse {
+            size: (width, height),
+            status: (x_status, y_status),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let (length, thumb) = self.track(size);
+        let position = self.range.normalize(self.value) as f32 * (length - thumb);
+
+        let (thumb_size, thumb_position) = if self.vertical {
+            ((size.0, thumb), Translation3::new(0., position, 0.01))
+        } else {
+            ((thumb, size.1), Translation3::new(position, 0., 0.01))
+        };
+
+        let label = format!("{:.*}", self.precision, self.value);
+        let (label_width, _) = self.font.borrow().size_of(label.as_str(), self.font_size);
+        let label_position = Translation3::new(
+            (size.0 - label_width) / 2.,
+            (size.1 - self.font_size) / 2.,
+            0.02,
+        );
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+        list.commands.push(quad(
+            thumb_size.0,
+            thumb_size.1,
+            None,
+            self.thumb_color,
+            metadata.transform * thumb_position,
+        ));
+        list.commands.push(draw_text(
+            label.as_str(),
+            &self.font,
+            self.font_size,
+            self.text_color,
+            (metadata.transform * label_position).to_homogeneous(),
+        ));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default()
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                metadata.capture_pointer();
+                self.follow_pointer(metadata);
+                EventResponse::Registered
+            }
+            Event::MouseMoved(_, _) if metadata.has_pointer_capture() => {
+                self.follow_pointer(metadata);
+                EventResponse::Registered
+            }
+            Event::MouseButtonReleased(MouseButton::Left)
+            | Event::MouseButtonReleased(MouseButton::Touch)
+                if metadata.has_pointer_capture() =>
+            {
+                metadata.release_pointer();
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+
+    #[test]
+    fn drag_thumb() {
+        let font = mock_font();
+        let mut value = 0i32;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, value: &mut i32| {
+            let mut changed = false;
+            build_in_window(ui, |window| {
+                changed = SliderBuilder::new(value, 0, 10, &font)
+                    .length(10.)
+                    .build(loc!(), window);
+            });
+            changed
+        };
+
+        assert!(!build(&mut ui, &mut value));
+        // The track goes from x = -5 to x = 5, and the thumb is 0.7 wide
+        let y = 4.5;
+        press_at(&mut ui, 0., y);
+        assert!(build(&mut ui, &mut value));
+        assert_eq!(value, 5);
+
+        // The pointer is captured, so the thumb follows it outside of the track
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(20., -20.)));
+        ui.register_event(
+            Event::MouseButtonReleased(MouseButton::Left),
+            Some(&ray_at(20., -20.)),
+        );
+        assert!(build(&mut ui, &mut value));
+        assert_eq!(value, 10);
+
+        // Released, so moving has no effect anymore
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-20., y)));
+        assert!(!build(&mut ui, &mut value));
+        assert_eq!(value, 10);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::core::*;
+
+/// An empty space between widgets.
+///
+/// A fixed spacer takes the size given to it. A flexible one takes no space by itself,
+/// but shares with the other flexible spacers the space left by its siblings along the
+/// direction of its container, a `Window` or an `Inline`. It can therefore push the
+/// widgets built after it to the bottom of a window, or to the right of a row.
+pub struct SpacerBuilder {
+    size: (f32, f32),
+    flexible: bool,
+}
+
+impl SpacerBuilder {
+    pub fn fixed(size: (f32, f32)) -> Self {
+        Self {
+            size,
+            flexible: false,
+        }
+    }
+
+    pub fn flexible() -> Self {
+        Self {
+            size: (0., 0.),
+            flexible: true,
+        }
+    }
+}
+
+impl WidgetBuilder for SpacerBuilder {
+    type AchievedType = Spacer;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self.create();
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Spacer {
+            size: self.size,
+            flexible: self.flexible,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+/// The widget behind a `SpacerBuilder`
+pub struct Spacer {
+    size: (f32, f32),
+    flexible: bool,
+}
+
+impl Spacer {
+    /// Whether the node holds a flexible spacer, whose size is given by its container
+    pub(crate) fn is_flexible(node: &NodeReference) -> bool {
+        let mut flexible = false;
+        if node.holds::<Spacer>() {
+            node.apply_to_widget::<Spacer, _>(|_, widget| flexible = widget.flexible);
+        }
+        flexible
+    }
+}
+
+impl WidgetLogic for Spacer {
+    fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
+        LayoutResponse {
+            size: self.size,
+            status: (LayoutStatus::Ok, LayoutStatus::Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::widget::{InlineBuilder, SeparatorBuilder, WindowBuilder};
+
+    /// Builds a spacer directly, to get its node
+    fn spacer(parent: &NodeReference, loc: CodeLocation, builder: SpacerBuilder) -> NodeReference {
+        let id = ComponentId::new::<Spacer>(loc);
+        let (node, _) = parent.query::<Spacer>(id).update(builder);
+        node
+    }
+
+    /// The position of the bottom left corner of a node in its parent, and its size
+    fn placement(node: &NodeReference) -> ((f32, f32), (f32, f32)) {
+        let translation = node.get_transform().isometry.translation;
+        let mut size = (0., 0.);
+        node.apply_to_widget::<Spacer, _>(|metadata, _| size = metadata.size);
+        ((translation.x, translation.y), size)
+    }
+
+    #[test]
+    fn flexible_spacers() {
+        let mut ui = Interface::new();
+        let (mut column, mut row) = (Vec::new(), Vec::new());
+        ui.new_frame();
+        WindowBuilder::new(|window| {
+            column.push(spacer(window, loc!(), SpacerBuilder::fixed((2., 2.))));
+            column.push(spacer(window, loc!(), SpacerBuilder::flexible()));
+            SeparatorBuilder::horizontal()
+                .thickness(1.)
+                .build(loc!(), window);
+            InlineBuilder::new(|inline| {
+                row.push(spacer(inline, loc!(), SpacerBuilder::fixed((2., 1.))));
+                row.push(spacer(inline, loc!(), SpacerBuilder::flexible()));
+                row.push(spacer(inline, loc!(), SpacerBuilder::flexible()));
+                row.push(spacer(inline, loc!(), SpacerBuilder::fixed((1., 1.))));
+            })
+            .build(loc!(), window);
+        })
+        .size((10., 10.))
+        .spacing(1.)
+        .build(loc!(), &ui.root);
+        ui.end_frame();
+        let response = ui.generate_layout();
+        assert_eq!(response.status, (LayoutStatus::Ok, LayoutStatus::Ok));
+
+        // The window is 10 high: 3 remain below the fixed widgets and the spacing,
+        // so the row is at the bottom of the window
+        assert_eq!(placement(&column[0]), ((0., 8.), (2., 2.)));
+        assert_eq!(placement(&column[1]), ((0., 4.), (0., 3.)));
+
+        // The row is 10 wide: the two spacers share the 5.5 left
+        assert_eq!(placement(&row[0]), ((0., 0.), (2., 1.)));
+        assert_eq!(placement(&row[1]), ((2.5, 0.), (2.75, 0.)));
+        assert_eq!(placement(&row[2]), ((5.75, 0.), (2.75, 0.)));
+        assert_eq!(placement(&row[3]), ((9., 0.), (1., 1.)));
+    }
+}
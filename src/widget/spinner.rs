@@ -0,0 +1,188 @@
+use std::f32::consts::PI;
+
+use nalgebra::{Translation3, Vector2, Vector3};
+
+use crate::core::*;
+
+/// A loading indicator: an arc turning around its center, driven by the time
+/// elapsed between frames
+pub struct SpinnerBuilder {
+    size: f32,
+    speed: f32,
+    color: (f32, f32, f32, f32),
+}
+
+const ARC: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+
+impl SpinnerBuilder {
+    pub fn new() -> Self {
+        Self {
+            size: 1.4,
+            speed: 1.,
+            color: ARC,
+        }
+    }
+
+    /// Sets the diameter of the spinner
+    pub fn size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets the number of turns per second
+    pub fn speed(self, speed: f32) -> Self {
+        Self { speed, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl Default for SpinnerBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl WidgetBuilder for SpinnerBuilder {
+    type AchievedType = Spinner;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let turns = metadata.delta_time().as_secs_f32() * self.speed;
+        widget.angle = (widget.angle + turns * 2. * PI) % (2. * PI);
+        widget.size = self.size;
+        widget.color = self.color;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Spinner {
+            angle: 0.,
+            size: self.size,
+            color: self.color,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        parent.query::<Self::AchievedType>(id).update(self);
+    }
+}
+
+/// The widget behind a `SpinnerBuilder`
+pub struct Spinner {
+    /// Angle of the head of the arc, in radians
+    angle: f32,
+    size: f32,
+    color: (f32, f32, f32, f32),
+}
+
+/// Angle covered by the arc, in radians
+const SWEEP: f32 = 1.5 * PI;
+/// Thickness of the arc, relative to the radius of the spinner
+const THICKNESS: f32 = 0.25;
+/// Number of segments approximating the arc
+const SEGMENTS: u32 = 24;
+
+impl WidgetLogic for Spinner {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let status = |available: Option<f32>| match available {
+            Some(available) if self.size > available => LayoutStatus::Inconsistencies,
+            _ => LayoutStatus::Ok,
+        };
+        LayoutResponse {
+            size: (self.size, self.size),
+            status: (
+                status(query.available_space.0),
+                status(query.available_space.1),
+            ),
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let outer = self.size / 2.;
+        let inner = outer * (1. - THICKNESS);
+        let (r, g, b, a) = self.color;
+        let tex_uv = Vector2::new(0., 0.);
+
+        // A band between the two radii, fading out from the head of the arc to its tail
+        let mut vertex_buffer = Vec::with_capacity(2 * (SEGMENTS as usize + 1));
+        let mut index_buffer = Vec::with_capacity(6 * SEGMENTS as usize);
+        for segment in 0..=SEGMENTS {
+            let progress = segment as f32 / SEGMENTS as f32;
+            let angle = self.angle - progress * SWEEP;
+            let color = (r, g, b, a * (1. - progress));
+            for &radius in &[outer, inner] {
+                vertex_buffer.push(Vertex {
+                    position: Vector3::new(radius * angle.cos(), radius * angle.sin(), 0.),
+                    color,
+                    tex_uv,
+                });
+            }
+            if segment < SEGMENTS {
+                let first = 2 * segment;
+                index_buffer.extend_from_slice(&[
+                    first,
+                    first + 1,
+                    first + 2,
+                    first + 1,
+                    first + 2,
+                    first + 3,
+                ]);
+            }
+        }
+
+        let mut uniforms = Uniforms::new();
+        uniforms.model_matrix =
+            (metadata.transform * Translation3::new(outer, outer, 0.)).to_homogeneous();
+        let mut list = DrawList::new();
+        list.commands.push(DrawCommand {
+            vertex_buffer,
+            index_buffer,
+            draw_mode: DrawMode::Triangles,
+            uniforms,
+        });
+        list
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::widget::WindowBuilder;
+    use std::time::Duration;
+
+    #[test]
+    fn turns_with_time() {
+        let mut ui = Interface::new();
+        // Builds a frame at the given time, and returns the angle of the spinner
+        let build = |ui: &mut Interface, millis: u64| {
+            ui.new_frame_at(Duration::from_millis(millis));
+            let mut angle = 0.;
+            WindowBuilder::new(|window| {
+                let id = ComponentId::new::<Spinner>(loc!());
+                let spinner = SpinnerBuilder::new().speed(0.5);
+                let (node_ref, _) = window.query::<Spinner>(id).update(spinner);
+                node_ref.apply_to_widget::<Spinner, _>(|_, widget| angle = widget.angle);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            angle
+        };
+
+        assert_eq!(build(&mut ui, 0), 0.);
+        assert!((build(&mut ui, 500) - PI / 2.).abs() < 1e-5);
+        assert!((build(&mut ui, 1000) - PI).abs() < 1e-5);
+        // A frame at the same time does not move it, and it wraps after a turn
+        assert!((build(&mut ui, 1000) - PI).abs() < 1e-5);
+        assert!((build(&mut ui, 2500) - PI / 2.).abs() < 1e-5);
+    }
+}
@@ -0,0 +1,326 @@
+use nalgebra::{Point3, Translation3, Vector2};
+
+use crate::core::*;
+
+/// A surface whose height, towards the viewer, follows a grid of values.
+///
+/// The rows of the grid go from the bottom of the widget to its top, and their values from
+/// its left to its right. The surface is colored from `low` to `high` color along its height.
+/// When the pointer is over the surface, the vertex nearest to it is highlighted, and `build`
+/// returns its row, its column and its value.
+pub struct SurfacePlotBuilder {
+    values: Vec<Vec<f32>>,
+    size: (f32, f32),
+    height: f32,
+    range: Option<(f32, f32)>,
+    low: (f32, f32, f32, f32),
+    high: (f32, f32, f32, f32),
+}
+
+const LOW: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const HIGH: (f32, f32, f32, f32) = (0.9, 0.6, 0.2, 1.);
+const MARKER: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl SurfacePlotBuilder {
+    /// Creates a surface from rows of values, which must all have the same length
+    pub fn new(values: &[Vec<f32>]) -> Self {
+        Self {
+            values: values.to_vec(),
+            size: (8., 8.),
+            height: 3.,
+            range: None,
+            low: LOW,
+            high: HIGH,
+        }
+    }
+
+    /// Sets the size of the grid in the plane of the widget, when it is not maximized by its parent
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Sets the height of the highest values, towards the viewer
+    pub fn height(self, height: f32) -> Self {
+        Self { height, ..self }
+    }
+
+    /// Fixes the values at the bottom and at the top of the surface,
+    /// instead of fitting the values of the grid
+    pub fn range(self, min: f32, max: f32) -> Self {
+        Self {
+            range: Some((min, max)),
+            ..self
+        }
+    }
+
+    /// Sets the colors of the bottom and of the top of the surface
+    pub fn colors(self, low: (f32, f32, f32, f32), high: (f32, f32, f32, f32)) -> Self {
+        Self { low, high, ..self }
+    }
+}
+
+impl WidgetBuilder for SurfacePlotBuilder {
+    type AchievedType = SurfacePlot;
+    type UpdateFeedback = Option<(usize, usize, f32)>;
+    type BuildFeedback = Option<(usize, usize, f32)>;
+
+    fn update(
+        self,
+        metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        *widget = self.create();
+        let ray = metadata
+            .pointer_ray
+            .filter(|_| metadata.hover_duration().is_some());
+        widget.hovered = ray.and_then(|ray| {
+            let transformation = metadata.transform.inverse();
+            let local_ray = Ray::new(
+                transformation * ray.direction(),
+                transformation * ray.origin(),
+            );
+            widget
+                .hit(metadata.size, &local_ray)
+                .map(|(_, vertex)| vertex)
+        });
+        widget
+            .hovered
+            .map(|(row, column)| (row, column, widget.values[row][column]))
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let (min, max) = self.range.unwrap_or_else(|| {
+            self.values
+                .iter()
+                .flatten()
+                .filter(|value| value.is_finite())
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                    (min.min(*value), max.max(*value))
+                })
+        });
+        SurfacePlot {
+            values: self.values,
+            size: self.size,
+            height: self.height,
+            range: if max > min {
+                (min, max)
+            } else if min.is_finite() {
+                (min, min + 1.)
+            } else {
+                (0., 1.)
+            },
+            low: self.low,
+            high: self.high,
+            hovered: None,
+        }
+    }
+
+    fn build(self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let (_, hovered) = parent.query::<Self::AchievedType>(id).update(self);
+        hovered
+    }
+}
+
+/// The widget behind a `SurfacePlotBuilder`
+pub struct SurfacePlot {
+    values: Vec<Vec<f32>>,
+    size: (f32, f32),
+    height: f32,
+    range: (f32, f32),
+    low: (f32, f32, f32, f32),
+    high: (f32, f32, f32, f32),
+    /// The row and the column of the vertex nearest to the pointer
+    hovered: Option<(usize, usize)>,
+}
+
+const MARKER_SIZE: f32 = 0.3;
+
+impl SurfacePlot {
+    fn rows(&self) -> usize {
+        self.values.len()
+    }
+
+    fn columns(&self) -> usize {
+        self.values.iter().map(Vec::len).min().unwrap_or(0)
+    }
+
+    /// Where the value is between the bottom and the top of the surface, from 0 to 1
+    fn normalize(&self, value: f32) -> f32 {
+        let (min, max) = self.range;
+        ((value - min) / (max - min)).clamp(0., 1.)
+    }
+
+    /// The position of a vertex of the grid, in the local space of the widget
+    fn vertex(&self, size: (f32, f32), row: usize, column: usize) -> Point3<f32> {
+        let step = |count: usize, length: f32| length / (count.max(2) - 1) as f32;
+        Point3::new(
+            column as f32 * step(self.columns(), size.0),
+            row as f32 * step(self.rows(), size.1),
+            self.normalize(self.values[row][column]) * self.height,
+        )
+    }
+
+    /// The vertices of the two triangles of each cell of the grid, as rows and columns
+    fn triangles(&self) -> impl Iterator<Item = [(usize, usize); 3]> {
+        let (rows, columns) = (self.rows(), self.columns());
+        (0..rows.saturating_sub(1)).flat_map(move |row| {
+            (0..columns.saturating_sub(1)).flat_map(move |column| {
+                let cell = [
+                    (row, column),
+                    (row, column + 1),
+                    (row + 1, column),
+                    (row + 1, column + 1),
+                ];
+                vec![[cell[0], cell[1], cell[2]], [cell[1], cell[2], cell[3]]]
+            })
+        })
+    }
+
+    /// Finds where the ray, in the local space of the widget, hits the surface first.
+    /// Returns the distance to the surface, and the vertex nearest to the point hit
+    fn hit(&self, size: (f32, f32), ray: &Ray) -> Option<(f32, (usize, usize))> {
+        let (distance, triangle) = self
+            .triangles()
+            .filter_map(|triangle| {
+                let points = [
+                    self.vertex(size, triangle[0].0, triangle[0].1),
+                    self.vertex(size, triangle[1].0, triangle[1].1),
+                    self.vertex(size, triangle[2].0, triangle[2].1),
+                ];
+                intersection(ray, &points).map(|distance| (distance, triangle))
+            })
+            .min_by(|(d1, _), (d2, _)| d1.partial_cmp(d2).unwrap())?;
+
+        let point = ray.origin() + ray.direction() * distance;
+        let nearest = triangle
+            .iter()
+            .min_by(|v1, v2| {
+                let d1 = (self.vertex(size, v1.0, v1.1) - point).norm();
+                let d2 = (self.vertex(size, v2.0, v2.1) - point).norm();
+                d1.partial_cmp(&d2).unwrap()
+            })
+            .copied()?;
+        Some((distance, nearest))
+    }
+
+    fn color(&self, value: f32) -> (f32, f32, f32, f32) {
+        let t = self.normalize(value);
+        let mix = |low: f32, high: f32| low + (high - low) * t;
+        (
+            mix(self.low.0, self.high.0),
+            mix(self.low.1, self.high.1),
+            mix(self.low.2, self.high.2),
+            mix(self.low.3, self.high.3),
+        )
+    }
+}
+
+impl WidgetLogic for SurfacePlot {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        query.fit(self.size)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let columns = self.columns();
+
+        let mut vertex_buffer = Vec::with_capacity(self.rows() * columns);
+        for (row, values) in self.values.iter().enumerate() {
+            for (column, value) in values.iter().take(columns).enumerate() {
+                vertex_buffer.push(Vertex {
+                    position: self.vertex(size, row, column).coords,
+                    color: self.color(*value),
+                    tex_uv: Vector2::new(0., 0.),
+                });
+            }
+        }
+        let index_buffer = self
+            .triangles()
+            .flat_map(|triangle| {
+                let index = |(row, column): (usize, usize)| (row * columns + column) as u32;
+                vec![index(triangle[0]), index(triangle[1]), index(triangle[2])]
+            })
+            .collect();
+
+        let mut uniforms = Uniforms::new();
+        uniforms.model_matrix = metadata.transform.to_homogeneous();
+        let mut list = DrawList::new();
+        list.commands.push(DrawCommand {
+            vertex_buffer,
+            index_buffer,
+            draw_mode: DrawMode::Triangles,
+            uniforms,
+        });
+
+        if let Some((row, column)) = self.hovered {
+            let point = self.vertex(size, row, column);
+            let half = MARKER_SIZE / 2.;
+            list.commands.push(quad(
+                MARKER_SIZE,
+                MARKER_SIZE,
+                None,
+                MARKER,
+                metadata.transform
+                    * Translation3::new(point.x - half, point.y - half, point.z + 0.01),
+            ));
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.hit(metadata.size, &new_ray)
+            .map(|(d, _)| vec![(d, self_node)])
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, ray_at};
+
+    #[test]
+    fn hover_vertex() {
+        let values = vec![vec![0., 1., 2.], vec![1., 2., 3.], vec![2., 3., 4.]];
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            let mut hovered = None;
+            build_in_window(ui, |window| {
+                hovered = SurfacePlotBuilder::new(&values)
+                    .size((10., 10.))
+                    .build(loc!(), window);
+            });
+            hovered
+        };
+
+        // The vertices are 5 apart, from -5 to 5 in both directions
+        assert_eq!(build(&mut ui), None);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-4., -4.)));
+        assert_eq!(build(&mut ui), Some((0, 0, 0.)));
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(0.5, 4.)));
+        assert_eq!(build(&mut ui), Some((2, 1, 3.)));
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(20., 20.)));
+        assert_eq!(build(&mut ui), None);
+    }
+
+    #[test]
+    fn automatic_range() {
+        let range = |values: Vec<Vec<f32>>| SurfacePlotBuilder::new(&values).create().range;
+        assert_eq!(range(vec![vec![1., f32::NAN], vec![-2., 3.]]), (-2., 3.));
+        assert_eq!(range(vec![vec![2., 2.]]), (2., 3.));
+        assert_eq!(range(vec![vec![f32::NAN, f32::INFINITY]]), (0., 1.));
+        assert_eq!(range(Vec::new()), (0., 1.));
+    }
+}
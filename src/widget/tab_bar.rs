@@ -0,0 +1,589 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3};
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// Builds the widgets of a tab in its page
+type PageGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// A row of tabs, displaying the content of the selected one below it.
+///
+/// Each tab has a name and a generator. Only the generator of the selected tab
+/// is called, but the widgets of the other tabs are kept as they were,
+/// so they are found in the same state when their tab is selected again.
+/// The pages, and the selection, are kept by tab index. Tabs added with `tab_with_id`
+/// are kept by their id instead, so that they follow their tab when tabs are added,
+/// removed or moved.
+///
+/// `build` returns the index of the selected tab.
+pub struct TabBarBuilder<'a> {
+    titles: Vec<String>,
+    keys: Vec<TabKey>,
+    generators: Vec<PageGenerator<'a>>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    color: (f32, f32, f32, f32),
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a> TabBarBuilder<'a> {
+    pub fn new(font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            titles: Vec::new(),
+            keys: Vec::new(),
+            generators: Vec::new(),
+            font: font.clone(),
+            font_size: 1.,
+            color: BACKGROUND,
+            selected_color: SELECTED,
+            text_color: TEXT,
+        }
+    }
+
+    /// Adds a tab, whose content is built by the generator
+    pub fn tab<F: 'a + FnMut(&NodeReference)>(mut self, name: String, generator: F) -> Self {
+        self.keys.push(TabKey::Index(self.titles.len()));
+        self.titles.push(name);
+        self.generators.push(Box::new(generator));
+        self
+    }
+
+    /// Adds a tab like `tab`, whose page is kept by the given id, unique in the tab bar
+    pub fn tab_with_id<F: 'a + FnMut(&NodeReference)>(
+        mut self,
+        id: UniqueId,
+        name: String,
+        generator: F,
+    ) -> Self {
+        self.keys.push(TabKey::Id(id));
+        self.titles.push(name);
+        self.generators.push(Box::new(generator));
+        self
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for TabBarBuilder<'a> {
+    type AchievedType = TabBar;
+    type UpdateFeedback = ();
+    type BuildFeedback = usize;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        let selected = widget
+            .keys
+            .get(widget.selected)
+            .and_then(|key| self.keys.iter().position(|other| other == key));
+        widget.selected =
+            selected.unwrap_or_else(|| widget.selected.min(self.titles.len().max(1) - 1));
+        widget.active = None;
+        widget.titles = self.titles;
+        widget.keys = self.keys;
+        widget.font_size = self.font_size;
+        widget.color = self.color;
+        widget.selected_color = self.selected_color;
+        widget.text_color = self.text_color;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        TabBar {
+            titles: self.titles,
+            keys: self.keys,
+            selected: 0,
+            pages: Vec::new(),
+            active: None,
+            font: self.font,
+            font_size: self.font_size,
+            color: self.color,
+            selected_color: self.selected_color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generators = std::mem::take(&mut self.generators);
+        let page_ids: Vec<ComponentId> = self.keys.iter().map(|key| page_id(loc, *key)).collect();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut selected = 0;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            // The pages of the tabs that disappeared are dropped
+            widget
+                .pages
+                .retain(|page| page_ids.iter().any(|id| page.has_id(*id)));
+            selected = widget.selected;
+        });
+        if let Some(generator) = generators.get_mut(selected) {
+            let (page, _) = node_ref
+                .query::<TabPage>(page_ids[selected])
+                .update(TabPageBuilder);
+            (generator)(&page);
+            page.apply_to_widget::<TabPage, _>(|_, widget| {
+                widget.content.retain(|child| child.is_valid())
+            });
+        }
+        selected
+    }
+}
+
+/// What the page of a tab, and the selection, are kept by
+#[derive(Copy, Clone, PartialEq, Hash)]
+enum TabKey {
+    Index(usize),
+    Id(UniqueId),
+}
+
+/// The id of the page of the tab with the given key, in a tab bar built at `loc`
+fn page_id(loc: CodeLocation, key: TabKey) -> ComponentId {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    ComponentId::new_biased::<TabPage>(loc, hasher.finish())
+}
+
+/// The widget behind a `TabBarBuilder`
+pub struct TabBar {
+    titles: Vec<String>,
+    keys: Vec<TabKey>,
+    selected: usize,
+    /// The pages of all the tabs built so far
+    pages: Vec<NodeReference>,
+    /// The page of the selected tab, once it was built in the current frame
+    active: Option<NodeReference>,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    color: (f32, f32, f32, f32),
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+const TAB_SEPARATOR: f32 = 0.1;
+
+impl TabBar {
+    fn header_height(&self) -> f32 {
+        self.font_size + 2. * PADDING
+    }
+
+    /// Returns the horizontal position and the width of each tab in the header
+    fn tab_positions(&self) -> Vec<(f32, f32)> {
+        let font = self.font.borrow();
+        let mut cursor = 0.;
+        self.titles
+            .iter()
+            .map(|title| {
+                let width = font.size_of(title.as_str(), self.font_size).0 + 2. * PADDING;
+                let position = (cursor, width);
+                cursor += width + TAB_SEPARATOR;
+                position
+            })
+            .collect()
+    }
+}
+
+impl WidgetLogic for TabBar {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let header_height = self.header_height();
+        let header_width = self
+            .tab_positions()
+            .last()
+            .map_or(0., |(x, width)| x + width);
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+
+        let (mut width, mut height) = (header_width, header_height);
+        if let Some(page) = &self.active {
+            let response = page.layout(&LayoutQuery {
+                available_space: (
+                    query.available_space.0,
+                    query
+                        .available_space
+                        .1
+                        .map(|available| (available - header_height - WIDGET_SEPARATOR).max(0.)),
+                ),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status = response.status;
+            page.set_size(response.size);
+            page.set_transform(Similarity3::identity());
+            width = width.max(response.size.0);
+            height += WIDGET_SEPARATOR + response.size.1;
+        }
+
+        let (available_width, available_height) = (
+            query.available_space.0.unwrap_or(width),
+            query.available_space.1.unwrap_or(height),
+        );
+        if width > available_width {
+            status.0 = LayoutStatus::and(status.0, LayoutStatus::Inconsistencies);
+        }
+        if height > available_height {
+            status.1 = LayoutStatus::and(status.1, LayoutStatus::Inconsistencies);
+        }
+
+        LayoutResponse {
+            size: (available_width, height),
+            status,
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        let page = self.pages.iter().find(|page| page.has_id(id)).cloned();
+        let result = match page {
+            Some(node_ref) => WidgetQueryResult::Initialized(node_ref),
+            None => {
+                let node_ref = Node::new_reference(id, &metadata.ui_properties);
+                self.pages.push(node_ref.clone());
+                WidgetQueryResult::Uninitialized(node_ref)
+            }
+        };
+        self.active = Some(match &result {
+            WidgetQueryResult::Initialized(node_ref)
+            | WidgetQueryResult::Uninitialized(node_ref) => node_ref.clone(),
+        });
+        result
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let header_height = self.header_height();
+        let header_transform =
+            metadata.transform * Translation3::new(0., size.1 - header_height, 0.);
+
+        let mut list = DrawList::new();
+        for (index, (title, (x, width))) in self.titles.iter().zip(self.tab_positions()).enumerate()
+        {
+            let transform = header_transform * Translation3::new(x, 0., 0.);
+            let color = if index == self.selected {
+                self.selected_color
+            } else {
+                self.color
+            };
+            list.commands
+                .push(quad(width, header_height, None, color, transform));
+            list.commands.push(draw_text(
+                title.as_str(),
+                &self.font,
+                self.font_size,
+                self.text_color,
+                (transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+            ));
+        }
+
+        if let Some(page) = &self.active {
+            list.list.push(page.draw());
+            list.list_transform = metadata.transform.to_homogeneous();
+        }
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let bottom = size.1 - self.header_height();
+        let points = [
+            Point3::new(0., bottom, 0.),
+            Point3::new(size.0, bottom, 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let mut result: Vec<(f32, NodeReference)> = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        .map(|d| vec![(d, self_node)])
+        .unwrap_or_default();
+
+        if let Some(page) = &self.active {
+            result.extend(page.interaction_distance(&new_ray, page.clone()));
+        }
+        result
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if let Some((x, _)) = metadata.pointer_position() {
+                    if let Some(index) = self
+                        .tab_positions()
+                        .iter()
+                        .position(|(left, width)| x >= *left && x <= left + width)
+                    {
+                        self.selected = index;
+                    }
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The builder of the page holding the content of a tab
+struct TabPageBuilder;
+
+impl WidgetBuilder for TabPageBuilder {
+    type AchievedType = TabPage;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        TabPage {
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(self, _loc: CodeLocation, _parent: &NodeReference) -> Self::BuildFeedback {}
+}
+
+/// The content of a tab, displayed from top to bottom
+struct TabPage {
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl WidgetLogic for TabPage {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut sizes = Vec::with_capacity(self.content.len());
+        let (mut width, mut height) = (0f32, 0f32);
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (
+                    query.available_space.0,
+                    query
+                        .available_space
+                        .1
+                        .map(|available| (available - height).max(0.)),
+                ),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            width = width.max(response.size.0);
+            height += response.size.1 + WIDGET_SEPARATOR;
+            sizes.push(response.size);
+        }
+        let height = (height - WIDGET_SEPARATOR).max(0.);
+
+        let mut cursor = height;
+        for (node, size) in self.content.iter().zip(sizes) {
+            cursor -= size.1;
+            node.set_size(size);
+            node.set_transform(Similarity3::identity() * Translation3::new(0., cursor, 0.));
+            cursor -= WIDGET_SEPARATOR;
+        }
+
+        LayoutResponse {
+            size: (width, height),
+            status,
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        self.content.iter().for_each(|node| {
+            list.list.push(node.draw());
+        });
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+    use crate::widget::*;
+
+    #[test]
+    fn switch_tabs() {
+        let font = mock_font();
+        let mut first = "first".to_string();
+        let mut second = "second".to_string();
+        let mut ui = Interface::new();
+        let mut build = |ui: &mut Interface| {
+            let mut selected = 0;
+            build_in_window(ui, |window| {
+                selected = TabBarBuilder::new(&font)
+                    .tab("One".to_string(), |tab| {
+                        TextBuilder::new(&mut first, &font).build(loc!(), tab);
+                    })
+                    .tab("Two".to_string(), |tab| {
+                        TextBuilder::new(&mut second, &font).build(loc!(), tab);
+                    })
+                    .build(loc!(), window);
+            });
+            selected
+        };
+
+        assert_eq!(build(&mut ui), 0);
+        // Focus the end of the text of the first tab, which is 2.5 wide, under the header
+        press_at(&mut ui, -2.55, 2.6);
+        build(&mut ui);
+
+        // The second tab starts after the first one, 1.9 wide
+        press_at(&mut ui, -2.5, 4.3);
+        assert_eq!(build(&mut ui), 1);
+
+        // Back to the first tab, the text is still focused
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui), 0);
+        ui.register_event(Event::Character('!'), None);
+        build(&mut ui);
+        assert_eq!(first, "first!");
+        assert_eq!(second, "second");
+    }
+
+    #[test]
+    fn remove_tab() {
+        let font = mock_font();
+        let mut first = "first".to_string();
+        let mut second = "second".to_string();
+        let mut ui = Interface::new();
+        let mut build = |ui: &mut Interface, with_first: bool| {
+            let mut selected = 0;
+            build_in_window(ui, |window| {
+                let mut tabs = TabBarBuilder::new(&font);
+                if with_first {
+                    tabs = tabs.tab_with_id(1, "One".to_string(), |tab| {
+                        TextBuilder::new(&mut first, &font).build(loc!(), tab);
+                    });
+                }
+                selected = tabs
+                    .tab_with_id(2, "Two".to_string(), |tab| {
+                        TextBuilder::new(&mut second, &font).build(loc!(), tab);
+                    })
+                    .build(loc!(), window);
+            });
+            selected
+        };
+
+        // Select the second tab, and focus the end of its text, 3 wide
+        build(&mut ui, true);
+        press_at(&mut ui, -2.5, 4.3);
+        assert_eq!(build(&mut ui, true), 1);
+        press_at(&mut ui, -2.05, 2.6);
+        build(&mut ui, true);
+
+        // Without the first tab, the second one is still selected, with the same page
+        assert_eq!(build(&mut ui, false), 0);
+        ui.register_event(Event::Character('!'), None);
+        build(&mut ui, false);
+        assert_eq!(second, "second!");
+        assert_eq!(first, "first");
+    }
+
+    #[test]
+    fn same_titles() {
+        let font = mock_font();
+        let mut first = "first".to_string();
+        let mut second = "second".to_string();
+        let mut ui = Interface::new();
+        let mut build = |ui: &mut Interface| {
+            let mut selected = 0;
+            build_in_window(ui, |window| {
+                selected = TabBarBuilder::new(&font)
+                    .tab("Tab".to_string(), |tab| {
+                        TextBuilder::new(&mut first, &font).build(loc!(), tab);
+                    })
+                    .tab("Tab".to_string(), |tab| {
+                        TextBuilder::new(&mut second, &font).build(loc!(), tab);
+                    })
+                    .build(loc!(), window);
+            });
+            selected
+        };
+
+        // Focus the end of the text of the first tab, then select the second tab, 1.9 wide each
+        build(&mut ui);
+        press_at(&mut ui, -2.55, 2.6);
+        build(&mut ui);
+        press_at(&mut ui, -2.5, 4.3);
+        assert_eq!(build(&mut ui), 1);
+
+        // The tabs have their own pages, so the first one is found as it was
+        press_at(&mut ui, -4., 4.3);
+        assert_eq!(build(&mut ui), 0);
+        ui.register_event(Event::Character('!'), None);
+        build(&mut ui);
+        assert_eq!(first, "first!");
+        assert_eq!(second, "second");
+    }
+}
@@ -0,0 +1,876 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use nalgebra::{Point3, Similarity3, Translation3};
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// How wide a column of a table is
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ColumnWidth {
+    Fixed(f32),
+    /// The column shares the width left by the fixed columns with the other filling columns
+    Fill,
+}
+
+/// Where the content of a cell is placed horizontally
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+/// The definition of a column of a table: its title, its width and the alignment of its cells
+pub struct Column {
+    title: String,
+    width: ColumnWidth,
+    align: Align,
+    sortable: bool,
+}
+
+impl Column {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: title.to_string(),
+            width: ColumnWidth::Fill,
+            align: Align::Left,
+            sortable: true,
+        }
+    }
+
+    pub fn width(self, width: ColumnWidth) -> Self {
+        Self { width, ..self }
+    }
+
+    pub fn align(self, align: Align) -> Self {
+        Self { align, ..self }
+    }
+
+    /// Sets whether clicking the header of the column sorts the table, which is the default
+    pub fn sortable(self, sortable: bool) -> Self {
+        Self { sortable, ..self }
+    }
+}
+
+/// Builds the widgets of a row, given its index and a cell for each column
+type RowGenerator<'a> = Box<dyn 'a + FnMut(usize, &[NodeReference])>;
+
+/// A table, with a header giving the title of each column, and rows of the same height
+/// scrolled below it with the wheel.
+///
+/// Only the visible rows are built: the generator is called with the index of each of them,
+/// and a cell for each column, in which the widgets of the row are built. Each row has its
+/// own node, so the widgets built with the same location in different rows are different,
+/// and keep their state while the row stays visible.
+///
+/// Clicking the header of a sortable column sorts the table by it, ascending first, then
+/// descending. The table does not sort the rows itself: `build` returns the new sort when
+/// it changes, so that the application can sort its data. Clicking a row selects it.
+pub struct TableBuilder<'a> {
+    columns: Vec<Column>,
+    rows: usize,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    row_height: f32,
+    size: Option<(f32, f32)>,
+    striped: bool,
+    selection: Option<&'a mut Option<usize>>,
+    color: (f32, f32, f32, f32),
+    header_color: (f32, f32, f32, f32),
+    stripe_color: (f32, f32, f32, f32),
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    generator: Option<RowGenerator<'a>>,
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const HEADER: (f32, f32, f32, f32) = (0.2, 0.22, 0.27, 1.);
+const STRIPE: (f32, f32, f32, f32) = (0.18, 0.196, 0.24, 1.);
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a> TableBuilder<'a> {
+    pub fn new<F: 'a + FnMut(usize, &[NodeReference])>(
+        columns: Vec<Column>,
+        rows: usize,
+        font: &Rc<RefCell<dyn FontAtlas>>,
+        generator: F,
+    ) -> Self {
+        Self {
+            columns,
+            rows,
+            font: font.clone(),
+            font_size: 1.,
+            row_height: 1.4,
+            size: None,
+            striped: true,
+            selection: None,
+            color: BACKGROUND,
+            header_color: HEADER,
+            stripe_color: STRIPE,
+            selected_color: SELECTED,
+            text_color: TEXT,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the size of the table. By default, it takes all the space given by its parent
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    /// Sets the size of the titles of the header
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    /// Sets the height of the header and of each row
+    pub fn row_height(self, row_height: f32) -> Self {
+        Self { row_height, ..self }
+    }
+
+    /// Sets whether every other row has a different background, which is the default
+    pub fn striped(self, striped: bool) -> Self {
+        Self { striped, ..self }
+    }
+
+    /// Binds the index of the selected row, updated when a row is clicked
+    pub fn selection(self, selection: &'a mut Option<usize>) -> Self {
+        Self {
+            selection: Some(selection),
+            ..self
+        }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn header_color(self, header_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            header_color,
+            ..self
+        }
+    }
+
+    pub fn stripe_color(self, stripe_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            stripe_color,
+            ..self
+        }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for TableBuilder<'a> {
+    type AchievedType = Table;
+    type UpdateFeedback = Option<(usize, SortOrder)>;
+    type BuildFeedback = Option<(usize, SortOrder)>;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.columns = self.columns;
+        widget.rows = self.rows;
+        widget.font_size = self.font_size;
+        widget.row_height = self.row_height;
+        widget.size = self.size;
+        widget.color = self.color;
+        widget.header_color = self.header_color;
+        widget.text_color = self.text_color;
+
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+
+        let sort_changed = widget.sort_changed;
+        widget.sort_changed = false;
+        widget.sort.filter(|_| sort_changed)
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Table {
+            columns: self.columns,
+            rows: self.rows,
+            font: self.font,
+            font_size: self.font_size,
+            row_height: self.row_height,
+            size: self.size,
+            offset: 0.,
+            sort: None,
+            sort_changed: false,
+            content: Vec::new(),
+            valid_index: 0,
+            color: self.color,
+            header_color: self.header_color,
+            text_color: self.text_color,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let mut selection = self.selection.take();
+        let rows = self.rows;
+        let (striped, stripe_color, selected_color) =
+            (self.striped, self.stripe_color, self.selected_color);
+        let (node_ref, sort) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let mut visible = 0..0;
+        let mut aligns = Vec::new();
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, widget| {
+            visible = widget.visible_rows(widget.size.unwrap_or(metadata.size));
+            aligns = widget.columns.iter().map(|column| column.align).collect();
+        });
+
+        for index in visible.filter(|index| *index < rows) {
+            let row_id = ComponentId::new_biased::<TableRow>(loc, index as UniqueId);
+            let color = if striped && index % 2 == 1 {
+                Some(stripe_color)
+            } else {
+                None
+            };
+            let (row, clicked) = node_ref
+                .query::<TableRow>(row_id)
+                .update(TableRowBuilder { color });
+            if let (true, Some(selection)) = (clicked, selection.as_mut()) {
+                **selection = Some(index);
+            }
+            if matches!(&selection, Some(selection) if **selection == Some(index)) {
+                row.apply_to_widget::<TableRow, _>(|_, widget| widget.color = Some(selected_color));
+            }
+
+            let cells = aligns
+                .iter()
+                .enumerate()
+                .map(|(column, align)| {
+                    let cell_id = ComponentId::new_biased::<TableCell>(loc, column as UniqueId);
+                    let (cell, _) = row
+                        .query::<TableCell>(cell_id)
+                        .update(TableCellBuilder { align: *align });
+                    cell
+                })
+                .collect::<Vec<_>>();
+            (generator)(index, &cells);
+            for cell in &cells {
+                cell.apply_to_widget::<TableCell, _>(|_, widget| {
+                    widget.content.retain(|child| child.is_valid())
+                });
+            }
+            row.apply_to_widget::<TableRow, _>(|_, widget| {
+                widget.content.retain(|child| child.is_valid())
+            });
+        }
+
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+        sort
+    }
+}
+
+/// The widget behind a `TableBuilder`, holding the visible rows
+pub struct Table {
+    columns: Vec<Column>,
+    rows: usize,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    row_height: f32,
+    size: Option<(f32, f32)>,
+    /// How far the rows are scrolled, from the top
+    offset: f32,
+    sort: Option<(usize, SortOrder)>,
+    /// Whether the sort was changed since the last update
+    sort_changed: bool,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+    color: (f32, f32, f32, f32),
+    header_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+}
+
+const PADDING: f32 = 0.2;
+const SCROLLBAR_WIDTH: f32 = 0.3;
+const THUMB: (f32, f32, f32, f32) = (0.3, 0.33, 0.4, 1.);
+/// How far the rows are in front of the table, so that they are hit before it
+const ROW_DEPTH: f32 = 0.01;
+
+impl Table {
+    /// The height of the part of the table displaying the rows, below the header
+    fn viewport(&self, size: (f32, f32)) -> f32 {
+        (size.1 - self.row_height).max(0.)
+    }
+
+    fn max_offset(&self, size: (f32, f32)) -> f32 {
+        (self.rows as f32 * self.row_height - self.viewport(size)).max(0.)
+    }
+
+    /// The indices of the rows at least partly visible
+    fn visible_rows(&self, size: (f32, f32)) -> std::ops::Range<usize> {
+        let first = (self.offset / self.row_height).floor() as usize;
+        let last = ((self.offset + self.viewport(size)) / self.row_height).ceil() as usize;
+        first..last.min(self.rows)
+    }
+
+    /// The horizontal position and the width of each column
+    fn column_positions(&self, width: f32) -> Vec<(f32, f32)> {
+        let fixed = self
+            .columns
+            .iter()
+            .map(|column| match column.width {
+                ColumnWidth::Fixed(width) => width,
+                ColumnWidth::Fill => 0.,
+            })
+            .sum::<f32>();
+        let filling = self
+            .columns
+            .iter()
+            .filter(|column| column.width == ColumnWidth::Fill)
+            .count();
+        let fill = (width - fixed).max(0.) / filling.max(1) as f32;
+
+        let mut cursor = 0.;
+        self.columns
+            .iter()
+            .map(|column| {
+                let width = match column.width {
+                    ColumnWidth::Fixed(width) => width,
+                    ColumnWidth::Fill => fill,
+                };
+                cursor += width;
+                (cursor - width, width)
+            })
+            .collect()
+    }
+
+    /// The title of a column in the header, with the direction of the sort if it is sorted by it
+    fn header_title(&self, index: usize) -> String {
+        let title = &self.columns[index].title;
+        match self.sort {
+            Some((column, SortOrder::Ascending)) if column == index => format!("{} ^", title),
+            Some((column, SortOrder::Descending)) if column == index => format!("{} v", title),
+            _ => title.clone(),
+        }
+    }
+}
+
+impl WidgetLogic for Table {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = self.size.unwrap_or((
+            query.available_space.0.unwrap_or(10.),
+            query.available_space.1.unwrap_or(10.),
+        ));
+        self.offset = self.offset.max(0.).min(self.max_offset(size));
+        let width = size.0 - SCROLLBAR_WIDTH;
+        let columns = self.column_positions(width);
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+
+        // The rows are built in order, from the first visible one
+        let first = self.visible_rows(size).start;
+        for (index, row) in self.content.iter().enumerate() {
+            let response = row.layout(&LayoutQuery {
+                available_space: (Some(width), Some(self.row_height)),
+                objectives: (Objective::Maximize, Objective::Maximize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            row.apply_to_widget::<TableRow, _>(|_, widget| widget.columns = columns.clone());
+            let y = size.1 - (first + index + 2) as f32 * self.row_height + self.offset;
+            row.set_size((width, self.row_height));
+            row.set_transform(Similarity3::identity() * Translation3::new(0., y, ROW_DEPTH));
+        }
+
+        LayoutResponse { size, status }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let viewport = self.viewport(size);
+        let header = size.1 - viewport;
+
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+        list.commands.push(quad(
+            size.0,
+            header,
+            None,
+            self.header_color,
+            metadata.transform * Translation3::new(0., viewport, 0.01),
+        ));
+        let columns = self.column_positions(size.0 - SCROLLBAR_WIDTH);
+        for (index, (x, width)) in columns.iter().enumerate() {
+            let title = self.header_title(index);
+            let title_width = self.font.borrow().size_of(title.as_str(), self.font_size).0;
+            let position = match self.columns[index].align {
+                Align::Left => x + PADDING,
+                Align::Center => x + (width - title_width) / 2.,
+                Align::Right => x + width - PADDING - title_width,
+            };
+            list.commands.push(draw_text(
+                title.as_str(),
+                &self.font,
+                self.font_size,
+                self.text_color,
+                (metadata.transform
+                    * Translation3::new(position, viewport + (header - self.font_size) / 2., 0.02))
+                .to_homogeneous(),
+            ));
+        }
+
+        let content = self.rows as f32 * self.row_height;
+        if content > viewport {
+            let length = (viewport * viewport / content).min(viewport);
+            let position = self.offset / (content - viewport) * (viewport - length);
+            list.commands.push(quad(
+                SCROLLBAR_WIDTH,
+                length,
+                None,
+                THUMB,
+                metadata.transform
+                    * Translation3::new(
+                        size.0 - SCROLLBAR_WIDTH,
+                        viewport - position - length,
+                        0.01,
+                    ),
+            ));
+        }
+
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list.clip = Some((0., 0., size.0 - SCROLLBAR_WIDTH, viewport));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let distance = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap());
+
+        // The rows are always reached, so that the rays stay up to date,
+        // but they can only be interacted with below the header
+        let content: Vec<(f32, NodeReference)> = self
+            .content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect();
+
+        match distance {
+            Some(distance) => {
+                let viewport = self.viewport(size);
+                let inside = matches!(
+                    metadata.pointer_position(),
+                    Some((x, y)) if x <= size.0 - SCROLLBAR_WIDTH && y <= viewport
+                );
+                let mut result = if inside { content } else { Vec::new() };
+                result.push((distance, self_node));
+                result
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        let size = metadata.size;
+        match event {
+            Event::MouseScrolled(delta) => {
+                let offset = self.offset - delta * self.row_height;
+                self.offset = offset.max(0.).min(self.max_offset(size));
+                EventResponse::Registered
+            }
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                let (x, y) = match metadata.pointer_position() {
+                    Some(position) => position,
+                    None => return EventResponse::Pass,
+                };
+                if y < self.viewport(size) {
+                    return EventResponse::Pass;
+                }
+                let column = self
+                    .column_positions(size.0 - SCROLLBAR_WIDTH)
+                    .iter()
+                    .position(|(left, width)| x >= *left && x <= left + width);
+                if let Some(column) = column.filter(|column| self.columns[*column].sortable) {
+                    self.sort = match self.sort {
+                        Some((sorted, SortOrder::Ascending)) if sorted == column => {
+                            Some((column, SortOrder::Descending))
+                        }
+                        _ => Some((column, SortOrder::Ascending)),
+                    };
+                    self.sort_changed = true;
+                }
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The builder of a row of a table
+struct TableRowBuilder {
+    /// The background of the row, if it is striped
+    color: Option<(f32, f32, f32, f32)>,
+}
+
+impl WidgetBuilder for TableRowBuilder {
+    type AchievedType = TableRow;
+    type UpdateFeedback = bool;
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.color = self.color;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+
+        let clicked = widget.clicked;
+        widget.clicked = false;
+        clicked
+    }
+
+    fn create(self) -> Self::AchievedType {
+        TableRow {
+            color: self.color,
+            columns: Vec::new(),
+            clicked: false,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(self, _loc: CodeLocation, _parent: &NodeReference) -> Self::BuildFeedback {}
+}
+
+/// A row of a table, holding a cell for each column
+pub struct TableRow {
+    color: Option<(f32, f32, f32, f32)>,
+    /// The horizontal position and the width of each column, given by the table
+    columns: Vec<(f32, f32)>,
+    clicked: bool,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl WidgetLogic for TableRow {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let height = query.available_space.1.unwrap_or(0.);
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        for (cell, (x, width)) in self.content.iter().zip(&self.columns) {
+            let response = cell.layout(&LayoutQuery {
+                available_space: (Some(*width), Some(height)),
+                objectives: (Objective::Maximize, Objective::Maximize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            cell.set_size((*width, height));
+            cell.set_transform(Similarity3::identity() * Translation3::new(*x, 0., 0.));
+        }
+        LayoutResponse {
+            size: (query.available_space.0.unwrap_or(0.), height),
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        if let Some(color) = self.color {
+            list.commands.push(quad(
+                size.0,
+                size.1,
+                None,
+                color,
+                metadata.transform * Translation3::new(0., 0., 0.01),
+            ));
+        }
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let mut distances: Vec<(f32, NodeReference)> = self
+            .content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect();
+        if let Some(distance) = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap())
+        {
+            distances.push((distance, self_node));
+        }
+        distances
+    }
+
+    fn send_event(&mut self, _metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                self.clicked = true;
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The builder of a cell of a table
+struct TableCellBuilder {
+    align: Align,
+}
+
+impl WidgetBuilder for TableCellBuilder {
+    type AchievedType = TableCell;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.align = self.align;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        TableCell {
+            align: self.align,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(self, _loc: CodeLocation, _parent: &NodeReference) -> Self::BuildFeedback {}
+}
+
+/// A cell of a table, placing its content side by side, centered vertically
+/// and aligned horizontally like its column
+pub struct TableCell {
+    align: Align,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl WidgetLogic for TableCell {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = (
+            query.available_space.0.unwrap_or(0.),
+            query.available_space.1.unwrap_or(0.),
+        );
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let responses = self
+            .content
+            .iter()
+            .map(|node| {
+                let response = node.layout(&LayoutQuery {
+                    available_space: (Some(size.0 - 2. * PADDING), None),
+                    objectives: (Objective::Minimize, Objective::Minimize),
+                });
+                status.0 = LayoutStatus::and(status.0, response.status.0);
+                status.1 = LayoutStatus::and(status.1, response.status.1);
+                response.size
+            })
+            .collect::<Vec<_>>();
+
+        let width = responses.iter().map(|size| size.0).sum::<f32>()
+            + WIDGET_SEPARATOR * responses.len().saturating_sub(1) as f32;
+        let mut cursor = match self.align {
+            Align::Left => PADDING,
+            Align::Center => (size.0 - width) / 2.,
+            Align::Right => size.0 - PADDING - width,
+        };
+        for (node, child_size) in self.content.iter().zip(responses) {
+            node.set_size(child_size);
+            node.set_transform(
+                Similarity3::identity()
+                    * Translation3::new(cursor, (size.1 - child_size.1) / 2., 0.02),
+            );
+            cursor += child_size.0 + WIDGET_SEPARATOR;
+        }
+        LayoutResponse { size, status }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+    use crate::widget::{CheckBoxBuilder, LabelBuilder};
+
+    #[test]
+    fn lazy_rows_sort_and_selection() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let mut checked = vec![false; 100];
+        let mut selection = None;
+        // Returns the rows built, and the sort changed
+        let build = |ui: &mut Interface, checked: &mut Vec<bool>, selection: &mut Option<usize>| {
+            let (mut built, mut sort) = (Vec::new(), None);
+            build_in_window(ui, |window| {
+                let columns = vec![
+                    Column::new("#").width(ColumnWidth::Fixed(2.)),
+                    Column::new("Name"),
+                    Column::new("Done").align(Align::Right).sortable(false),
+                ];
+                sort = TableBuilder::new(columns, 100, &font, |row, cells| {
+                    built.push(row);
+                    LabelBuilder::new(&row.to_string(), &font).build(loc!(), &cells[0]);
+                    LabelBuilder::new("Item", &font).build(loc!(), &cells[1]);
+                    let mut done = checked[row];
+                    CheckBoxBuilder::new(String::new(), &mut done, &font).build(loc!(), &cells[2]);
+                    checked[row] = done;
+                })
+                .selection(selection)
+                .build(loc!(), window);
+            });
+            (built, sort)
+        };
+
+        // The visible rows are only known once the table has been laid out,
+        // then the header goes from y = 3.6 to 5, and the 8.6 below it show 7 rows
+        build(&mut ui, &mut checked, &mut selection);
+        assert_eq!(
+            build(&mut ui, &mut checked, &mut selection),
+            ((0..7).collect(), None)
+        );
+
+        // The columns are 2, 3.85 and 3.85 wide
+        press_at(&mut ui, -2., 4.3);
+        assert_eq!(
+            build(&mut ui, &mut checked, &mut selection).1,
+            Some((1, SortOrder::Ascending))
+        );
+        press_at(&mut ui, -2., 4.3);
+        assert_eq!(
+            build(&mut ui, &mut checked, &mut selection).1,
+            Some((1, SortOrder::Descending))
+        );
+        press_at(&mut ui, 4., 4.3);
+        assert_eq!(build(&mut ui, &mut checked, &mut selection).1, None);
+
+        // The third row goes from y = -0.6 to 0.8
+        press_at(&mut ui, -2., 0.);
+        build(&mut ui, &mut checked, &mut selection);
+        assert_eq!(selection, Some(2));
+
+        // The checkboxes keep their state in each row
+        press_at(&mut ui, 3.5, 2.9);
+        build(&mut ui, &mut checked, &mut selection);
+        build(&mut ui, &mut checked, &mut selection);
+        assert_eq!(checked.iter().position(|done| *done), Some(0));
+
+        ui.register_event(Event::MouseScrolled(-2.), Some(&ray_at(0., 0.)));
+        assert_eq!(
+            build(&mut ui, &mut checked, &mut selection).0,
+            (2..9).collect::<Vec<_>>()
+        );
+    }
+}
@@ -15,7 +15,9 @@ pub struct TextBuilder<'a> {
 
 const BACKGROUND_FOCUSED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
 const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const SELECTION: (f32, f32, f32, f32) = (0.349, 0.475, 0.765, 1.);
 const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+const CARET_WIDTH: f32 = 0.05;
 
 impl<'a> TextBuilder<'a> {
     pub fn new(text: &'a mut String, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
@@ -46,14 +48,15 @@ impl<'a> WidgetBuilder for TextBuilder<'a> {
         _metadata: &NodeMetadata,
         widget: &mut Self::AchievedType,
     ) -> Self::UpdateFeedback {
-        self.text.clone_from(&widget.text);
+        self.text.clear();
+        self.text.push_str(widget.edit.text());
         widget.size = self.size;
         widget.text_color = self.text_color;
     }
 
     fn create(self) -> Self::AchievedType {
         Text {
-            text: self.text.clone(),
+            edit: TextEditState::new(self.text.clone()),
             font: self.font,
             size: self.size,
             text_color: self.text_color,
@@ -67,9 +70,10 @@ impl<'a> WidgetBuilder for TextBuilder<'a> {
     }
 }
 
-/// A basic widget that can display a text given a font
+/// A basic widget that can display a text given a font, and edit it
+/// when focused
 pub struct Text {
-    text: String,
+    edit: TextEditState,
     font: Rc<RefCell<dyn FontAtlas>>,
     size: f32,
     text_color: (f32, f32, f32, f32),
@@ -80,9 +84,9 @@ impl WidgetLogic for Text {
         let (mut width, height) = if let Some(max_width) = query.available_space.0 {
             self.font
                 .borrow()
-                .multiline_size_of(self.text.as_str(), self.size, max_width)
+                .multiline_size_of(self.edit.text(), self.size, max_width)
         } else {
-            self.font.borrow().size_of(self.text.as_str(), self.size)
+            self.font.borrow().size_of(self.edit.text(), self.size)
         };
 
         if let Some(available_height) = query.available_space.1 {
@@ -127,7 +131,7 @@ impl WidgetLogic for Text {
         );
 
         let text_command = draw_multiline_text(
-            self.text.as_str(),
+            self.edit.text(),
             &self.font,
             self.size,
             metadata.size.0,
@@ -138,6 +142,32 @@ impl WidgetLogic for Text {
 
         let mut list = DrawList::new();
         list.commands.push(background_command);
+
+        if metadata.is_focused() {
+            let positions = self.character_positions(metadata);
+
+            if let Some((start, end)) = self.edit.selection() {
+                let selection: Vec<(f32, f32, f32, f32)> = positions[start..end]
+                    .iter()
+                    .map(|position| (position.x, position.y, position.width, self.size))
+                    .collect();
+                list.commands.push(rectangles(
+                    &selection,
+                    SELECTION,
+                    metadata.transform * Translation3::new(0., 0., 0.005),
+                ));
+            }
+
+            let caret = positions[self.edit.caret()];
+            list.commands.push(quad(
+                CARET_WIDTH,
+                self.size,
+                None,
+                self.text_color,
+                metadata.transform * Translation3::new(caret.x, caret.y, 0.015),
+            ));
+        }
+
         list.commands.push(text_command);
         list
     }
@@ -149,7 +179,10 @@ impl WidgetLogic for Text {
         self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         let size = metadata.size;
         let points = [
             Point3::new(0., 0., 0.),
@@ -170,19 +203,66 @@ impl WidgetLogic for Text {
     }
 
     fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        let Modifiers { shift, ctrl, .. } = metadata.modifiers();
         match event {
             Event::MouseButtonPressed(MouseButton::Left)
             | Event::MouseButtonPressed(MouseButton::Touch) => {
+                if let Some(point) = metadata.pointer_position() {
+                    let positions = self.character_positions(metadata);
+                    let index = caret_index_at(&positions, self.size, point);
+                    // Shift-clicking only extends the selection when already editing
+                    self.edit.set_caret(index, shift && metadata.is_focused());
+                }
                 metadata.request_focus();
                 EventResponse::Registered
             }
-            Event::Character(c) => {
-                match c {
-                    '\u{8}' => {
-                        self.text.pop();
+            Event::KeyPressed(key) => {
+                match key {
+                    Key::ArrowLeft => self.edit.move_left(shift, ctrl),
+                    Key::ArrowRight => self.edit.move_right(shift, ctrl),
+                    Key::ArrowUp => self.move_vertically(metadata, 1., shift),
+                    Key::ArrowDown => self.move_vertically(metadata, -1., shift),
+                    Key::Home => self.edit.move_home(shift, ctrl),
+                    Key::End => self.edit.move_end(shift, ctrl),
+                    Key::Backspace => self.edit.backspace(ctrl),
+                    Key::Delete => self.edit.delete(ctrl),
+                    Key::Z if ctrl && shift => {
+                        self.edit.redo();
                     }
-                    _ if *c != '\u{7f}' => self.text.push(*c),
-                    _ => {}
+                    Key::Z if ctrl => {
+                        self.edit.undo();
+                    }
+                    Key::Y if ctrl => {
+                        self.edit.redo();
+                    }
+                    Key::A if ctrl => self.edit.select_all(),
+                    Key::C if ctrl => {
+                        if self.edit.selection().is_some() {
+                            metadata.copy_to_clipboard(self.edit.selected_text().to_string());
+                        }
+                    }
+                    Key::X if ctrl => {
+                        if self.edit.selection().is_some() {
+                            metadata.copy_to_clipboard(self.edit.selected_text().to_string());
+                            self.edit.backspace(false);
+                        }
+                    }
+                    Key::V if ctrl => {
+                        if let Some(contents) = metadata.paste_from_clipboard() {
+                            // Lines are separated by carriage returns in this library
+                            let contents = contents.replace("\r\n", "\r").replace('\n', "\r");
+                            self.edit.insert(contents.as_str());
+                        }
+                    }
+                    _ => return EventResponse::Pass,
+                }
+                EventResponse::Registered
+            }
+            Event::Character(c) => {
+                // Control characters are handled through `KeyPressed` events,
+                // except for the carriage return which breaks lines
+                if *c == '\r' || !c.is_control() {
+                    self.edit.insert(c.encode_utf8(&mut [0; 4]));
                 }
                 EventResponse::Registered
             }
@@ -190,3 +270,66 @@ impl WidgetLogic for Text {
         }
     }
 }
+
+impl Text {
+    fn character_positions(&self, metadata: &NodeMetadata) -> Vec<CharacterPosition> {
+        multiline_character_positions(
+            self.edit.text(),
+            &self.font,
+            self.size,
+            metadata.size.0,
+            metadata.size.1,
+        )
+    }
+
+    /// Moves the caret to the previous (`direction > 0`) or next
+    /// (`direction < 0`) displayed line, keeping it horizontally in place
+    fn move_vertically(&mut self, metadata: &NodeMetadata, direction: f32, select: bool) {
+        let line_gap = self.font.borrow().get_vertical_metrics().line_gap;
+        let positions = self.character_positions(metadata);
+        let caret = positions[self.edit.caret()];
+        let target_y = caret.y + self.size / 2. + direction * (line_gap + 1.) * self.size;
+        let top = positions.first().map(|position| position.y).unwrap_or(0.);
+        let bottom = positions.last().map(|position| position.y).unwrap_or(0.);
+        let index = if target_y > top + self.size {
+            0
+        } else if target_y < bottom {
+            positions.len() - 1
+        } else {
+            caret_index_at(&positions, self.size, (caret.x, target_y))
+        };
+        self.edit.set_caret(index, select);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+
+    #[test]
+    fn edit_through_interface() {
+        let font = mock_font();
+        let mut text = "hello".to_string();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, text: &mut String| {
+            build_in_window(ui, |window| {
+                TextBuilder::new(text, &font).build(loc!(), window);
+            });
+        };
+
+        build(&mut ui, &mut text);
+        // The window is centered, so the text starts at (-5, 4)
+        press_at(&mut ui, -4.9, 4.5);
+        ui.register_event(Event::Character('H'), None);
+        ui.register_event(Event::KeyPressed(Key::Delete), None);
+        ui.register_event(Event::KeyPressed(Key::LCtrl), None);
+        ui.register_event(Event::KeyPressed(Key::A), None);
+        ui.register_event(Event::KeyPressed(Key::C), None);
+        ui.register_event(Event::KeyPressed(Key::End), None);
+        ui.register_event(Event::KeyPressed(Key::V), None);
+        build(&mut ui, &mut text);
+        assert_eq!(text, "HelloHello");
+    }
+}
@@ -0,0 +1,303 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use nalgebra::{Similarity3, Translation3};
+
+use crate::core::*;
+use crate::loc;
+use crate::widget::{LabelBuilder, Wrapped};
+
+/// Builds the widgets of a tooltip in its panel
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// Adds a tooltip to any widget builder, with `.tooltip(text, font)`
+/// or `.tooltip_with(generator)` for any content
+pub trait WithTooltip<'a>: WidgetBuilder + Sized {
+    /// Displays the text in a panel when the widget is hovered long enough
+    fn tooltip(self, text: &str, font: &Rc<RefCell<dyn FontAtlas>>) -> TooltipBuilder<'a, Self> {
+        let text = text.to_string();
+        let font = font.clone();
+        self.tooltip_with(move |panel| {
+            LabelBuilder::new(text.as_str(), &font).build(loc!(), panel);
+        })
+    }
+
+    /// Builds the content of the panel with the generator, when the widget is hovered long enough
+    fn tooltip_with<F: 'a + FnMut(&NodeReference)>(self, generator: F) -> TooltipBuilder<'a, Self> {
+        TooltipBuilder::new(self, generator)
+    }
+}
+
+impl<'a, T: WidgetBuilder> WithTooltip<'a> for T {}
+
+/// Wraps a widget, displaying a tooltip once the pointer has hovered it for some time.
+///
+/// The tooltip is a panel appearing next to the point hovered, slightly in front of the widget.
+/// It is built in the popup layer, but does not react to the pointer, and disappears as soon
+/// as the widget is not hovered anymore. A click restarts the delay.
+/// `build` returns the feedback of the widget.
+pub struct TooltipBuilder<'a, T: WidgetBuilder> {
+    content: Option<T>,
+    delay: Duration,
+    color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const DELAY: Duration = Duration::from_millis(500);
+
+impl<'a, T: WidgetBuilder> TooltipBuilder<'a, T> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(content: T, generator: F) -> Self {
+        Self {
+            content: Some(content),
+            delay: DELAY,
+            color: BACKGROUND,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets how long the widget has to be hovered before the tooltip appears
+    pub fn delay(self, delay: Duration) -> Self {
+        Self { delay, ..self }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl<'a, T: WidgetBuilder> WidgetBuilder for TooltipBuilder<'a, T> {
+    type AchievedType = Tooltip;
+    type UpdateFeedback = ();
+    type BuildFeedback = T::BuildFeedback;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        _widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+    }
+
+    fn create(self) -> Self::AchievedType {
+        Tooltip {
+            content: Wrapped::new(),
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let content = self.content.take().unwrap();
+        let generator = self.generator.take().unwrap();
+        let (delay, color) = (self.delay, self.color);
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        let feedback = content.build(loc, &node_ref);
+
+        let mut panel = None;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, _| {
+            if !matches!(metadata.hover_duration(), Some(duration) if duration >= delay) {
+                return;
+            }
+            if let (Some(layer), Some((x, y))) =
+                (metadata.popup_layer(), metadata.pointer_position())
+            {
+                let corner = Translation3::new(x + OFFSET, y - OFFSET, TOOLTIP_DEPTH);
+                panel = Some((layer, metadata.global_transform() * corner));
+            }
+        });
+        if let Some((layer, transform)) = panel {
+            TooltipPanelBuilder {
+                transform,
+                color,
+                generator: Some(generator),
+            }
+            .build(loc, &layer);
+        }
+        feedback
+    }
+}
+
+/// The widget behind a `TooltipBuilder`, holding the widget the tooltip is attached to
+pub struct Tooltip {
+    content: Wrapped,
+}
+
+const PADDING: f32 = 0.2;
+/// Distance between the tooltip and the widget, towards the viewer
+const TOOLTIP_DEPTH: f32 = 0.2;
+/// Distance from the point hovered to the top left corner of the tooltip,
+/// so that the pointer does not hide it
+const OFFSET: f32 = 0.5;
+
+impl WidgetLogic for Tooltip {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        self.content.query(metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        self.content.layout(query)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        self.content.draw(metadata)
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        self.content.interaction_distance(metadata, ray)
+    }
+}
+
+/// Builds the panel of a tooltip in the popup layer
+struct TooltipPanelBuilder<'a> {
+    /// The placement of the top left corner of the panel, in the space of the interface
+    transform: Similarity3<f32>,
+    color: (f32, f32, f32, f32),
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a> WidgetBuilder for TooltipPanelBuilder<'a> {
+    type AchievedType = TooltipPanel;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.color = self.color;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        TooltipPanel {
+            color: self.color,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let transform = self.transform;
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid());
+        });
+        node_ref.set_transform(transform);
+    }
+}
+
+/// The panel of a tooltip, stacking its content from the top.
+///
+/// Its origin is its top left corner, so that it can be placed before its size is known.
+/// It never reacts to the pointer, so that it does not hide the widget it belongs to.
+pub struct TooltipPanel {
+    color: (f32, f32, f32, f32),
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl WidgetLogic for TooltipPanel {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut width: f32 = 0.;
+        let mut height = PADDING;
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (None, None),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            height += response.size.1;
+            width = width.max(response.size.0);
+            node.set_size(response.size);
+            node.set_transform(Similarity3::identity() * Translation3::new(PADDING, -height, 0.));
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+        }
+        LayoutResponse {
+            size: (width + 2. * PADDING, height + PADDING),
+            status,
+        }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        list.commands.push(quad(
+            size.0,
+            size.1,
+            None,
+            self.color,
+            metadata.transform * Translation3::new(0., -size.1, 0.),
+        ));
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        _metadata: &NodeMetadata,
+        _ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{mock_font, press_at, ray_at};
+    use crate::widget::{Button, WindowBuilder};
+
+    #[test]
+    fn hover_delay() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // Builds a frame at the given time, and returns the number of popups drawn, in the last layer
+        let build = |ui: &mut Interface, millis: u64| {
+            ui.new_frame_at(Duration::from_millis(millis));
+            WindowBuilder::new(|window| {
+                Button::new("Hover me".to_string(), &font)
+                    .tooltip("Tooltip", &font)
+                    .delay(Duration::from_secs(1))
+                    .build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            ui.draw().list.last().unwrap().list.len()
+        };
+
+        // The button goes from y = 3.6 to 5
+        assert_eq!(build(&mut ui, 0), 0);
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-4., 4.3)));
+        assert_eq!(build(&mut ui, 500), 0);
+        assert_eq!(build(&mut ui, 1200), 1);
+
+        // Moving on the button keeps the tooltip, but a click hides it for a while
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(-3., 4.)));
+        assert_eq!(build(&mut ui, 1300), 1);
+        press_at(&mut ui, -3., 4.);
+        assert_eq!(build(&mut ui, 1500), 0);
+        assert_eq!(build(&mut ui, 2400), 1);
+
+        ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(20., 20.)));
+        assert_eq!(build(&mut ui, 5000), 0);
+    }
+}
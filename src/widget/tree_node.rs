@@ -0,0 +1,178 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::core::*;
+use crate::widget::CollapsingHeader;
+
+/// Builds the children of an open tree node
+type ChildrenGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// A node of a tree view, like the ones of a scene graph.
+///
+/// A node is a leaf, unless it is given `children`: it then has an arrow to show
+/// or hide them, indented under it. Nesting nodes builds the tree, one level of
+/// indentation per level of the tree.
+///
+/// Clicking the label of a node selects it: `build` returns whether the label was clicked,
+/// and the application marks the nodes that are `selected`.
+pub struct TreeNodeBuilder<'a> {
+    label: String,
+    font: Rc<RefCell<dyn FontAtlas>>,
+    font_size: f32,
+    selected: bool,
+    default_open: bool,
+    expandable: bool,
+    selected_color: (f32, f32, f32, f32),
+    text_color: (f32, f32, f32, f32),
+    generator: Option<ChildrenGenerator<'a>>,
+}
+
+const SELECTED: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
+
+impl<'a> TreeNodeBuilder<'a> {
+    pub fn new(label: String, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            label,
+            font: font.clone(),
+            font_size: 1.,
+            selected: false,
+            default_open: false,
+            expandable: false,
+            selected_color: SELECTED,
+            text_color: TEXT,
+            generator: None,
+        }
+    }
+
+    /// Sets the generator building the children of the node
+    pub fn children<F: 'a + FnMut(&NodeReference)>(self, generator: F) -> Self {
+        Self {
+            expandable: true,
+            generator: Some(Box::new(generator)),
+            ..self
+        }
+    }
+
+    /// Highlights the node as selected
+    pub fn selected(self, selected: bool) -> Self {
+        Self { selected, ..self }
+    }
+
+    /// Sets whether the children are shown when the node is first built
+    pub fn default_open(self, default_open: bool) -> Self {
+        Self {
+            default_open,
+            ..self
+        }
+    }
+
+    pub fn font_size(self, font_size: f32) -> Self {
+        Self { font_size, ..self }
+    }
+
+    pub fn selected_color(self, selected_color: (f32, f32, f32, f32)) -> Self {
+        Self {
+            selected_color,
+            ..self
+        }
+    }
+
+    pub fn text_color(self, text_color: (f32, f32, f32, f32)) -> Self {
+        Self { text_color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for TreeNodeBuilder<'a> {
+    type AchievedType = CollapsingHeader;
+    type UpdateFeedback = bool;
+    type BuildFeedback = bool;
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.set_label(self.label, self.font_size);
+        widget.set_colors(None, self.selected_color, self.text_color);
+        widget.set_expandable(self.expandable);
+        widget.set_selection(Some(self.selected));
+        widget.start_update()
+    }
+
+    fn create(self) -> Self::AchievedType {
+        let mut widget = CollapsingHeader::new(self.label, self.font_size, &self.font);
+        widget.set_colors(None, self.selected_color, self.text_color);
+        widget.set_expandable(self.expandable);
+        widget.set_selection(Some(self.selected));
+        widget.set_open(self.default_open);
+        widget
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let generator = self.generator.take();
+        let (node_ref, clicked) = parent.query::<Self::AchievedType>(id).update(self);
+        CollapsingHeader::build_content(&node_ref, generator);
+        clicked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at};
+
+    #[test]
+    fn nested_selection() {
+        let font = mock_font();
+        let mut selected = None;
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface, selected: &mut Option<u32>| {
+            build_in_window(ui, |window| {
+                let root_selected = *selected == Some(0);
+                if TreeNodeBuilder::new("Root".to_string(), &font)
+                    .selected(root_selected)
+                    .children(|root| {
+                        for index in 1..3 {
+                            if TreeNodeBuilder::new(format!("Leaf {}", index), &font)
+                                .selected(*selected == Some(index))
+                                .build(CodeLocation(file!(), line!(), index), root)
+                            {
+                                *selected = Some(index);
+                            }
+                        }
+                    })
+                    .build(loc!(), window)
+                {
+                    *selected = Some(0);
+                }
+            });
+        };
+
+        build(&mut ui, &mut selected);
+        // The label of the root is selected, and its arrow opens it
+        press_at(&mut ui, 0., 4.3);
+        build(&mut ui, &mut selected);
+        assert_eq!(selected, Some(0));
+        press_at(&mut ui, -4.5, 4.3);
+        build(&mut ui, &mut selected);
+        assert_eq!(selected, Some(0));
+
+        // The leaves are indented under the root, 1.4 high and separated by 0.5
+        press_at(&mut ui, -3.5, 4.3 - 3.8);
+        build(&mut ui, &mut selected);
+        assert_eq!(selected, Some(2));
+
+        // Closing the root hides them
+        press_at(&mut ui, -4.5, 4.3);
+        build(&mut ui, &mut selected);
+        press_at(&mut ui, -3.5, 4.3 - 3.8);
+        build(&mut ui, &mut selected);
+        assert_eq!(selected, Some(2));
+        press_at(&mut ui, -3.5, 4.3 - 1.9);
+        build(&mut ui, &mut selected);
+        assert_eq!(selected, Some(2));
+    }
+}
@@ -0,0 +1,466 @@
+use nalgebra::{Point3, Similarity3, Translation3};
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+
+/// Builds the widgets of a row, given its index and the node to build them in
+type RowGenerator<'a> = Box<dyn 'a + FnMut(usize, &NodeReference)>;
+
+/// A list of rows of the same height, scrolled with the wheel, that only builds
+/// the rows visible in its viewport.
+///
+/// Its cost only depends on the number of visible rows, so it can display a very large
+/// number of items. The generator is called with the index of each visible row, and the
+/// node in which its widgets are built, side by side and centered vertically.
+///
+/// The nodes of the rows are recycled: when a row scrolls out of the list, its node, with
+/// the widgets built in it, is reused for the row scrolling in. Widgets keeping a state
+/// between frames should therefore get it from the application, for each item.
+///
+/// By default, the list takes all the space given by its parent. Until it has been laid out,
+/// it builds the rows fitting a list of 10 by 10, and the others appear on the next frame.
+pub struct VirtualListBuilder<'a> {
+    count: usize,
+    row_height: f32,
+    size: Option<(f32, f32)>,
+    color: (f32, f32, f32, f32),
+    generator: Option<RowGenerator<'a>>,
+}
+
+const BACKGROUND: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const THUMB: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+/// The size of a list without one, before its first layout or when its parent does not bound it
+const DEFAULT_SIZE: (f32, f32) = (10., 10.);
+
+impl<'a> VirtualListBuilder<'a> {
+    pub fn new<F: 'a + FnMut(usize, &NodeReference)>(
+        count: usize,
+        row_height: f32,
+        generator: F,
+    ) -> Self {
+        Self {
+            count,
+            row_height,
+            size: None,
+            color: BACKGROUND,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the size of the visible part of the list
+    pub fn size(self, size: (f32, f32)) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    pub fn color(self, color: (f32, f32, f32, f32)) -> Self {
+        Self { color, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for VirtualListBuilder<'a> {
+    type AchievedType = VirtualList;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.count = self.count;
+        widget.row_height = self.row_height;
+        widget.size = self.size;
+        widget.color = self.color;
+
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        VirtualList {
+            count: self.count,
+            row_height: self.row_height,
+            size: self.size,
+            color: self.color,
+            offset: 0.,
+            laid_out: false,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+
+        let (mut visible, mut slots) = (0..0, 1);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|metadata, widget| {
+            let size = match widget.size {
+                Some(size) => size,
+                None if widget.laid_out => metadata.size,
+                None => DEFAULT_SIZE,
+            };
+            visible = widget.visible_rows(size);
+            slots = widget.slots(size);
+        });
+
+        // The visible rows are consecutive and fewer than the slots,
+        // so each of them gets a different slot
+        for index in visible {
+            let slot_id = ComponentId::new_biased::<VirtualRow>(loc, (index % slots) as UniqueId);
+            let (row, _) = node_ref
+                .query::<VirtualRow>(slot_id)
+                .update(VirtualRowBuilder { index });
+            (generator)(index, &row);
+            row.apply_to_widget::<VirtualRow, _>(|_, widget| {
+                widget.content.retain(|child| child.is_valid())
+            });
+        }
+
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+    }
+}
+
+/// The widget behind a `VirtualListBuilder`, holding the visible rows
+pub struct VirtualList {
+    count: usize,
+    row_height: f32,
+    size: Option<(f32, f32)>,
+    color: (f32, f32, f32, f32),
+    /// How far the rows are scrolled, from the top
+    offset: f32,
+    /// Whether the size of the list is known, from its last layout
+    laid_out: bool,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+const SCROLLBAR_WIDTH: f32 = 0.3;
+const MIN_THUMB_LENGTH: f32 = 0.5;
+/// How far the rows are in front of the list, so that they are hit before it
+const ROW_DEPTH: f32 = 0.01;
+
+impl VirtualList {
+    fn max_offset(&self, size: (f32, f32)) -> f32 {
+        (self.count as f32 * self.row_height - size.1).max(0.)
+    }
+
+    /// The indices of the rows at least partly visible
+    fn visible_rows(&self, size: (f32, f32)) -> std::ops::Range<usize> {
+        if self.row_height <= 0. {
+            return 0..0;
+        }
+        let first = (self.offset / self.row_height).floor() as usize;
+        let last = ((self.offset + size.1) / self.row_height).ceil() as usize;
+        first.min(self.count)..last.min(self.count)
+    }
+
+    /// The number of nodes needed to display the rows, whatever the offset
+    fn slots(&self, size: (f32, f32)) -> usize {
+        if self.row_height <= 0. {
+            return 1;
+        }
+        (size.1 / self.row_height).ceil() as usize + 1
+    }
+}
+
+impl WidgetLogic for VirtualList {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = self.size.unwrap_or((
+            query.available_space.0.unwrap_or(DEFAULT_SIZE.0),
+            query.available_space.1.unwrap_or(DEFAULT_SIZE.1),
+        ));
+        self.laid_out = true;
+        self.offset = self.offset.clamp(0., self.max_offset(size));
+        let width = size.0 - SCROLLBAR_WIDTH;
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+
+        for row in &self.content {
+            let response = row.layout(&LayoutQuery {
+                available_space: (Some(width), Some(self.row_height)),
+                objectives: (Objective::Maximize, Objective::Maximize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+
+            let mut index = 0;
+            row.apply_to_widget::<VirtualRow, _>(|_, widget| index = widget.index);
+            let y = size.1 - (index + 1) as f32 * self.row_height + self.offset;
+            row.set_size((width, self.row_height));
+            row.set_transform(Similarity3::identity() * Translation3::new(0., y, ROW_DEPTH));
+        }
+
+        LayoutResponse { size, status }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let size = metadata.size;
+        let mut list = DrawList::new();
+        list.commands
+            .push(quad(size.0, size.1, None, self.color, metadata.transform));
+
+        let content = self.count as f32 * self.row_height;
+        if content > size.1 {
+            let length = (size.1 * size.1 / content).clamp(MIN_THUMB_LENGTH.min(size.1), size.1);
+            let position = self.offset / (content - size.1) * (size.1 - length);
+            list.commands.push(quad(
+                SCROLLBAR_WIDTH,
+                length,
+                None,
+                THUMB,
+                metadata.transform
+                    * Translation3::new(size.0 - SCROLLBAR_WIDTH, size.1 - position - length, 0.01),
+            ));
+        }
+
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list.clip = Some((0., 0., size.0 - SCROLLBAR_WIDTH, size.1));
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let size = metadata.size;
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(size.0, 0., 0.),
+            Point3::new(0., size.1, 0.),
+            Point3::new(size.0, size.1, 0.),
+        ];
+        let distance = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap());
+
+        // The rows are always reached, so that the rays stay up to date,
+        // but they can only be interacted with inside the list
+        let content: Vec<(f32, NodeReference)> = self
+            .content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect();
+
+        match distance {
+            Some(distance) => {
+                let inside = matches!(
+                    metadata.pointer_position(),
+                    Some((x, _)) if x <= size.0 - SCROLLBAR_WIDTH
+                );
+                let mut result = if inside { content } else { Vec::new() };
+                result.push((distance, self_node));
+                result
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseScrolled(delta) => {
+                let offset = self.offset - delta * self.row_height;
+                self.offset = offset.clamp(0., self.max_offset(metadata.size));
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
+    }
+}
+
+/// The builder of a row of a virtual list, giving the index of the row it displays
+struct VirtualRowBuilder {
+    index: usize,
+}
+
+impl WidgetBuilder for VirtualRowBuilder {
+    type AchievedType = VirtualRow;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.index = self.index;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        VirtualRow {
+            index: self.index,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(self, _loc: CodeLocation, _parent: &NodeReference) -> Self::BuildFeedback {}
+}
+
+/// A row of a virtual list, placing its content side by side, centered vertically
+pub struct VirtualRow {
+    /// The index of the row currently displayed
+    index: usize,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+const PADDING: f32 = 0.2;
+
+impl WidgetLogic for VirtualRow {
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let size = (
+            query.available_space.0.unwrap_or(0.),
+            query.available_space.1.unwrap_or(0.),
+        );
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut cursor = PADDING;
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (Some((size.0 - cursor).max(0.)), Some(size.1)),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+            node.set_size(response.size);
+            node.set_transform(
+                Similarity3::identity()
+                    * Translation3::new(cursor, (size.1 - response.size.1) / 2., 0.),
+            );
+            cursor += response.size.0 + WIDGET_SEPARATOR;
+        }
+        LayoutResponse { size, status }
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::test_support::{build_in_window, mock_font, press_at, ray_at};
+    use crate::widget::{Button, LabelBuilder};
+
+    #[test]
+    fn builds_and_recycles_visible_rows() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // Returns the rows built with their nodes, and the button clicked
+        let build = |ui: &mut Interface| {
+            let (mut rows, mut clicked) = (Vec::new(), None);
+            build_in_window(ui, |window| {
+                VirtualListBuilder::new(10_000, 1., |index, row| {
+                    rows.push((index, row.clone()));
+                    LabelBuilder::new(&index.to_string(), &font).build(loc!(), row);
+                    if Button::new("Go".to_string(), &font).build(loc!(), row) {
+                        clicked = Some(index);
+                    }
+                })
+                .size((10., 10.))
+                .build(loc!(), window);
+            });
+            (rows, clicked)
+        };
+
+        let (first, _) = build(&mut ui);
+        let indices = |rows: &[(usize, NodeReference)]| -> Vec<usize> {
+            rows.iter().map(|(index, _)| *index).collect()
+        };
+        assert_eq!(indices(&first), (0..10).collect::<Vec<_>>());
+
+        // The rows are built from the top, and are 1 high
+        press_at(&mut ui, -3., 2.5);
+        ui.register_event(
+            Event::MouseButtonReleased(MouseButton::Left),
+            Some(&ray_at(-3., 2.5)),
+        );
+        assert_eq!(build(&mut ui).1, Some(2));
+
+        // After scrolling, the rows 11 and 12 reuse the nodes of the rows 0 and 1
+        ui.register_event(Event::MouseScrolled(-3.), Some(&ray_at(0., 0.)));
+        build(&mut ui);
+        let (scrolled, _) = build(&mut ui);
+        assert_eq!(indices(&scrolled), (3..13).collect::<Vec<_>>());
+        assert!(scrolled[8].1.ptr_eq(&first[0].1));
+        assert!(scrolled[9].1.ptr_eq(&first[1].1));
+        assert!(scrolled[0].1.ptr_eq(&first[3].1));
+
+        // Scrolling stops at the last row
+        ui.register_event(Event::MouseScrolled(-100_000.), Some(&ray_at(0., 0.)));
+        build(&mut ui);
+        let (last, _) = build(&mut ui);
+        assert_eq!(indices(&last), (9_990..10_000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn rows_on_first_frame() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            let mut rows = Vec::new();
+            build_in_window(ui, |window| {
+                VirtualListBuilder::new(100, 1., |index, row| {
+                    rows.push(index);
+                    LabelBuilder::new(&index.to_string(), &font).build(loc!(), row);
+                })
+                .build(loc!(), window);
+            });
+            rows
+        };
+
+        // Without a size, the list builds rows before knowing the space given by the window,
+        // which then fits as many of them
+        assert_eq!(build(&mut ui), (0..10).collect::<Vec<_>>());
+        assert_eq!(build(&mut ui), (0..10).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,264 @@
+use nalgebra::*;
+
+use crate::core::*;
+use crate::widget::window::WIDGET_SEPARATOR;
+use crate::widget::{Align, Spacer};
+
+/// Builds the widgets of a stack in its node
+type ContentGenerator<'a> = Box<dyn 'a + FnMut(&NodeReference)>;
+
+/// The builder for a vertical stack, holding an unlimited number of widgets
+/// displayed from top to bottom. It is the vertical counterpart of `InlineBuilder`,
+/// and the content of a `WindowBuilder`.
+///
+/// Each widget is laid out with the width of the stack and the height left, and is
+/// placed horizontally according to the `align` of the stack. Widgets that do not fit
+/// are dropped. When its parent maximizes it, the stack takes all the space given,
+/// and the height left below its widgets is shared by its flexible spacers.
+pub struct VStackBuilder<'a> {
+    spacing: f32,
+    align: Align,
+    generator: Option<ContentGenerator<'a>>,
+}
+
+impl<'a> VStackBuilder<'a> {
+    pub fn new<F: 'a + FnMut(&NodeReference)>(generator: F) -> Self {
+        Self {
+            spacing: WIDGET_SEPARATOR,
+            align: Align::Left,
+            generator: Some(Box::new(generator)),
+        }
+    }
+
+    /// Sets the space between two widgets of the stack
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Sets where the widgets narrower than the stack are placed
+    pub fn align(self, align: Align) -> Self {
+        Self { align, ..self }
+    }
+}
+
+impl<'a> WidgetBuilder for VStackBuilder<'a> {
+    type AchievedType = VStack;
+    type UpdateFeedback = ();
+    type BuildFeedback = ();
+
+    fn update(
+        self,
+        _metadata: &NodeMetadata,
+        widget: &mut Self::AchievedType,
+    ) -> Self::UpdateFeedback {
+        widget.spacing = self.spacing;
+        widget.align = self.align;
+        widget.content.iter().for_each(|child| child.invalidate());
+        widget.valid_index = 0;
+    }
+
+    fn create(self) -> Self::AchievedType {
+        VStack {
+            spacing: self.spacing,
+            align: self.align,
+            content: Vec::new(),
+            valid_index: 0,
+        }
+    }
+
+    fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
+        let id = ComponentId::new::<Self::AchievedType>(loc);
+        let mut generator = self.generator.take().unwrap();
+        let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
+        (generator)(&node_ref);
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
+            widget.content.retain(|child| child.is_valid())
+        });
+    }
+}
+
+/// The widget behind a `VStackBuilder`
+pub struct VStack {
+    /// The space between two widgets
+    spacing: f32,
+    align: Align,
+    content: Vec<NodeReference>,
+    valid_index: usize,
+}
+
+impl VStack {
+    /// Places the components laid out from top to bottom, with their sizes, in a stack
+    /// of the given size, sharing the `remaining` vertical space between the flexible spacers
+    fn place(&self, sizes: &[(f32, f32)], size: (f32, f32), remaining: f32) {
+        let flexible = self
+            .content
+            .iter()
+            .take(sizes.len())
+            .filter(|node| Spacer::is_flexible(node))
+            .count();
+        let extra = remaining.max(0.) / flexible.max(1) as f32;
+        let mut cursor = size.1;
+        for (node, child_size) in self.content.iter().zip(sizes) {
+            let child_size = if Spacer::is_flexible(node) {
+                (child_size.0, child_size.1 + extra)
+            } else {
+                *child_size
+            };
+            cursor -= child_size.1;
+            let x = match self.align {
+                Align::Left => 0.,
+                Align::Center => (size.0 - child_size.0) / 2.,
+                Align::Right => size.0 - child_size.0,
+            };
+            node.set_size(child_size);
+            node.set_transform(Similarity3::identity() * Translation3::new(x, cursor, 0.));
+            cursor -= self.spacing;
+        }
+    }
+}
+
+impl WidgetLogic for VStack {
+    fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let (horizontal_space, mut vertical_space) = query.available_space;
+        let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
+        let mut sizes = Vec::with_capacity(self.content.len());
+        let mut overflow = false;
+        // For each component, compute the layout with all available space
+        // but with the `Minimize` objective. The vertical space taken is
+        // substracted from the remaining space
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (horizontal_space, vertical_space),
+                objectives: (Objective::Minimize, Objective::Minimize),
+            });
+
+            sizes.push(response.size);
+            vertical_space = vertical_space.map(|y| y - response.size.1);
+            status.0 = LayoutStatus::and(status.0, response.status.0);
+            status.1 = LayoutStatus::and(status.1, response.status.1);
+
+            if vertical_space.unwrap_or(0.) < 0. {
+                overflow = true;
+                break;
+            }
+            vertical_space = vertical_space.map(|y| y - self.spacing);
+        }
+
+        let widest = sizes.iter().fold(0f32, |width, size| width.max(size.0));
+        let width = match (query.objectives.0, horizontal_space) {
+            (Objective::Maximize, Some(available)) => available,
+            _ => widest,
+        };
+        let content_height = sizes.iter().map(|size| size.1).sum::<f32>()
+            + self.spacing * sizes.len().saturating_sub(1) as f32;
+
+        // The stack will ignore the components that cannot fit
+        if overflow {
+            let height = query.available_space.1.unwrap_or(content_height);
+            self.place(&sizes, (width, height), 0.);
+            return LayoutResponse {
+                size: (width, height),
+                status: (
+                    status.0,
+                    LayoutStatus::and(LayoutStatus::Inconsistencies, status.1),
+                ),
+            };
+        }
+
+        let (height, remaining) = match (query.objectives.1, query.available_space.1) {
+            (Objective::Maximize, Some(available)) => {
+                (available, vertical_space.map_or(0., |y| y + self.spacing))
+            }
+            _ => (content_height, 0.),
+        };
+        self.place(&sizes, (width, height), remaining);
+        LayoutResponse {
+            size: (width, height),
+            status,
+        }
+    }
+
+    fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
+    }
+
+    fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list = self.content.iter().map(|node| node.draw()).collect();
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+        _self_node: NodeReference,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loc;
+    use crate::widget::{InlineBuilder, SpacerBuilder, WindowBuilder};
+
+    /// The position of the bottom left corner of a node in its parent, and its size
+    fn placement(node: &NodeReference) -> ((f32, f32), (f32, f32)) {
+        let translation = node.get_transform().isometry.translation;
+        let mut size = (0., 0.);
+        node.apply_to_widget::<Spacer, _>(|metadata, _| size = metadata.size);
+        ((translation.x, translation.y), size)
+    }
+
+    #[test]
+    fn nested_in_row() {
+        let mut ui = Interface::new();
+        let mut spacers = Vec::new();
+        let mut stack_node = None;
+        ui.new_frame();
+        WindowBuilder::new(|window| {
+            InlineBuilder::new(|row| {
+                let id = ComponentId::new::<VStack>(loc!());
+                let (stack, _) = row
+                    .query::<VStack>(id)
+                    .update(VStackBuilder::new(|_| {}).spacing(0.5).align(Align::Right));
+                for (index, size) in [(2., 1.), (1., 1.), (1.5, 0.5)].iter().enumerate() {
+                    let id = ComponentId::new_biased::<Spacer>(loc!(), index as UniqueId);
+                    let (spacer, _) = stack
+                        .query::<Spacer>(id)
+                        .update(SpacerBuilder::fixed(*size));
+                    spacers.push(spacer);
+                }
+                stack_node = Some(stack);
+                SpacerBuilder::fixed((1., 4.)).build(loc!(), row);
+            })
+            .build(loc!(), window);
+        })
+        .size((10., 10.))
+        .build(loc!(), &ui.root);
+        ui.end_frame();
+        let response = ui.generate_layout();
+        assert_eq!(response.status, (LayoutStatus::Ok, LayoutStatus::Ok));
+
+        // In a row, the stack is only as large as its widest widget and as high as its content
+        let mut size = (0., 0.);
+        stack_node
+            .unwrap()
+            .apply_to_widget::<VStack, _>(|metadata, _| size = metadata.size);
+        assert_eq!(size, (2., 3.5));
+        assert_eq!(placement(&spacers[0]), ((0., 2.5), (2., 1.)));
+        assert_eq!(placement(&spacers[1]), ((1., 1.), (1., 1.)));
+        assert_eq!(placement(&spacers[2]), ((0.5, 0.), (1.5, 0.5)));
+    }
+}
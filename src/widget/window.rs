@@ -1,17 +1,43 @@
+use std::cell::RefCell;
+use std::f32::consts::{FRAC_PI_2, FRAC_PI_4};
+use std::rc::Rc;
+
 use nalgebra::*;
 
 use crate::core::*;
+use crate::widget::{ScrollAreaBuilder, VStackBuilder};
 
 /// The builder for a window, that can hold an unlimited number of widgets.
-/// Display them in a finite space from top to bottom.
+/// Display them in a finite space from top to bottom, in a `VStackBuilder` filling the window.
+///
+/// Content that does not fit is dropped, unless the window is `scrollable`.
+///
+/// A window with a `title_bar` can be moved by dragging the bar, and a `resizable`
+/// one can be resized with the handles on its bottom corners. In both cases, the
+/// transform and the size given to the builder are only the initial ones, and
+/// `build` returns the current ones so that they can be saved.
+///
+/// The title bar can also hold buttons to close and to collapse the window.
+/// Closing only reports the click: the window disappears once it is not built anymore.
 pub struct WindowBuilder<'a> {
     title: String,
     size: (f32, f32),
     transform: Similarity3<f32>,
+    scrollable: bool,
+    title_font: Option<Rc<RefCell<dyn FontAtlas>>>,
+    resizable: bool,
+    closable: bool,
+    collapsible: bool,
+    spacing: f32,
+    popup_owner: Option<NodeReference>,
     generator: Option<Box<dyn 'a + FnMut(&NodeReference)>>,
 }
 
 const BACKGROUND: (f32, f32, f32, f32) = (0.106, 0.125, 0.173, 1.);
+const TITLE_BAR: (f32, f32, f32, f32) = (0.161, 0.176, 0.216, 1.);
+const HANDLE: (f32, f32, f32, f32) = (0.231, 0.294, 0.451, 1.);
+const CLOSE: (f32, f32, f32, f32) = (0.773, 0.251, 0.267, 1.);
+const TEXT: (f32, f32, f32, f32) = (1., 1., 1., 1.);
 
 impl<'a> WindowBuilder<'a> {
     pub fn new<F: 'a + FnMut(&NodeReference)>(generator: F) -> Self {
@@ -19,6 +45,13 @@ impl<'a> WindowBuilder<'a> {
             title: "".to_string(),
             size: (5., 5.),
             transform: Similarity3::identity(),
+            scrollable: false,
+            title_font: None,
+            resizable: false,
+            closable: false,
+            collapsible: false,
+            spacing: WIDGET_SEPARATOR,
+            popup_owner: None,
             generator: Some(Box::new(generator)),
         }
     }
@@ -34,20 +67,99 @@ impl<'a> WindowBuilder<'a> {
     pub fn transform(self, transform: Similarity3<f32>) -> Self {
         Self { transform, ..self }
     }
+
+    /// Places the content in a `ScrollAreaBuilder` filling the window,
+    /// so that it can be scrolled when it does not fit
+    pub fn scrollable(self) -> Self {
+        Self {
+            scrollable: true,
+            ..self
+        }
+    }
+
+    /// Displays the title in a bar at the top of the window,
+    /// that can be dragged to move the window in its plane
+    pub fn title_bar(self, font: &Rc<RefCell<dyn FontAtlas>>) -> Self {
+        Self {
+            title_font: Some(font.clone()),
+            ..self
+        }
+    }
+
+    /// Adds handles on the bottom corners of the window to resize it
+    pub fn resizable(self) -> Self {
+        Self {
+            resizable: true,
+            ..self
+        }
+    }
+
+    /// Adds a button to close the window in its title bar
+    pub fn closable(self) -> Self {
+        Self {
+            closable: true,
+            ..self
+        }
+    }
+
+    /// Sets the space between two widgets of the window
+    pub fn spacing(self, spacing: f32) -> Self {
+        Self { spacing, ..self }
+    }
+
+    /// Adds a button to collapse the window in its title bar.
+    /// The content of a collapsed window is not built
+    pub fn collapsible(self) -> Self {
+        Self {
+            collapsible: true,
+            ..self
+        }
+    }
+
+    /// Marks the window, built in the popup layer, as the popup opened by the given widget
+    /// (see `NodeReference::set_popup_owner`)
+    pub fn popup_of(self, owner: &NodeReference) -> Self {
+        Self {
+            popup_owner: Some(owner.clone()),
+            ..self
+        }
+    }
+}
+
+/// The placement of a window after it was built
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WindowFeedback {
+    /// The transform of the window, as given to `WindowBuilder::transform`
+    pub transform: Similarity3<f32>,
+    pub size: (f32, f32),
+    /// Whether the close button was clicked since the last build
+    pub closed: bool,
+    pub collapsed: bool,
 }
 
 impl<'a> WidgetBuilder for WindowBuilder<'a> {
     type AchievedType = Window;
     type UpdateFeedback = ();
-    type BuildFeedback = ();
+    type BuildFeedback = WindowFeedback;
 
     fn update(
         self,
         _metadata: &NodeMetadata,
         widget: &mut Self::AchievedType,
     ) -> Self::UpdateFeedback {
-        widget.size = self.size;
+        // Once the user can move or resize the window, it keeps its own placement
+        if self.title_font.is_none() {
+            widget.transform = self.transform;
+        }
+        if !self.resizable {
+            widget.size = self.size;
+        }
         widget.title = self.title;
+        widget.title_font = self.title_font;
+        widget.resizable = self.resizable;
+        widget.closable = self.closable;
+        widget.collapsible = self.collapsible;
+        widget.collapsed &= self.collapsible;
 
         widget.content.iter().for_each(|child| child.invalidate());
         widget.valid_index = 0;
@@ -57,6 +169,14 @@ impl<'a> WidgetBuilder for WindowBuilder<'a> {
         Window {
             title: self.title,
             size: self.size,
+            transform: self.transform,
+            title_font: self.title_font,
+            resizable: self.resizable,
+            closable: self.closable,
+            collapsible: self.collapsible,
+            collapsed: false,
+            closed: false,
+            drag: None,
             content: Vec::new(),
             valid_index: 0,
         }
@@ -65,61 +185,209 @@ impl<'a> WidgetBuilder for WindowBuilder<'a> {
     fn build(mut self, loc: CodeLocation, parent: &NodeReference) -> Self::BuildFeedback {
         let id = ComponentId::new::<Self::AchievedType>(loc);
         let mut generator = self.generator.take().unwrap();
-        let transform = self.transform;
+        let (scrollable, spacing) = (self.scrollable, self.spacing);
+        let popup_owner = self.popup_owner.take();
         let (node_ref, _) = parent.query::<Self::AchievedType>(id).update(self);
-        node_ref.set_transform(transform);
-        (generator)(&node_ref);
+        // Set before building the content, so that it knows it is in a popup
+        if let Some(owner) = popup_owner {
+            node_ref.set_popup_owner(&owner);
+        }
+        let mut collapsed = false;
+        node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| collapsed = widget.collapsed);
+
+        // The transform is replaced after building the content, so that it keeps
+        // the one of the last layout meanwhile (see `NodeMetadata::global_transform`).
+        // The content of a collapsed window is kept as it is until it is expanded
+        if !collapsed {
+            if scrollable {
+                ScrollAreaBuilder::new(|area| (generator)(area))
+                    .spacing(spacing)
+                    .build(loc, &node_ref);
+            } else {
+                VStackBuilder::new(|stack| (generator)(stack))
+                    .spacing(spacing)
+                    .build(loc, &node_ref);
+            }
+        }
+        let mut feedback = None;
         node_ref.apply_to_widget::<Self::AchievedType, _>(|_, widget| {
-            widget.content.retain(|child| child.is_valid())
+            if !collapsed {
+                widget.content.retain(|child| child.is_valid());
+            }
+            feedback = Some(WindowFeedback {
+                transform: widget.transform,
+                size: widget.size,
+                closed: widget.closed,
+                collapsed: widget.collapsed,
+            });
+            widget.closed = false;
         });
+        let feedback = feedback.unwrap();
+        node_ref.set_transform(feedback.transform);
+        feedback
     }
 }
 
+/// The parts of a window that react to the pointer
+#[derive(Copy, Clone, PartialEq)]
+enum Part {
+    Background,
+    TitleBar,
+    CloseButton,
+    CollapseButton,
+    LeftHandle,
+    RightHandle,
+}
+
+/// What is being dragged in a window, with the point of the window
+/// under the pointer, in its local space
+#[derive(Copy, Clone)]
+enum Drag {
+    Move((f32, f32)),
+    ResizeLeft((f32, f32)),
+    ResizeRight((f32, f32)),
+}
+
 /// Internal window structure
 pub struct Window {
     title: String,
     size: (f32, f32),
+    transform: Similarity3<f32>,
+    title_font: Option<Rc<RefCell<dyn FontAtlas>>>,
+    resizable: bool,
+    closable: bool,
+    collapsible: bool,
+    collapsed: bool,
+    closed: bool,
+    drag: Option<Drag>,
     content: Vec<NodeReference>,
     valid_index: usize,
 }
 
-const WIDGET_SEPARATOR: f32 = 0.5;
+pub(crate) const WIDGET_SEPARATOR: f32 = 0.5;
+const PADDING: f32 = 0.2;
+const TITLE_SIZE: f32 = 1.;
+const HANDLE_SIZE: f32 = 0.5;
+const MIN_SIZE: (f32, f32) = (2., 2.);
+/// Added to the distance of the background, so that the content is always in front
+const DEPTH_BIAS: f32 = 1e-3;
+
+impl Window {
+    fn title_bar_height(&self) -> f32 {
+        if self.title_font.is_some() {
+            TITLE_SIZE + 2. * PADDING
+        } else {
+            0.
+        }
+    }
+
+    /// Moves the origin of the window by `shift` in its plane, and changes its size
+    fn reshape(&mut self, metadata: &mut NodeMetadata, shift: (f32, f32), size: (f32, f32)) {
+        let resize = (size.0 - self.size.0, size.1 - self.size.1);
+        metadata.transform *= Translation3::new(shift.0, shift.1, 0.);
+        // The `WindowHandler` centers windows on their transform
+        self.transform *= Translation3::new(shift.0 + resize.0 / 2., shift.1 + resize.1 / 2., 0.);
+        self.size = size;
+    }
+
+    /// Follows the pointer, at `position` in the local space, during a drag
+    fn follow_pointer(&mut self, metadata: &mut NodeMetadata, drag: Drag, position: (f32, f32)) {
+        let (width, height) = self.size;
+        match drag {
+            Drag::Move((x, y)) => {
+                self.reshape(metadata, (position.0 - x, position.1 - y), self.size);
+            }
+            Drag::ResizeLeft((x, y)) => {
+                let new_width = (width - (position.0 - x)).max(MIN_SIZE.0);
+                let new_height = (height - (position.1 - y)).max(MIN_SIZE.1);
+                self.reshape(
+                    metadata,
+                    (width - new_width, height - new_height),
+                    (new_width, new_height),
+                );
+            }
+            Drag::ResizeRight((x, y)) => {
+                let new_width = (width + (position.0 - x)).max(MIN_SIZE.0);
+                let new_height = (height - (position.1 - y)).max(MIN_SIZE.1);
+                self.reshape(metadata, (0., height - new_height), (new_width, new_height));
+                // The grabbed corner moved away from the origin
+                self.drag = Some(Drag::ResizeRight((x + new_width - width, y)));
+            }
+        }
+    }
+
+    /// Returns the position of the buttons of the title bar, from the left of the window
+    fn buttons(&self) -> (Option<f32>, Option<f32>) {
+        let mut x = self.size.0;
+        let mut next = |present: bool| {
+            if present && self.title_font.is_some() {
+                x -= TITLE_SIZE + PADDING;
+                Some(x)
+            } else {
+                None
+            }
+        };
+        let close = next(self.closable);
+        let collapse = next(self.collapsible);
+        (close, collapse)
+    }
+
+    /// Returns the part of the window at the given position in the local space
+    fn part_at(&self, (x, y): (f32, f32)) -> Option<Part> {
+        let (width, height) = self.size;
+        let bar_height = self.title_bar_height();
+        let bottom = if self.collapsed {
+            height - bar_height
+        } else {
+            0.
+        };
+        let on_button = |button: Option<f32>| matches!(button, Some(left) if x >= left && x <= left + TITLE_SIZE);
+        let (close, collapse) = self.buttons();
+        if x < 0. || y < bottom || x > width || y > height {
+            None
+        } else if y >= height - bar_height {
+            if on_button(close) {
+                Some(Part::CloseButton)
+            } else if on_button(collapse) {
+                Some(Part::CollapseButton)
+            } else {
+                Some(Part::TitleBar)
+            }
+        } else if self.resizable && y <= HANDLE_SIZE && x <= HANDLE_SIZE {
+            Some(Part::LeftHandle)
+        } else if self.resizable && y <= HANDLE_SIZE && x >= width - HANDLE_SIZE {
+            Some(Part::RightHandle)
+        } else {
+            Some(Part::Background)
+        }
+    }
+}
 
 impl WidgetLogic for Window {
     fn layout(&mut self, _query: &LayoutQuery) -> LayoutResponse {
-        let (horizontal_space, mut vertical_space) = self.size;
+        let horizontal_space = self.size.0;
+        let vertical_space = self.size.1 - self.title_bar_height();
         let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
-        // For each component, compute the layout with all available space
-        // but with the `Minimize` objective. The vertical space taken is
-        // substracted from the remaining space
-        for node in &mut self.content {
-            let response = {
-                node.layout(&LayoutQuery {
-                    available_space: (Some(horizontal_space), Some(vertical_space)),
-                    objectives: (Objective::Minimize, Objective::Minimize),
-                })
+        // The size stays the same when collapsed, so that the title bar does not move
+        if self.collapsed {
+            return LayoutResponse {
+                size: self.size,
+                status,
             };
-
+        }
+        // The content is a single stack or scroll area, filling the window below the title bar
+        for node in &self.content {
+            let response = node.layout(&LayoutQuery {
+                available_space: (Some(horizontal_space), Some(vertical_space)),
+                objectives: (Objective::Maximize, Objective::Maximize),
+            });
             node.set_size(response.size);
             node.set_transform(
                 Similarity3::identity()
                     * Translation3::new(0., vertical_space - response.size.1, 0.),
             );
-
-            vertical_space -= response.size.1 + WIDGET_SEPARATOR;
             status.0 = LayoutStatus::and(status.0, response.status.0);
             status.1 = LayoutStatus::and(status.1, response.status.1);
-
-            if vertical_space < 0. {
-                // The window will ignore the components that cannot fit
-                return LayoutResponse {
-                    size: self.size,
-                    status: (
-                        status.0,
-                        LayoutStatus::and(LayoutStatus::Inconsistencies, status.1),
-                    ),
-                };
-            }
         }
 
         LayoutResponse {
@@ -129,43 +397,102 @@ impl WidgetLogic for Window {
     }
 
     fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
-        let child = self
-            .content
-            .iter()
-            .enumerate()
-            .find(|(_, other)| other.has_id(id))
-            .map(|(index, other)| (index, other.clone()));
-        let (index, result) = match child {
-            Some((index, node_ref)) => (index, WidgetQueryResult::Initialized(node_ref)),
-            None => {
-                let node_ref = Node::new_reference(id, &metadata.ui_properties);
-                self.content.push(node_ref.clone());
-                (
-                    self.content.len() - 1,
-                    WidgetQueryResult::Uninitialized(node_ref),
-                )
-            }
-        };
-        self.content.swap(self.valid_index, index);
-        self.valid_index += 1;
-        result
+        query_content(&mut self.content, &mut self.valid_index, metadata, id)
     }
 
     fn draw(&self, metadata: &NodeMetadata) -> DrawList {
         let mut list = DrawList::new();
-        self.content.iter().for_each(|node| {
-            list.list.push(node.draw());
-        });
-        list.list_transform =
-            (metadata.transform * Translation3::new(0., 0., 0.01)).to_homogeneous();
+        let bar_height = self.title_bar_height();
+
+        if !self.collapsed {
+            self.content.iter().for_each(|node| {
+                list.list.push(node.draw());
+            });
+            list.list_transform =
+                (metadata.transform * Translation3::new(0., 0., 0.01)).to_homogeneous();
+
+            list.commands.push(quad(
+                self.size.0,
+                self.size.1,
+                None,
+                BACKGROUND,
+                metadata.transform,
+            ));
+
+            if self.resizable {
+                for x in &[0., self.size.0 - HANDLE_SIZE] {
+                    list.commands.push(quad(
+                        HANDLE_SIZE,
+                        HANDLE_SIZE,
+                        None,
+                        HANDLE,
+                        metadata.transform * Translation3::new(*x, 0., 0.02),
+                    ));
+                }
+            }
+        }
+
+        if let Some(font) = &self.title_font {
+            let bar_transform =
+                metadata.transform * Translation3::new(0., self.size.1 - bar_height, 0.01);
+            list.commands.push(quad(
+                self.size.0,
+                bar_height,
+                None,
+                TITLE_BAR,
+                bar_transform,
+            ));
+            list.commands.push(draw_text(
+                self.title.as_str(),
+                font,
+                TITLE_SIZE,
+                TEXT,
+                (bar_transform * Translation3::new(PADDING, PADDING, 0.01)).to_homogeneous(),
+            ));
 
-        list.commands.push(quad(
-            self.size.0,
-            self.size.1,
-            None,
-            BACKGROUND,
-            metadata.transform,
-        ));
+            let (close, collapse) = self.buttons();
+            if let Some(x) = close {
+                // A cross made of two thin bars
+                let center =
+                    bar_transform * Translation3::new(x + TITLE_SIZE / 2., bar_height / 2., 0.01);
+                for angle in &[FRAC_PI_4, -FRAC_PI_4] {
+                    list.commands.push(quad(
+                        TITLE_SIZE,
+                        TITLE_SIZE / 6.,
+                        None,
+                        CLOSE,
+                        center
+                            * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), *angle)
+                            * Translation3::new(-TITLE_SIZE / 2., -TITLE_SIZE / 12., 0.),
+                    ));
+                }
+            }
+            if let Some(x) = collapse {
+                // A triangle pointing down when expanded, and to the right when collapsed
+                let angle = if self.collapsed { FRAC_PI_2 } else { 0. };
+                let center = bar_transform
+                    * Translation3::new(x + TITLE_SIZE / 2., bar_height / 2., 0.01)
+                    * UnitQuaternion::from_axis_angle(&Vector3::z_axis(), angle);
+                let half = TITLE_SIZE / 3.;
+                let mut uniforms = Uniforms::new();
+                uniforms.model_matrix = center.to_homogeneous();
+                let vertex = |x: f32, y: f32| Vertex {
+                    position: Vector3::new(x, y, 0.),
+                    color: TEXT,
+                    tex_uv: Vector2::new(0., 0.),
+                };
+                list.commands.push(DrawCommand {
+                    vertex_buffer: vec![
+                        vertex(-half, half / 2.),
+                        vertex(half, half / 2.),
+                        vertex(0., -half / 2.),
+                    ],
+                    index_buffer: vec![0, 1, 2],
+                    draw_mode: DrawMode::Triangles,
+                    uniforms,
+                });
+            }
+        }
 
         list
     }
@@ -174,15 +501,94 @@ impl WidgetLogic for Window {
         &self,
         metadata: &NodeMetadata,
         ray: &Ray,
-        _self_node: NodeReference,
+        self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
-        self.content
-            .iter()
-            .map(|content| content.interaction_distance(&new_ray, content.clone()))
-            .flatten()
-            .collect()
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        let content: Vec<(f32, NodeReference)> = if self.collapsed {
+            Vec::new()
+        } else {
+            self.content
+                .iter()
+                .flat_map(|content| content.interaction_distance(&new_ray, content.clone()))
+                .collect()
+        };
+
+        let points = [
+            Point3::new(0., 0., 0.),
+            Point3::new(self.size.0, 0., 0.),
+            Point3::new(0., self.size.1, 0.),
+            Point3::new(self.size.0, self.size.1, 0.),
+        ];
+        let distance = [
+            [points[0], points[1], points[2]],
+            [points[1], points[2], points[3]],
+        ]
+        .iter()
+        .filter_map(|triangle| intersection(&new_ray, triangle))
+        .min_by(|d1, d2| d1.partial_cmp(d2).unwrap());
+        let part = metadata
+            .pointer_position()
+            .and_then(|position| self.part_at(position));
+
+        // The handles are in front of the content, and the background behind it,
+        // so that the window hides whatever is behind it
+        match (distance, part) {
+            (Some(distance), Some(Part::LeftHandle))
+            | (Some(distance), Some(Part::RightHandle)) => {
+                vec![(distance, self_node)]
+            }
+            (Some(distance), Some(_)) => {
+                let mut result = content;
+                result.push((distance + DEPTH_BIAS, self_node));
+                result
+            }
+            _ => content,
+        }
+    }
+
+    fn send_event(&mut self, metadata: &mut NodeMetadata, event: &Event) -> EventResponse {
+        match event {
+            Event::MouseButtonPressed(MouseButton::Left)
+            | Event::MouseButtonPressed(MouseButton::Touch) => {
+                let position = match metadata.pointer_position() {
+                    Some(position) => position,
+                    None => return EventResponse::Pass,
+                };
+                self.drag = None;
+                match self.part_at(position) {
+                    Some(Part::TitleBar) => self.drag = Some(Drag::Move(position)),
+                    Some(Part::LeftHandle) => self.drag = Some(Drag::ResizeLeft(position)),
+                    Some(Part::RightHandle) => self.drag = Some(Drag::ResizeRight(position)),
+                    Some(Part::CloseButton) => self.closed = true,
+                    Some(Part::CollapseButton) => self.collapsed = !self.collapsed,
+                    Some(Part::Background) => (),
+                    None => return EventResponse::Pass,
+                }
+                if self.drag.is_some() {
+                    metadata.capture_pointer();
+                }
+                EventResponse::Registered
+            }
+            Event::MouseMoved(_, _) if metadata.has_pointer_capture() => {
+                if let (Some(drag), Some(position)) = (self.drag, metadata.pointer_position()) {
+                    self.follow_pointer(metadata, drag, position);
+                }
+                EventResponse::Registered
+            }
+            Event::MouseButtonReleased(MouseButton::Left)
+            | Event::MouseButtonReleased(MouseButton::Touch)
+                if metadata.has_pointer_capture() =>
+            {
+                self.drag = None;
+                metadata.release_pointer();
+                EventResponse::Registered
+            }
+            _ => EventResponse::Pass,
+        }
     }
 }
 
@@ -286,4 +692,141 @@ mod tests {
             assert_eq!(response.status.1, LayoutStatus::Inconsistencies);
         }
     }
+
+    #[test]
+    fn move_and_resize() {
+        use crate::test_support::{mock_font, press_at, ray_at};
+
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            ui.new_frame();
+            let feedback = WindowBuilder::new(|_| {})
+                .title("Title".to_string())
+                .title_bar(&font)
+                .resizable()
+                .size((10., 10.))
+                .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            feedback
+        };
+        let drag = |ui: &mut Interface, from: (f32, f32), to: (f32, f32)| {
+            press_at(ui, from.0, from.1);
+            ui.register_event(Event::MouseMoved(0., 0.), Some(&ray_at(to.0, to.1)));
+            ui.register_event(
+                Event::MouseButtonReleased(MouseButton::Left),
+                Some(&ray_at(to.0, to.1)),
+            );
+        };
+
+        build(&mut ui);
+        // The title bar goes from y = 3.6 to 5
+        drag(&mut ui, (0., 4.3), (2., 5.3));
+        let feedback = build(&mut ui);
+        assert_eq!(
+            feedback.transform.isometry.translation.vector,
+            Vector3::new(2., 1., 0.)
+        );
+        assert_eq!(feedback.size, (10., 10.));
+
+        // The window now spans from x = -3 to 7 and from y = -4 to 6.
+        // The top left corner stays in place while resizing from the bottom right one
+        drag(&mut ui, (6.8, -3.8), (7.8, -4.8));
+        let feedback = build(&mut ui);
+        assert_eq!(feedback.size, (11., 11.));
+        assert_eq!(
+            feedback.transform.isometry.translation.vector,
+            Vector3::new(2.5, 0.5, 0.)
+        );
+
+        // Dragging the content does nothing
+        drag(&mut ui, (0., 0.), (3., 3.));
+        assert_eq!(build(&mut ui), feedback);
+    }
+
+    #[test]
+    fn close_and_collapse() {
+        use crate::test_support::{mock_font, press_at};
+
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            ui.new_frame();
+            let mut pressed = false;
+            let feedback = WindowBuilder::new(|window| {
+                pressed = Button::new("Button".to_string(), &font).build(loc!(), window);
+            })
+            .title("Title".to_string())
+            .title_bar(&font)
+            .closable()
+            .collapsible()
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            (feedback, pressed)
+        };
+
+        build(&mut ui);
+        // The buttons are 1 wide, separated by 0.2, on the right of the title bar
+        press_at(&mut ui, 4.3, 4.3);
+        let (feedback, _) = build(&mut ui);
+        assert!(feedback.closed);
+        assert!(!build(&mut ui).0.closed);
+
+        // The button is below the title bar, from y = 2.1 to 3.5
+        press_at(&mut ui, 3.1, 4.3);
+        let (feedback, _) = build(&mut ui);
+        assert!(feedback.collapsed);
+        assert_eq!(feedback.size, (10., 10.));
+        press_at(&mut ui, -4., 2.8);
+        assert!(!build(&mut ui).1);
+
+        press_at(&mut ui, 3.1, 4.3);
+        assert!(!build(&mut ui).0.collapsed);
+        press_at(&mut ui, -4., 2.8);
+        assert!(build(&mut ui).1);
+    }
+
+    #[test]
+    fn rotated_window() {
+        use crate::test_support::mock_font;
+
+        let font = mock_font();
+        let mut ui = Interface::new();
+        let build = |ui: &mut Interface| {
+            ui.new_frame();
+            let mut pressed = false;
+            WindowBuilder::new(|window| {
+                pressed = Button::new("Button".to_string(), &font).build(loc!(), window);
+            })
+            // The window faces the positive x axis, its left side towards the positive z axis
+            .transform(Similarity3::new(
+                Vector3::new(2., 0., 0.),
+                Vector3::y() * FRAC_PI_2,
+                1.,
+            ))
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            pressed
+        };
+
+        build(&mut ui);
+        // The button is at the top left of the window, (-4.5, 4.3) in its plane
+        ui.register_event(
+            Event::MouseButtonPressed(MouseButton::Left),
+            Some(&Ray::new(-Vector3::x(), Point3::new(10., 4.3, 4.5))),
+        );
+        assert!(build(&mut ui));
+
+        // Next to the button, in the plane of the window, is its background
+        ui.register_event(
+            Event::MouseButtonPressed(MouseButton::Left),
+            Some(&Ray::new(-Vector3::x(), Point3::new(10., 2., 4.5))),
+        );
+        assert!(!build(&mut ui));
+    }
 }
@@ -2,15 +2,58 @@ use nalgebra::*;
 
 use crate::core::*;
 
-/// A handler able to place windows in space
+/// A handler able to place windows in space.
+///
+/// Windows are kept from back to front. Each one is lifted along its z axis a bit more
+/// than the one behind it, so that windows in the same plane are stacked in this order,
+/// both when drawn and when interacted with. A window goes to the front when it is clicked.
 pub struct WindowHandler {
     windows: Vec<NodeReference>,
+    /// The layer of the window at the back, to stack the windows over the ones of another handler
+    first_layer: usize,
 }
 
+/// Distance between two windows stacked on top of each other, along their z axis
+const LAYER_SPACING: f32 = 0.1;
+
 impl WindowHandler {
     pub fn new() -> Self {
         WindowHandler {
             windows: Vec::new(),
+            first_layer: 0,
+        }
+    }
+
+    /// Invalidates all windows, at the beginning of a frame
+    pub fn invalidate_content(&mut self) {
+        self.windows.iter().for_each(|window| window.invalidate());
+    }
+
+    /// Removes the windows that were not rebuilt, at the end of a frame
+    pub fn remove_invalid(&mut self) {
+        self.windows.retain(|window| window.is_valid());
+    }
+
+    /// Whether the handler holds no window
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Returns the number of windows held by the handler
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    /// Stacks the windows over the given number of layers, like the windows of another handler
+    pub fn stack_over(&mut self, layers: usize) {
+        self.first_layer = layers;
+    }
+
+    /// Puts a window in front of all the others
+    pub fn bring_to_front(&mut self, window: &NodeReference) {
+        if let Some(index) = self.windows.iter().position(|other| other.ptr_eq(window)) {
+            let window = self.windows.remove(index);
+            self.windows.push(window);
         }
     }
 }
@@ -62,7 +105,7 @@ impl WidgetLogic for WindowHandler {
         // We don't need to modify the size and position of each
         // window, as they manage this themselves
         let mut status = (LayoutStatus::Ok, LayoutStatus::Ok);
-        for node in &self.windows {
+        for (index, node) in self.windows.iter().enumerate() {
             let response = node.layout(&LayoutQuery {
                 available_space: (None, None),
                 objectives: (Objective::Minimize, Objective::Minimize),
@@ -70,9 +113,11 @@ impl WidgetLogic for WindowHandler {
             status.0 = LayoutStatus::and(status.0, response.status.0);
             status.1 = LayoutStatus::and(status.1, response.status.1);
 
+            // Centered, and lifted over the windows behind it
+            let lift = (self.first_layer + index) as f32 * LAYER_SPACING;
             node.set_transform(
                 node.get_transform()
-                    * Translation3::new(-response.size.0 / 2., -response.size.1 / 2., 0.),
+                    * Translation3::new(-response.size.0 / 2., -response.size.1 / 2., lift),
             );
         }
         // The response is irrelevant here
@@ -97,11 +142,133 @@ impl WidgetLogic for WindowHandler {
         _self_node: NodeReference,
     ) -> Vec<(f32, NodeReference)> {
         let transformation = metadata.transform.inverse();
-        let new_ray = Ray::new(ray.direction(), transformation * ray.origin());
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
         self.windows
             .iter()
-            .map(|window| window.interaction_distance(&new_ray, window.clone()))
-            .flatten()
+            .flat_map(|window| window.interaction_distance(&new_ray, window.clone()))
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::core::*;
+    use crate::loc;
+    use crate::test_support::{mock_font, press_at};
+    use crate::widget::*;
+
+    use nalgebra::*;
+
+    #[test]
+    fn bring_to_front() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // Two windows in the same plane, the second one being two units to the right
+        let build = |ui: &mut Interface, with_second: bool| {
+            ui.new_frame();
+            let mut pressed = (false, false);
+            WindowBuilder::new(|window| {
+                pressed.0 = Button::new("Button A".to_string(), &font).build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            if with_second {
+                WindowBuilder::new(|window| {
+                    pressed.1 = Button::new("Button B".to_string(), &font).build(loc!(), window);
+                })
+                .size((10., 10.))
+                .transform(Similarity3::new(
+                    Vector3::new(2., 0., 0.),
+                    Vector3::zeros(),
+                    1.,
+                ))
+                .build(loc!(), &ui.root);
+            }
+            ui.end_frame();
+            ui.generate_layout();
+            pressed
+        };
+
+        build(&mut ui, true);
+        // Both buttons are under this point, and the last window created is in front
+        press_at(&mut ui, -1., 4.3);
+        assert_eq!(build(&mut ui, true), (false, true));
+
+        // Clicking the background of the first window brings it to the front
+        press_at(&mut ui, -4.5, 0.);
+        assert_eq!(build(&mut ui, true), (false, false));
+        press_at(&mut ui, -1., 4.3);
+        assert_eq!(build(&mut ui, true), (true, false));
+
+        press_at(&mut ui, 6., 0.);
+        build(&mut ui, true);
+        press_at(&mut ui, -1., 4.3);
+        assert_eq!(build(&mut ui, true), (false, true));
+
+        // A window that is not built anymore is removed
+        build(&mut ui, false);
+        press_at(&mut ui, -1., 4.3);
+        assert_eq!(build(&mut ui, false), (true, false));
+    }
+
+    #[test]
+    fn window_behind_in_depth() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        // The second window is stacked over the first one, but further from the viewer
+        let build = |ui: &mut Interface| {
+            ui.new_frame();
+            let mut pressed = (false, false);
+            WindowBuilder::new(|window| {
+                pressed.0 = Button::new("Button A".to_string(), &font).build(loc!(), window);
+            })
+            .size((10., 10.))
+            .build(loc!(), &ui.root);
+            WindowBuilder::new(|window| {
+                pressed.1 = Button::new("Button B".to_string(), &font).build(loc!(), window);
+            })
+            .size((10., 10.))
+            .transform(Similarity3::new(
+                Vector3::new(0., 0., -1.),
+                Vector3::zeros(),
+                1.,
+            ))
+            .build(loc!(), &ui.root);
+            ui.end_frame();
+            ui.generate_layout();
+            pressed
+        };
+
+        // What is nearer is drawn over what is further, and clicked first
+        build(&mut ui);
+        press_at(&mut ui, -3., 4.3);
+        assert_eq!(build(&mut ui), (true, false));
+    }
+
+    #[test]
+    fn modals_over_windows() {
+        let font = mock_font();
+        let mut ui = Interface::new();
+        ui.new_frame();
+        for index in 0..2 {
+            WindowBuilder::new(|_| ())
+                .size((10., 10.))
+                .build(CodeLocation(file!(), line!(), index), &ui.root);
+        }
+        ModalBuilder::confirm("Sure?", &font)
+            .size((10., 6.))
+            .build(loc!(), &ui.modals);
+        ui.end_frame();
+        ui.generate_layout();
+
+        // The windows, in the same plane, are stacked by their handlers, the modals last
+        let list = ui.draw();
+        let depth = |list: &DrawList| list.list_transform[(2, 3)];
+        let (back, front) = (depth(&list.list[0].list[0]), depth(&list.list[0].list[1]));
+        let modal = depth(&list.list[1].list[0]);
+        assert!(back < front && front < modal);
+    }
+}
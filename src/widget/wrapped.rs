@@ -0,0 +1,69 @@
+use nalgebra::Similarity3;
+
+use crate::core::*;
+
+/// The widget held by a wrapper, which adds a behaviour to it without changing
+/// its placement, like a `ContextMenu` or a `Tooltip`.
+///
+/// The wrapper forwards its query, layout, drawing and hit-testing to these methods,
+/// and builds the widget with the location given to its own `build`.
+pub struct Wrapped {
+    content: Option<NodeReference>,
+}
+
+impl Wrapped {
+    pub fn new() -> Self {
+        Self { content: None }
+    }
+
+    pub fn query(&mut self, metadata: &NodeMetadata, id: ComponentId) -> WidgetQueryResult {
+        match &self.content {
+            Some(node_ref) if node_ref.has_id(id) => {
+                WidgetQueryResult::Initialized(node_ref.clone())
+            }
+            _ => {
+                let node_ref = Node::new_reference(id, &metadata.ui_properties);
+                self.content = Some(node_ref.clone());
+                WidgetQueryResult::Uninitialized(node_ref)
+            }
+        }
+    }
+
+    /// Gives the whole space of the wrapper to the widget, which takes the same place
+    pub fn layout(&mut self, query: &LayoutQuery) -> LayoutResponse {
+        let node = self.content.as_ref().unwrap();
+        let response = node.layout(query);
+        node.set_size(response.size);
+        node.set_transform(Similarity3::identity());
+        response
+    }
+
+    pub fn draw(&self, metadata: &NodeMetadata) -> DrawList {
+        let mut list = DrawList::new();
+        list.list.push(self.content.as_ref().unwrap().draw());
+        list.list_transform = metadata.transform.to_homogeneous();
+        list
+    }
+
+    pub fn interaction_distance(
+        &self,
+        metadata: &NodeMetadata,
+        ray: &Ray,
+    ) -> Vec<(f32, NodeReference)> {
+        let transformation = metadata.transform.inverse();
+        let new_ray = Ray::new(
+            transformation * ray.direction(),
+            transformation * ray.origin(),
+        );
+        self.content
+            .iter()
+            .flat_map(|node| node.interaction_distance(&new_ray, node.clone()))
+            .collect()
+    }
+}
+
+impl Default for Wrapped {
+    fn default() -> Self {
+        Self::new()
+    }
+}